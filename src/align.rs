@@ -0,0 +1,157 @@
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+use super::spatial::SpatialHash;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct IcpOptions {
+    pub max_iterations: usize,
+    // Closest points further apart than this are not paired, so parts of
+    // one mesh the other doesn't cover don't drag the alignment.
+    pub max_distance: f32,
+    // Stop once an iteration lowers the RMS error by no more than this.
+    pub tolerance: f32,
+}
+
+impl Default for IcpOptions {
+    fn default() -> Self {
+        IcpOptions { max_iterations: 50, max_distance: f32::INFINITY, tolerance: 1e-6 }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Alignment {
+    // Rigid transform taking positions of the first mesh onto the second.
+    pub transform: Mat4,
+    // RMS distance between the paired vertices after the transform.
+    pub error: f32,
+    // The number of vertices of the first mesh that were paired.
+    pub pairs: usize,
+    pub iterations: usize,
+}
+
+// Iterative closest point: pairs every vertex of `a` with the nearest vertex
+// of `b`, moves `a` by the rigid transform that best fits those pairs, and
+// repeats until the error stops improving. Only converges to the right
+// alignment if the meshes start roughly aligned, e.g. after placing them by
+// hand or matching their centroids.
+pub fn icp<V: HasPosition, E, F, V2: HasPosition, E2, F2>(a: &Mesh<V, E, F>, b: &Mesh<V2, E2, F2>,
+                                                           options: &IcpOptions) -> Alignment {
+    let source: Vec<Vec3> = a.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+    let target: Vec<Vec3> = b.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+    let mut ret = Alignment { transform: IDENTITY, error: f32::INFINITY, pairs: 0, iterations: 0 };
+    let center = match centroid(&target) {
+        Some(c) => c,
+        None => return ret,
+    };
+    let radius = target.iter().map(|p| distance(*p, center)).fold(0.0, f32::max);
+    let cell_size = if radius > 0.0 { 2.0 * radius / (target.len() as f32).cbrt() } else { 1.0 };
+    let mut index = SpatialHash::new(cell_size);
+    for (i, p) in target.iter().enumerate() {
+        index.insert(VertexIndex::new(i), *p);
+    }
+
+    let mut transform: DMat4 = identity();
+    loop {
+        let moved: Vec<Vec3> = source.iter().map(|p| convert(transform_point(&transform, convert(*p)))).collect();
+        let mut pairs: Vec<(DVec3, DVec3)> = Vec::new();
+        let mut sum_squared = 0.0;
+        for p in moved.iter() {
+            // every target vertex is within this, which bounds the search.
+            let search = options.max_distance.min(distance(*p, center) + radius + cell_size);
+            if let Some(q) = index.nearest(*p, search) {
+                let q = target[q.index()];
+                sum_squared += distance_squared(*p, q) as f64;
+                pairs.push((convert(*p), convert(q)));
+            }
+        }
+        let error = if pairs.is_empty() { f32::INFINITY } else { (sum_squared / pairs.len() as f64).sqrt() as f32 };
+        let converged = ret.error - error <= options.tolerance;
+        ret.error = error;
+        ret.pairs = pairs.len();
+        if converged || pairs.len() < 3 || ret.iterations == options.max_iterations {
+            break;
+        }
+        transform = mat4_mul(&rigid_fit(&pairs), &transform);
+        ret.iterations += 1;
+    }
+    for (i, row) in ret.transform.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = transform[i][j] as f32;
+        }
+    }
+    return ret;
+}
+
+// The rotation and translation taking the first point of each pair closest
+// to the second, in the least squares sense. Uses Horn's method: the best
+// rotation is the quaternion that is the dominant eigenvector of a 4x4
+// matrix built from the pairs' cross-covariance.
+fn rigid_fit(pairs: &[(DVec3, DVec3)]) -> DMat4 {
+    let from: Vec<DVec3> = pairs.iter().map(|(p, _)| *p).collect();
+    let to: Vec<DVec3> = pairs.iter().map(|(_, q)| *q).collect();
+    let (from_center, to_center) = (centroid(&from).unwrap(), centroid(&to).unwrap());
+    let mut s = [[0.0; 3]; 3];
+    for (p, q) in from.iter().zip(to.iter()) {
+        let (p, q) = (sub(*p, from_center), sub(*q, to_center));
+        for i in 0..3 {
+            for j in 0..3 {
+                s[i][j] += p[i] * q[j];
+            }
+        }
+    }
+    let n = [
+        [s[0][0] + s[1][1] + s[2][2], s[1][2] - s[2][1], s[2][0] - s[0][2], s[0][1] - s[1][0]],
+        [s[1][2] - s[2][1], s[0][0] - s[1][1] - s[2][2], s[0][1] + s[1][0], s[2][0] + s[0][2]],
+        [s[2][0] - s[0][2], s[0][1] + s[1][0], -s[0][0] + s[1][1] - s[2][2], s[1][2] + s[2][1]],
+        [s[0][1] - s[1][0], s[2][0] + s[0][2], s[1][2] + s[2][1], -s[0][0] - s[1][1] + s[2][2]],
+    ];
+    let [w, x, y, z] = dominant_eigenvector(n);
+    let mut ret: DMat4 = [
+        [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y), 0.0],
+        [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x), 0.0],
+        [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y), 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+    let t = sub(to_center, transform_point(&ret, from_center));
+    for i in 0..3 {
+        ret[i][3] = t[i];
+    }
+    return ret;
+}
+
+// The unit eigenvector of the largest eigenvalue of a symmetric matrix, by
+// cyclic Jacobi rotations.
+fn dominant_eigenvector(mut a: [[f64; 4]; 4]) -> [f64; 4] {
+    let mut v: DMat4 = identity();
+    let scale: f64 = a.iter().flatten().map(|x| x * x).sum();
+    for _ in 0..50 {
+        let off: f64 = (0..4).flat_map(|p| (p + 1..4).map(move |q| (p, q))).map(|(p, q)| a[p][q] * a[p][q]).sum();
+        if off <= 1e-30 * scale {
+            break;
+        }
+        for p in 0..4 {
+            for q in p + 1..4 {
+                if a[p][q] == 0.0 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut().chain(v.iter_mut()) {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+                let (row_p, row_q) = (a[p], a[q]);
+                for (k, (pk, qk)) in row_p.iter().zip(row_q.iter()).enumerate() {
+                    a[p][k] = c * pk - s * qk;
+                    a[q][k] = s * pk + c * qk;
+                }
+            }
+        }
+    }
+    let largest = (0..4).fold(0, |best, i| if a[i][i] > a[best][best] { i } else { best });
+    return [v[0][largest], v[1][largest], v[2][largest], v[3][largest]];
+}
@@ -0,0 +1,469 @@
+use super::dynamic::Attributes;
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+
+// Implemented by vertex payloads that carry texture coordinates. UVs are
+// per vertex, so a UV seam needs its vertices split.
+pub trait HasUv {
+    fn uv(&self) -> [f32; 2];
+}
+
+// Elements without a UV attribute map to the origin.
+impl HasUv for Attributes {
+    fn uv(&self) -> [f32; 2] {
+        let uv = self.get_vector(Attributes::UV).unwrap_or([0.0; 3]);
+        return [uv[0], uv[1]];
+    }
+}
+
+// A width x height grid of texels, stored row by row starting at v = 0.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Image<T> {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<T>,
+}
+
+impl<T: Clone> Image<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        return Image { width: width, height: height, pixels: vec![value; width * height] };
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        return &self.pixels[y * self.width + x];
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self.pixels[y * self.width + x] = value;
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct BakeOptions {
+    pub width: usize,
+    pub height: usize,
+    // Rays start this far out along the low mesh's normal, so detail that
+    // sticks out of the low mesh is still found; in effect an inflated cage.
+    pub cage_offset: f32,
+    // How far past the low mesh, inwards, rays search.
+    pub max_distance: f32,
+    // Rays per texel for ambient occlusion, or 0 to skip it.
+    pub occlusion_samples: usize,
+    // How far occluders are looked for.
+    pub occlusion_distance: f32,
+}
+
+impl Default for BakeOptions {
+    fn default() -> Self {
+        BakeOptions {
+            width: 256,
+            height: 256,
+            cage_offset: 0.1,
+            max_distance: 0.1,
+            occlusion_samples: 0,
+            occlusion_distance: 1.0,
+        }
+    }
+}
+
+pub struct BakedMaps {
+    // Tangent-space normals encoded to [0, 1] as n * 0.5 + 0.5, with the
+    // flat [0.5, 0.5, 1.0] where nothing was hit.
+    pub normal: Image<Vec3>,
+    // How far the high mesh lies outside the low one along its normal.
+    pub height: Image<f32>,
+    // The fraction of the hemisphere above the high mesh that is open; all
+    // ones unless BakeOptions::occlusion_samples is set.
+    pub occlusion: Image<f32>,
+    // Texels covered by the low mesh's UVs whose ray hit the high mesh.
+    // Only these hold baked values, so the rest can be padded or masked.
+    pub coverage: Image<bool>,
+}
+
+// Bakes the surface detail of `high` into the UV space of `low`. Every
+// texel inside a low mesh triangle casts a ray from the cage back along the
+// low mesh's interpolated normal, and the first hit on the high mesh is
+// recorded relative to the low mesh's tangent frame. Faces of either mesh
+// with more than three vertices are fanned from their first vertex.
+pub fn bake<V, E, F, V2, E2, F2>(high: &Mesh<V, E, F>, low: &Mesh<V2, E2, F2>, options: &BakeOptions) -> BakedMaps
+    where V: HasPosition, V2: HasPosition + HasUv {
+    assert!(options.width > 0 && options.height > 0, "bake: image size must be positive");
+    let (width, height) = (options.width, options.height);
+    let mut ret = BakedMaps {
+        normal: Image::new(width, height, [0.5, 0.5, 1.0]),
+        height: Image::new(width, height, 0.0),
+        occlusion: Image::new(width, height, 1.0),
+        coverage: Image::new(width, height, false),
+    };
+    let target = Triangles::new(high);
+    let bvh = Bvh::new(&target);
+    let source = Triangles::new(low);
+    let uvs: Vec<[f32; 2]> = low.vertex_iter().map(|v| v.data().unwrap().uv()).collect();
+    let pixel = |uv: [f32; 2]| [uv[0] * width as f32, uv[1] * height as f32];
+
+    for (t, corners) in source.triangles.iter().enumerate() {
+        let uv = [uvs[corners[0].index()], uvs[corners[1].index()], uvs[corners[2].index()]];
+        let p = [pixel(uv[0]), pixel(uv[1]), pixel(uv[2])];
+        let area = edge_function(p[0], p[1], p[2]);
+        if area == 0.0 {
+            continue;
+        }
+        let (tangent, bitangent) = uv_frame(source.positions(t), uv);
+        let lo_x = p.iter().map(|q| q[0]).fold(f32::INFINITY, f32::min).floor().max(0.0) as usize;
+        let hi_x = p.iter().map(|q| q[0]).fold(f32::NEG_INFINITY, f32::max).ceil().min(width as f32) as usize;
+        let lo_y = p.iter().map(|q| q[1]).fold(f32::INFINITY, f32::min).floor().max(0.0) as usize;
+        let hi_y = p.iter().map(|q| q[1]).fold(f32::NEG_INFINITY, f32::max).ceil().min(height as f32) as usize;
+        for y in lo_y..hi_y {
+            for x in lo_x..hi_x {
+                let center = [x as f32 + 0.5, y as f32 + 0.5];
+                let w = [edge_function(p[1], p[2], center) / area, edge_function(p[2], p[0], center) / area,
+                         edge_function(p[0], p[1], center) / area];
+                if w.iter().any(|w| *w < 0.0) {
+                    continue;
+                }
+                let position = source.interpolate_position(t, w);
+                let normal = normalize(source.interpolate_normal(t, w));
+                let origin = add(position, scale(normal, options.cage_offset));
+                let hit = match bvh.intersect(&target, origin, scale(normal, -1.0),
+                                              options.cage_offset + options.max_distance) {
+                    Some(hit) => hit,
+                    None => continue,
+                };
+                let hit_normal = normalize(target.interpolate_normal(hit.triangle, hit.weights));
+
+                // Gram-Schmidt the tangent against the smooth normal, keeping
+                // the UV frame's handedness.
+                let t_axis = normalize(sub(tangent, scale(normal, dot(normal, tangent))));
+                let mut b_axis = cross(normal, t_axis);
+                if dot(b_axis, bitangent) < 0.0 {
+                    b_axis = scale(b_axis, -1.0);
+                }
+                let local = [dot(hit_normal, t_axis), dot(hit_normal, b_axis), dot(hit_normal, normal)];
+                ret.normal.set(x, y, [local[0] * 0.5 + 0.5, local[1] * 0.5 + 0.5, local[2] * 0.5 + 0.5]);
+                ret.height.set(x, y, options.cage_offset - hit.distance);
+                ret.coverage.set(x, y, true);
+                if options.occlusion_samples > 0 {
+                    let point = add(origin, scale(normal, -hit.distance));
+                    ret.occlusion.set(x, y, openness(&bvh, &target, point, hit_normal, options));
+                }
+            }
+        }
+    }
+    return ret;
+}
+
+// The directions of increasing u and v across a triangle.
+fn uv_frame(positions: [Vec3; 3], uv: [[f32; 2]; 3]) -> (Vec3, Vec3) {
+    let (e1, e2) = (sub(positions[1], positions[0]), sub(positions[2], positions[0]));
+    let (du1, dv1) = (uv[1][0] - uv[0][0], uv[1][1] - uv[0][1]);
+    let (du2, dv2) = (uv[2][0] - uv[0][0], uv[2][1] - uv[0][1]);
+    let r = 1.0 / (du1 * dv2 - du2 * dv1);
+    let tangent = scale(sub(scale(e1, dv2), scale(e2, dv1)), r);
+    let bitangent = scale(sub(scale(e2, du1), scale(e1, du2)), r);
+    return (tangent, bitangent);
+}
+
+// The unoccluded fraction of cosine-weighted rays over the hemisphere
+// around `normal`.
+fn openness(bvh: &Bvh, triangles: &Triangles, point: Vec3, normal: Vec3, options: &BakeOptions) -> f32 {
+    // off the surface, so rays don't hit the triangle they start on.
+    let origin = add(point, scale(normal, options.occlusion_distance * 1e-4));
+    let n = options.occlusion_samples;
+    let open = hemisphere(normal, n)
+        .filter(|d| bvh.intersect(triangles, origin, *d, options.occlusion_distance).is_none())
+        .count();
+    return open as f32 / n as f32;
+}
+
+// `n` cosine-weighted directions over the hemisphere around the unit
+// `normal`, spread in a Fibonacci spiral so bakes are repeatable.
+fn hemisphere(normal: Vec3, n: usize) -> impl Iterator<Item = Vec3> {
+    let helper = if normal[0].abs() < 0.9 { [1.0, 0.0, 0.0] } else { [0.0, 1.0, 0.0] };
+    let u = normalize(cross(normal, helper));
+    let v = cross(normal, u);
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    return (0..n).map(move |i| {
+        let r = ((i as f32 + 0.5) / n as f32).sqrt();
+        let phi = i as f32 * golden_angle;
+        return add(add(scale(u, r * phi.cos()), scale(v, r * phi.sin())),
+                   scale(normal, (1.0 - r * r).max(0.0).sqrt()));
+    });
+}
+
+/*
+ * Per vertex
+ */
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct VertexBakeOptions {
+    // Rays per vertex for each of occlusion and thickness.
+    pub samples: usize,
+    // How far rays search; also the thickness where nothing is hit.
+    pub distance: f32,
+    // Worker threads sharing the vertices; 0 and 1 both bake on the
+    // calling thread.
+    pub threads: usize,
+}
+
+impl Default for VertexBakeOptions {
+    fn default() -> Self {
+        VertexBakeOptions {
+            samples: 64,
+            distance: 1.0,
+            threads: 1,
+        }
+    }
+}
+
+// Baked values by VertexIndex.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VertexBake {
+    // The open fraction of the hemisphere outside the surface, as in
+    // BakedMaps::occlusion.
+    pub occlusion: Vec<f32>,
+    // The mean distance to the surface's far side, casting into the
+    // hemisphere behind the vertex, capped at VertexBakeOptions::distance.
+    pub thickness: Vec<f32>,
+}
+
+impl VertexBake {
+    // Stores the values as the Attributes::OCCLUSION and
+    // Attributes::THICKNESS float attributes, for viewers to shade with.
+    pub fn store<E, F>(&self, mesh: &mut Mesh<Attributes, E, F>) {
+        assert_eq!(self.occlusion.len(), mesh.vertex_iter().len(), "VertexBake::store: baked for another mesh");
+        for i in 0..self.occlusion.len() {
+            let attributes = mesh.vertex_data_mut(VertexIndex::new(i)).unwrap();
+            attributes.set(Attributes::OCCLUSION, self.occlusion[i] as f64);
+            attributes.set(Attributes::THICKNESS, self.thickness[i] as f64);
+        }
+    }
+}
+
+// Bakes ambient occlusion and thickness at every vertex of `mesh`, along
+// its smooth vertex normal. Isolated vertices have no normal and come out
+// fully open and `distance` thick. The result doesn't depend on the
+// number of threads.
+pub fn bake_vertices<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, options: &VertexBakeOptions) -> VertexBake {
+    assert!(options.samples > 0, "bake_vertices: samples must be positive");
+    let triangles = Triangles::new(mesh);
+    let bvh = Bvh::new(&triangles);
+    let count = triangles.vertex_positions.len();
+    let mut values = vec![(1.0, options.distance); count];
+    let bake_range = |start: usize, out: &mut [(f32, f32)]| {
+        for (i, value) in out.iter_mut().enumerate() {
+            *value = bake_vertex(&bvh, &triangles, VertexIndex::new(start + i), options);
+        }
+    };
+    let threads = options.threads.max(1).min(count.max(1));
+    if threads == 1 {
+        bake_range(0, &mut values);
+    } else {
+        let chunk = count.div_ceil(threads);
+        std::thread::scope(|scope| {
+            for (c, out) in values.chunks_mut(chunk).enumerate() {
+                let bake_range = &bake_range;
+                scope.spawn(move || bake_range(c * chunk, out));
+            }
+        });
+    }
+    return VertexBake {
+        occlusion: values.iter().map(|v| v.0).collect(),
+        thickness: values.iter().map(|v| v.1).collect(),
+    };
+}
+
+// The occlusion and thickness at one vertex.
+fn bake_vertex(bvh: &Bvh, triangles: &Triangles, v: VertexIndex, options: &VertexBakeOptions) -> (f32, f32) {
+    let normal = normalize(triangles.vertex_normals[v.index()]);
+    if normal == [0.0; 3] {
+        return (1.0, options.distance);
+    }
+    let point = triangles.vertex_positions[v.index()];
+    // off the surface on either side, as in openness().
+    let offset = scale(normal, options.distance * 1e-4);
+    let (outside, inside) = (add(point, offset), sub(point, offset));
+    let n = options.samples;
+    let open = hemisphere(normal, n)
+        .filter(|d| bvh.intersect(triangles, outside, *d, options.distance).is_none())
+        .count();
+    let depth: f32 = hemisphere(scale(normal, -1.0), n)
+        .map(|d| bvh.intersect(triangles, inside, d, options.distance).map_or(options.distance, |hit| hit.distance))
+        .sum();
+    return (open as f32 / n as f32, depth / n as f32);
+}
+
+// A mesh's faces as triangles, with smooth vertex normals. Shared with
+// support and selection, which cast rays with the same Bvh.
+pub(crate) struct Triangles {
+    triangles: Vec<[VertexIndex; 3]>,
+    vertex_positions: Vec<Vec3>,
+    vertex_normals: Vec<Vec3>,
+}
+
+impl Triangles {
+    pub(crate) fn new<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>) -> Self {
+        let positions: Vec<Vec3> = mesh.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+        let mut normals = vec![[0.0; 3]; positions.len()];
+        let mut triangles = Vec::new();
+        for f in mesh.face_iter() {
+            let vertices: Vec<VertexIndex> = f.vertex_iter().map(|v| v.index()).collect();
+            let points: Vec<Vec3> = vertices.iter().map(|v| positions[v.index()]).collect();
+            // area weighted.
+            let normal = newell_normal(&points);
+            for v in vertices.iter() {
+                normals[v.index()] = add(normals[v.index()], normal);
+            }
+            for j in 1..vertices.len() - 1 {
+                triangles.push([vertices[0], vertices[j], vertices[j + 1]]);
+            }
+        }
+        return Triangles { triangles: triangles, vertex_positions: positions, vertex_normals: normals };
+    }
+
+    fn positions(&self, t: usize) -> [Vec3; 3] {
+        let [a, b, c] = self.triangles[t];
+        return [self.vertex_positions[a.index()], self.vertex_positions[b.index()], self.vertex_positions[c.index()]];
+    }
+
+    fn interpolate_position(&self, t: usize, w: [f32; 3]) -> Vec3 {
+        let p = self.positions(t);
+        return add(add(scale(p[0], w[0]), scale(p[1], w[1])), scale(p[2], w[2]));
+    }
+
+    fn interpolate_normal(&self, t: usize, w: [f32; 3]) -> Vec3 {
+        let [a, b, c] = self.triangles[t];
+        let n = [self.vertex_normals[a.index()], self.vertex_normals[b.index()], self.vertex_normals[c.index()]];
+        return add(add(scale(n[0], w[0]), scale(n[1], w[1])), scale(n[2], w[2]));
+    }
+}
+
+pub(crate) struct Hit {
+    triangle: usize,
+    distance: f32,
+    weights: [f32; 3],
+}
+
+// A bounding volume hierarchy over triangles, split at the median along
+// the longest axis of the centroids.
+pub(crate) struct Bvh {
+    nodes: Vec<BvhNode>,
+    order: Vec<usize>,
+}
+
+struct BvhNode {
+    min: Vec3,
+    max: Vec3,
+    // children for an inner node, or a range of `order` for a leaf.
+    left: usize,
+    right: usize,
+    leaf: bool,
+}
+
+impl Bvh {
+    const LEAF_SIZE: usize = 4;
+
+    pub(crate) fn new(triangles: &Triangles) -> Self {
+        let mut ret = Bvh { nodes: Vec::new(), order: (0..triangles.triangles.len()).collect() };
+        if !ret.order.is_empty() {
+            ret.build(triangles, 0, ret.order.len());
+        }
+        return ret;
+    }
+
+    fn build(&mut self, triangles: &Triangles, start: usize, end: usize) -> usize {
+        let (min, max) = bounds(self.order[start..end].iter().flat_map(|t| triangles.positions(*t))).unwrap();
+        let node = self.nodes.len();
+        self.nodes.push(BvhNode { min: min, max: max, left: start, right: end, leaf: true });
+        if end - start <= Bvh::LEAF_SIZE {
+            return node;
+        }
+        let center = |t: usize| centroid(&triangles.positions(t)).unwrap();
+        let (lo, hi) = bounds(self.order[start..end].iter().map(|t| center(*t))).unwrap();
+        let extent = sub(hi, lo);
+        let axis = if extent[0] >= extent[1] && extent[0] >= extent[2] { 0 } else if extent[1] >= extent[2] { 1 } else { 2 };
+        let middle = (start + end) / 2;
+        self.order[start..end].select_nth_unstable_by(middle - start, |a, b| {
+            return center(*a)[axis].total_cmp(&center(*b)[axis]);
+        });
+        let left = self.build(triangles, start, middle);
+        let right = self.build(triangles, middle, end);
+        self.nodes[node] = BvhNode { min: min, max: max, left: left, right: right, leaf: false };
+        return node;
+    }
+
+    // The nearest hit along the ray within `max_distance`.
+    pub(crate) fn intersect(&self, triangles: &Triangles, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<Hit> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let mut best: Option<Hit> = None;
+        let mut limit = max_distance;
+        let mut stack = vec![0];
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if !slab_test(node.min, node.max, origin, direction, limit) {
+                continue;
+            }
+            if !node.leaf {
+                stack.push(node.left);
+                stack.push(node.right);
+                continue;
+            }
+            for t in self.order[node.left..node.right].iter() {
+                let [a, b, c] = triangles.positions(*t);
+                if let Some((distance, weights)) = ray_triangle(origin, direction, a, b, c) {
+                    if distance <= limit {
+                        limit = distance;
+                        best = Some(Hit { triangle: *t, distance: distance, weights: weights });
+                    }
+                }
+            }
+        }
+        return best;
+    }
+}
+
+fn slab_test(min: Vec3, max: Vec3, origin: Vec3, direction: Vec3, limit: f32) -> bool {
+    let (mut near, mut far) = (0.0f32, limit);
+    for i in 0..3 {
+        if direction[i] == 0.0 {
+            if origin[i] < min[i] || origin[i] > max[i] {
+                return false;
+            }
+            continue;
+        }
+        let t1 = (min[i] - origin[i]) / direction[i];
+        let t2 = (max[i] - origin[i]) / direction[i];
+        near = near.max(t1.min(t2));
+        far = far.min(t1.max(t2));
+    }
+    return near <= far;
+}
+
+// Möller-Trumbore: the distance along the ray and the barycentric weights
+// of the hit, from either side of the triangle.
+fn ray_triangle(origin: Vec3, direction: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Option<(f32, [f32; 3])> {
+    let (e1, e2) = (sub(b, a), sub(c, a));
+    let p = cross(direction, e2);
+    let determinant = dot(e1, p);
+    if determinant == 0.0 {
+        return None;
+    }
+    let inverse = 1.0 / determinant;
+    let s = sub(origin, a);
+    let u = dot(s, p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = cross(s, e1);
+    let v = dot(direction, q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = dot(e2, q) * inverse;
+    if t < 0.0 {
+        return None;
+    }
+    return Some((t, [1.0 - u - v, u, v]));
+}
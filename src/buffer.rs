@@ -0,0 +1,61 @@
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+
+// Two copies of a per-vertex attribute for explicit time stepping: a step
+// reads the front buffer (the last completed state) and writes the back,
+// then swap() publishes the result. Nothing is allocated per step, and the
+// mesh itself is only touched by write_positions().
+#[derive(Clone, Debug)]
+pub struct DoubleBuffer<T> {
+    front: Vec<T>,
+    back: Vec<T>,
+}
+
+impl<T: Clone> DoubleBuffer<T> {
+    // Both buffers start as `initial`.
+    pub fn new(initial: Vec<T>) -> Self {
+        return DoubleBuffer { back: initial.clone(), front: initial };
+    }
+
+    pub fn len(&self) -> usize {
+        return self.front.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.front.is_empty();
+    }
+
+    pub fn front(&self) -> &[T] {
+        return &self.front;
+    }
+
+    pub fn back_mut(&mut self) -> &mut [T] {
+        return &mut self.back;
+    }
+
+    // The front to read from and the back to write to, at the same time.
+    pub fn split(&mut self) -> (&[T], &mut [T]) {
+        return (&self.front, &mut self.back);
+    }
+
+    // Makes the back buffer the front. The old front becomes the back and
+    // still holds the previous state, e.g. for Verlet integration.
+    pub fn swap(&mut self) {
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+}
+
+impl DoubleBuffer<Vec3> {
+    pub fn from_positions<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>) -> Self {
+        return DoubleBuffer::new(mesh.vertex_data_iter().map(|(_, v)| v.position()).collect());
+    }
+
+    // Copies the front buffer into the mesh's vertex positions.
+    pub fn write_positions<V: HasPosition, E, F>(&self, mesh: &mut Mesh<V, E, F>) {
+        assert_eq!(self.front.len(), mesh.vertex_iter().len(), "buffer and mesh vertex counts differ");
+        for (i, position) in self.front.iter().enumerate() {
+            mesh.vertex_data_mut(VertexIndex::new(i)).unwrap().set_position(*position);
+        }
+    }
+}
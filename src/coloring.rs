@@ -0,0 +1,65 @@
+use super::mesh::Mesh;
+use super::selection::Selectable;
+
+// Splits the elements into batches that can be processed in parallel: no two
+// elements of a batch are within `distance` steps of each other. Distance 1
+// suits operators that write an element and read its neighbours, such as
+// in-place (Gauss-Seidel) Laplacian smoothing; operators that also write
+// the neighbours, such as edge flips, need 2.
+//
+// The coloring is greedy, most-connected elements first, so it is not
+// minimal but usually close. Batches are in ascending index order, and the
+// result only depends on the connectivity.
+pub fn color<I: Selectable, V, E, F>(mesh: &Mesh<V, E, F>, distance: usize) -> Vec<Vec<I>> {
+    assert!(distance > 0, "coloring distance must be at least 1");
+    let adjacency = I::adjacency(mesh);
+    let conflicts: Vec<Vec<usize>> = (0..adjacency.len())
+        .map(|i| within(&adjacency, i, distance))
+        .collect();
+
+    let mut order: Vec<usize> = (0..adjacency.len()).collect();
+    order.sort_by(|a, b| conflicts[*b].len().cmp(&conflicts[*a].len()).then(a.cmp(b)));
+
+    let mut colors: Vec<Option<usize>> = vec![None; adjacency.len()];
+    let mut used: Vec<bool> = Vec::new();
+    let mut ret: Vec<Vec<I>> = Vec::new();
+    for i in order {
+        used.clear();
+        used.resize(ret.len() + 1, false);
+        for j in conflicts[i].iter() {
+            if let Some(c) = colors[*j] {
+                used[c] = true;
+            }
+        }
+        let c = used.iter().position(|u| !u).unwrap();
+        if c == ret.len() {
+            ret.push(Vec::new());
+        }
+        colors[i] = Some(c);
+        ret[c].push(I::new(i));
+    }
+    for batch in ret.iter_mut() {
+        batch.sort();
+    }
+    return ret;
+}
+
+// The elements within `distance` steps of `start`, excluding itself.
+fn within<I: Selectable>(adjacency: &[Vec<I>], start: usize, distance: usize) -> Vec<usize> {
+    let mut ret = vec![start];
+    let mut frontier = vec![start];
+    for _ in 0..distance {
+        let mut next = Vec::new();
+        for i in frontier {
+            for j in adjacency[i].iter() {
+                if !ret.contains(&j.index()) {
+                    ret.push(j.index());
+                    next.push(j.index());
+                }
+            }
+        }
+        frontier = next;
+    }
+    ret.remove(0);
+    return ret;
+}
@@ -0,0 +1,74 @@
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+
+pub type Line = [Vec3; 2];
+
+// Line lists for visualizing a mesh, one list per category so each can be
+// drawn with a single call.
+#[derive(Clone, Default)]
+pub struct DebugLines {
+    pub wireframe: Vec<Line>,
+    pub boundary: Vec<Line>,
+    // Normals drawn `normal_length` long, out of each vertex and each face's
+    // centroid. Vertex normals are area weighted over the faces around the
+    // vertex; vertices without faces, and degenerate faces, get none.
+    pub vertex_normals: Vec<Line>,
+    pub face_normals: Vec<Line>,
+}
+
+impl DebugLines {
+    pub fn new<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, normal_length: f32) -> Self {
+        let mut ret = DebugLines::default();
+        for e in mesh.edge_iter() {
+            let vertices = e.vertices();
+            let line = [
+                mesh.vertex(vertices[0]).data().unwrap().position(),
+                mesh.vertex(vertices[1]).data().unwrap().position(),
+            ];
+            ret.wireframe.push(line);
+            // edges with less than two faces are on the border (or are wire edges).
+            if e.faces().len() < 2 {
+                ret.boundary.push(line);
+            }
+        }
+
+        let positions: Vec<Vec3> = mesh.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+        let mut normals = vec![[0.0; 3]; positions.len()];
+        for f in mesh.face_iter() {
+            let vertices: Vec<VertexIndex> = f.vertex_iter().map(|v| v.index()).collect();
+            let points: Vec<Vec3> = vertices.iter().map(|v| positions[v.index()]).collect();
+            let normal = newell_normal(&points);
+            for v in vertices.iter() {
+                normals[v.index()] = add(normals[v.index()], normal);
+            }
+            if let Some(line) = normal_line(centroid(&points).unwrap(), normal, normal_length) {
+                ret.face_normals.push(line);
+            }
+        }
+        for (p, normal) in positions.iter().zip(normals.iter()) {
+            if let Some(line) = normal_line(*p, *normal, normal_length) {
+                ret.vertex_normals.push(line);
+            }
+        }
+        return ret;
+    }
+
+    // All lines flattened to a vertex buffer, two positions per line.
+    pub fn flatten(lines: &[Line]) -> Vec<f32> {
+        let mut ret = Vec::with_capacity(lines.len() * 6);
+        for line in lines {
+            ret.extend_from_slice(&line[0]);
+            ret.extend_from_slice(&line[1]);
+        }
+        return ret;
+    }
+}
+
+// A line `length` long from `from` along `normal`, unless the normal is zero.
+fn normal_line(from: Vec3, normal: Vec3, length: f32) -> Option<Line> {
+    if normal == [0.0; 3] {
+        return None;
+    }
+    return Some([from, add(from, scale(normalize(normal), length))]);
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use super::geometry::*;
+use super::mesh::Mesh;
+
+// A dynamically typed attribute value.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Vector(Vec3),
+    Text(String),
+}
+
+impl From<bool> for Value {
+    fn from(x: bool) -> Value {
+        return Value::Bool(x);
+    }
+}
+
+impl From<i64> for Value {
+    fn from(x: i64) -> Value {
+        return Value::Int(x);
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Value {
+        return Value::Float(x);
+    }
+}
+
+impl From<Vec3> for Value {
+    fn from(x: Vec3) -> Value {
+        return Value::Vector(x);
+    }
+}
+
+impl From<&str> for Value {
+    fn from(x: &str) -> Value {
+        return Value::Text(x.to_string());
+    }
+}
+
+// Named attributes of one element. Kept sorted by name so iteration (and
+// anything exported from it) is deterministic.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Attributes {
+    values: BTreeMap<String, Value>,
+}
+
+impl Attributes {
+    // The attribute HasPosition reads and writes.
+    pub const POSITION: &'static str = "position";
+    // The attribute bake::HasUv reads, as a vector whose z is ignored.
+    pub const UV: &'static str = "uv";
+    // The float attributes bake::VertexBake::store() writes.
+    pub const OCCLUSION: &'static str = "occlusion";
+    pub const THICKNESS: &'static str = "thickness";
+
+    pub fn new() -> Self {
+        return Attributes::default();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.values.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.values.is_empty();
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        return self.values.get(name);
+    }
+
+    // Returns the previous value, if any.
+    pub fn set<T: Into<Value>>(&mut self, name: &str, value: T) -> Option<Value> {
+        return self.values.insert(name.to_string(), value.into());
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Value> {
+        return self.values.remove(name);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        return self.values.iter().map(|(k, v)| (k.as_str(), v));
+    }
+
+    // Typed getters. None if the attribute is missing or has another type,
+    // except that get_float also accepts an Int.
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.get(name) {
+            Some(Value::Bool(x)) => Some(*x),
+            _ => None,
+        }
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        match self.get(name) {
+            Some(Value::Int(x)) => Some(*x),
+            _ => None,
+        }
+    }
+
+    pub fn get_float(&self, name: &str) -> Option<f64> {
+        match self.get(name) {
+            Some(Value::Float(x)) => Some(*x),
+            Some(Value::Int(x)) => Some(*x as f64),
+            _ => None,
+        }
+    }
+
+    pub fn get_vector(&self, name: &str) -> Option<Vec3> {
+        match self.get(name) {
+            Some(Value::Vector(x)) => Some(*x),
+            _ => None,
+        }
+    }
+
+    pub fn get_text(&self, name: &str) -> Option<&str> {
+        match self.get(name) {
+            Some(Value::Text(x)) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+// Elements without a position attribute sit at the origin.
+impl HasPosition for Attributes {
+    fn position(&self) -> Vec3 {
+        return self.get_vector(Attributes::POSITION).unwrap_or([0.0; 3]);
+    }
+
+    fn set_position(&mut self, position: Vec3) {
+        self.set(Attributes::POSITION, position);
+    }
+}
+
+// A mesh whose payloads are attribute bags instead of compile-time types,
+// for scripting bindings. Since Attributes implements HasPosition, the
+// geometric operators work on it directly.
+pub type DynMesh = Mesh<Attributes, Attributes, Attributes>;
+
+impl DynMesh {
+    // Converts a typed mesh, keeping each vertex's position as the
+    // "position" attribute and leaving the other attributes empty.
+    pub fn from_positions<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>) -> DynMesh {
+        return mesh.map_data(|v| {
+            let mut ret = Attributes::new();
+            ret.set_position(v.position());
+            return ret;
+        }, |_| Attributes::new(), |_| Attributes::new());
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasherDefault, Hasher};
+
+use super::index::*;
+use super::mesh::{EdgeRef, Mesh};
+
+// An undirected edge as an unordered vertex pair. The constructor puts the
+// smaller index first, so (a, b) and (b, a) give the same key.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct EdgeKey(VertexIndex, VertexIndex);
+
+impl EdgeKey {
+    pub fn new(a: VertexIndex, b: VertexIndex) -> Self {
+        if a <= b {
+            return EdgeKey(a, b);
+        }
+        return EdgeKey(b, a);
+    }
+
+    // Smaller index first.
+    pub fn vertices(&self) -> (VertexIndex, VertexIndex) {
+        return (self.0, self.1);
+    }
+
+    pub fn contains(&self, v: VertexIndex) -> bool {
+        return self.0 == v || self.1 == v;
+    }
+
+    // The other end of the edge from `v`, which must be one of its ends.
+    pub fn opposite(&self, v: VertexIndex) -> VertexIndex {
+        assert!(self.contains(v), "vertex {} is not on edge {:?}", v, self);
+        if self.0 == v {
+            return self.1;
+        }
+        return self.0;
+    }
+}
+
+impl<'a, V, E, F> From<EdgeRef<'a, V, E, F>> for EdgeKey {
+    fn from(e: EdgeRef<'a, V, E, F>) -> EdgeKey {
+        let vertices = e.vertices();
+        return EdgeKey::new(vertices[0], vertices[1]);
+    }
+}
+
+// Multiplicative hashing over the two indices. The default SipHash is built
+// to resist adversarial keys, which vertex indices are not, and it is
+// several times slower on small integer keys.
+#[derive(Copy, Clone, Default)]
+pub struct EdgeKeyHasher {
+    hash: u64,
+}
+
+impl Hasher for EdgeKeyHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.add(*b as u64);
+        }
+    }
+
+    fn write_u32(&mut self, x: u32) {
+        self.add(x as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        return self.hash;
+    }
+}
+
+impl EdgeKeyHasher {
+    fn add(&mut self, x: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ x).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+}
+
+pub type EdgeMap<T> = HashMap<EdgeKey, T, BuildHasherDefault<EdgeKeyHasher>>;
+pub type EdgeSet = HashSet<EdgeKey, BuildHasherDefault<EdgeKeyHasher>>;
+
+impl<V, E, F> Mesh<V, E, F> {
+    // Every edge keyed by its endpoints. If two edges join the same pair of
+    // vertices, the later one wins.
+    pub fn edge_map(&self) -> EdgeMap<EdgeIndex> {
+        let mut ret = EdgeMap::default();
+        for e in self.edge_iter() {
+            ret.insert(EdgeKey::from(e), e.index());
+        }
+        return ret;
+    }
+}
@@ -0,0 +1,260 @@
+use num::Float;
+
+// The floating-point types the geometry helpers work in. Everything below is
+// generic over it, so f64 users get the same code paths as f32 ones; the
+// Vec3/Mat4 aliases name the f32 versions the mesh types use.
+pub trait Scalar: Float + Default + std::fmt::Debug + 'static {
+    fn from_f64(x: f64) -> Self;
+}
+
+impl Scalar for f32 {
+    fn from_f64(x: f64) -> Self {
+        return x as f32;
+    }
+}
+
+impl Scalar for f64 {
+    fn from_f64(x: f64) -> Self {
+        return x;
+    }
+}
+
+pub type Vector3<S> = [S; 3];
+pub type Vec3 = Vector3<f32>;
+pub type DVec3 = Vector3<f64>;
+
+// Implemented by vertex payloads that carry a position in space.
+pub trait HasPosition {
+    fn position(&self) -> Vec3;
+    fn set_position(&mut self, position: Vec3);
+}
+
+impl HasPosition for Vec3 {
+    fn position(&self) -> Vec3 {
+        return *self;
+    }
+
+    fn set_position(&mut self, position: Vec3) {
+        *self = position;
+    }
+}
+
+// Precision conversion, e.g. to run a computation in f64 on f32 positions.
+pub fn convert<S: Scalar, T: Scalar>(a: Vector3<S>) -> Vector3<T> {
+    return [T::from(a[0]).unwrap(), T::from(a[1]).unwrap(), T::from(a[2]).unwrap()];
+}
+
+pub fn add<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> Vector3<S> {
+    return [a[0] + b[0], a[1] + b[1], a[2] + b[2]];
+}
+
+pub fn sub<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> Vector3<S> {
+    return [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+}
+
+pub fn scale<S: Scalar>(a: Vector3<S>, s: S) -> Vector3<S> {
+    return [a[0] * s, a[1] * s, a[2] * s];
+}
+
+pub fn dot<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> S {
+    return a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+}
+
+pub fn cross<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> Vector3<S> {
+    return [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ];
+}
+
+pub fn length<S: Scalar>(a: Vector3<S>) -> S {
+    return dot(a, a).sqrt();
+}
+
+pub fn distance_squared<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> S {
+    let d = sub(a, b);
+    return dot(d, d);
+}
+
+pub fn distance<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> S {
+    return distance_squared(a, b).sqrt();
+}
+
+// Returns the zero vector unchanged.
+pub fn normalize<S: Scalar>(a: Vector3<S>) -> Vector3<S> {
+    let len = length(a);
+    if len > S::zero() {
+        return scale(a, S::one() / len);
+    }
+    return a;
+}
+
+// Component-wise (min, max) of the points, or None if there are none.
+pub fn bounds<S: Scalar, I: IntoIterator<Item = Vector3<S>>>(points: I)
+    -> Option<(Vector3<S>, Vector3<S>)> {
+    let mut ret: Option<(Vector3<S>, Vector3<S>)> = None;
+    for p in points {
+        ret = match ret {
+            Some((min, max)) => Some((
+                [min[0].min(p[0]), min[1].min(p[1]), min[2].min(p[2])],
+                [max[0].max(p[0]), max[1].max(p[1]), max[2].max(p[2])],
+            )),
+            None => Some((p, p)),
+        };
+    }
+    return ret;
+}
+
+// None for an empty slice.
+pub fn centroid<S: Scalar>(points: &[Vector3<S>]) -> Option<Vector3<S>> {
+    if points.is_empty() {
+        return None;
+    }
+    let sum = points.iter().fold([S::zero(); 3], |sum, p| add(sum, *p));
+    return Some(scale(sum, S::one() / S::from_f64(points.len() as f64)));
+}
+
+// The closest point to `p` on segment ab, and its parameter t along it, so
+// the point is a + t (b - a).
+pub fn closest_point_on_segment<S: Scalar>(p: Vector3<S>, a: Vector3<S>, b: Vector3<S>) -> (Vector3<S>, S) {
+    let ab = sub(b, a);
+    let length_squared = dot(ab, ab);
+    if length_squared == S::zero() {
+        return (a, S::zero());
+    }
+    let t = (dot(sub(p, a), ab) / length_squared).max(S::zero()).min(S::one());
+    return (add(a, scale(ab, t)), t);
+}
+
+// The closest point to `p` on triangle abc, and its barycentric weights.
+// Finds the Voronoi region of p as in Ericson's Real-Time Collision
+// Detection; degenerate triangles fall back to their closest edge.
+pub fn closest_point_on_triangle<S: Scalar>(p: Vector3<S>, a: Vector3<S>, b: Vector3<S>, c: Vector3<S>)
+    -> (Vector3<S>, [S; 3]) {
+    let (zero, one) = (S::zero(), S::one());
+    let ab = sub(b, a);
+    let ac = sub(c, a);
+    let ap = sub(p, a);
+    let (d1, d2) = (dot(ab, ap), dot(ac, ap));
+    if d1 <= zero && d2 <= zero {
+        return (a, [one, zero, zero]);
+    }
+    let bp = sub(p, b);
+    let (d3, d4) = (dot(ab, bp), dot(ac, bp));
+    if d3 >= zero && d4 <= d3 {
+        return (b, [zero, one, zero]);
+    }
+    let cp = sub(p, c);
+    let (d5, d6) = (dot(ab, cp), dot(ac, cp));
+    if d6 >= zero && d5 <= d6 {
+        return (c, [zero, zero, one]);
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= zero && d1 >= zero && d3 <= zero && d1 - d3 > zero {
+        let v = d1 / (d1 - d3);
+        return (add(a, scale(ab, v)), [one - v, v, zero]);
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= zero && d2 >= zero && d6 <= zero && d2 - d6 > zero {
+        let w = d2 / (d2 - d6);
+        return (add(a, scale(ac, w)), [one - w, zero, w]);
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= zero && d4 - d3 >= zero && d5 - d6 >= zero && (d4 - d3) + (d5 - d6) > zero {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (add(b, scale(sub(c, b), w)), [zero, one - w, w]);
+    }
+    let sum = va + vb + vc;
+    if sum > zero {
+        let (v, w) = (vb / sum, vc / sum);
+        return (add(a, add(scale(ab, v), scale(ac, w))), [one - v - w, v, w]);
+    }
+    let (q_ab, t_ab) = closest_point_on_segment(p, a, b);
+    let (q_bc, t_bc) = closest_point_on_segment(p, b, c);
+    let (q_ca, t_ca) = closest_point_on_segment(p, c, a);
+    let (d_ab, d_bc, d_ca) = (distance_squared(p, q_ab), distance_squared(p, q_bc), distance_squared(p, q_ca));
+    if d_ab <= d_bc && d_ab <= d_ca {
+        return (q_ab, [one - t_ab, t_ab, zero]);
+    }
+    if d_bc <= d_ca {
+        return (q_bc, [zero, one - t_bc, t_bc]);
+    }
+    return (q_ca, [t_ca, zero, one - t_ca]);
+}
+
+// Twice the signed area of the 2D triangle abc, positive when it turns
+// counter-clockwise. Not exact; see predicates::orient2d for that.
+pub fn edge_function<S: Scalar>(a: [S; 2], b: [S; 2], c: [S; 2]) -> S {
+    return (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+}
+
+// Unnormalized normal of a closed polygon by Newell's method, which is
+// stable for non-convex polygons. Its length is twice the projected area,
+// so zero for degenerate input.
+pub fn newell_normal<S: Scalar>(points: &[Vector3<S>]) -> Vector3<S> {
+    let mut ret = [S::zero(); 3];
+    for (i, p) in points.iter().enumerate() {
+        let q = points[(i + 1) % points.len()];
+        ret[0] = ret[0] + (p[1] - q[1]) * (p[2] + q[2]);
+        ret[1] = ret[1] + (p[2] - q[2]) * (p[0] + q[0]);
+        ret[2] = ret[2] + (p[0] - q[0]) * (p[1] + q[1]);
+    }
+    return ret;
+}
+
+// Row-major 4x4 matrix; points are treated as column vectors.
+pub type Matrix4<S> = [[S; 4]; 4];
+pub type Mat4 = Matrix4<f32>;
+pub type DMat4 = Matrix4<f64>;
+
+pub const IDENTITY: Mat4 = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+pub fn identity<S: Scalar>() -> Matrix4<S> {
+    let mut m = [[S::zero(); 4]; 4];
+    for (i, row) in m.iter_mut().enumerate() {
+        row[i] = S::one();
+    }
+    return m;
+}
+
+pub fn translation<S: Scalar>(t: Vector3<S>) -> Matrix4<S> {
+    let mut m = identity();
+    m[0][3] = t[0];
+    m[1][3] = t[1];
+    m[2][3] = t[2];
+    return m;
+}
+
+pub fn mat4_mul<S: Scalar>(a: &Matrix4<S>, b: &Matrix4<S>) -> Matrix4<S> {
+    let mut m = [[S::zero(); 4]; 4];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = (0..4).fold(S::zero(), |sum, k| sum + a[i][k] * b[k][j]);
+        }
+    }
+    return m;
+}
+
+pub fn transform_point<S: Scalar>(m: &Matrix4<S>, p: Vector3<S>) -> Vector3<S> {
+    let mut ret = [S::zero(); 3];
+    for (i, r) in ret.iter_mut().enumerate() {
+        *r = m[i][0] * p[0] + m[i][1] * p[1] + m[i][2] * p[2] + m[i][3];
+    }
+    return ret;
+}
+
+// Applies a projective transform, including the divide by w. None for points
+// with w <= 0, which lie behind a perspective camera.
+pub fn project_point<S: Scalar>(m: &Matrix4<S>, p: Vector3<S>) -> Option<Vector3<S>> {
+    let w = m[3][0] * p[0] + m[3][1] * p[1] + m[3][2] * p[2] + m[3][3];
+    if w <= S::zero() {
+        return None;
+    }
+    return Some(scale(transform_point(m, p), S::one() / w));
+}
@@ -8,9 +8,9 @@ pub trait IndexType : Copy + Default {
 
     fn to_option(&self) -> Option<Self> {
         if self.is_valid() {
-            return None;
+            return Some(*self);
         }
-        return Some(*self);
+        return None;
     }
 }
 
@@ -24,10 +24,64 @@ impl IndexType for u32 {
     }
 
     fn is_valid(&self) -> bool {
-        return *self != Self::max_value();
+        return *self != Self::MAX;
     }
 
     fn max() -> Self {
-        return Self::max_value();
+        return Self::MAX;
     }
 }
+
+// Each element kind gets its own index type, so passing a face index where a
+// vertex index is expected is a compile error. The wrapped value is public
+// for interop with external arrays; INVALID marks an absent element.
+macro_rules! element_index {
+    ($name:ident) => {
+        #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        pub struct $name(pub Index);
+
+        impl $name {
+            pub const INVALID: $name = $name(Index::MAX);
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                return $name::INVALID;
+            }
+        }
+
+        impl IndexType for $name {
+            fn new(x: usize) -> Self {
+                return $name(x as Index);
+            }
+
+            fn index(&self) -> usize {
+                return self.0 as usize;
+            }
+
+            fn is_valid(&self) -> bool {
+                return self.0 != Index::MAX;
+            }
+
+            fn max() -> Self {
+                return $name::INVALID;
+            }
+        }
+
+        impl From<$name> for Index {
+            fn from(index: $name) -> Index {
+                return index.0;
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                return write!(f, "{}", self.0);
+            }
+        }
+    };
+}
+
+element_index!(VertexIndex);
+element_index!(EdgeIndex);
+element_index!(FaceIndex);
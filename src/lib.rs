@@ -1,2 +1,39 @@
+// The codebase favours explicit `return` statements and spelled-out field
+// initializers; keep clippy from fighting that style.
+#![allow(clippy::needless_return, clippy::redundant_field_names, clippy::needless_late_init)]
+
 pub mod index;
 pub mod mesh;
+pub mod edge_map;
+pub mod dynamic;
+pub mod geometry;
+pub mod buffer;
+pub mod predicates;
+pub mod tolerance;
+pub mod spatial;
+pub mod snap;
+pub mod reposition;
+pub mod scene;
+pub mod provenance;
+pub mod ops;
+pub mod selection;
+pub mod coloring;
+pub mod partition;
+pub mod symmetry;
+pub mod align;
+pub mod transfer;
+pub mod bake;
+pub mod slice;
+pub mod silhouette;
+pub mod support;
+pub mod debug;
+pub mod summary;
+
+#[cfg(feature = "reconstruct")]
+pub mod reconstruct;
+
+#[cfg(feature = "rhai")]
+pub mod script;
+
+#[cfg(feature = "python")]
+pub mod python;
@@ -1,5 +1,28 @@
+use super::geometry::{self, HasPosition, Vec3};
 use super::index::*;
 
+// The element refs, iterators and views only borrow the mesh, so they are
+// cloneable whatever the payload types are. #[derive(Clone)] would instead
+// demand V, E and F: Clone.
+macro_rules! impl_clone {
+    (Copy for $name:ident) => {
+        impl<'a, V, E, F> Copy for $name<'a, V, E, F> {}
+
+        impl<'a, V, E, F> Clone for $name<'a, V, E, F> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+    };
+    ($name:ident { $($field:ident),* }) => {
+        impl<'a, V, E, F> Clone for $name<'a, V, E, F> {
+            fn clone(&self) -> Self {
+                return $name { $($field: self.$field.clone()),* };
+            }
+        }
+    };
+}
+
 /*
  * Vertex
  */
@@ -15,21 +38,22 @@ impl<Ix : IndexType, V> VertexInfo<Ix, V> {
     }
 }
 
-#[derive(Copy, Clone)]
 pub struct VertexRef<'a, V, E, F> {
     mesh: &'a Mesh<V, E, F>,
-    vertex_index: Index,
+    vertex_index: VertexIndex,
 }
 
-impl<'a, V, E, F> Into<Index> for VertexRef<'a, V, E, F> {
-    fn into(self) -> Index {
-        return self.vertex_index;
+impl_clone!(Copy for VertexRef);
+
+impl<'a, V, E, F> From<VertexRef<'a, V, E, F>> for VertexIndex {
+    fn from(vertex: VertexRef<'a, V, E, F>) -> VertexIndex {
+        return vertex.vertex_index;
     }
 }
 
 impl<'a, V, E, F> VertexRef<'a, V, E, F> {
     // Public methods
-    pub fn new(mesh: &'a Mesh<V, E, F>, index: Index) -> Self {
+    pub fn new(mesh: &'a Mesh<V, E, F>, index: VertexIndex) -> Self {
         VertexRef{ mesh: mesh, vertex_index: index }
     }
 
@@ -43,11 +67,54 @@ impl<'a, V, E, F> VertexRef<'a, V, E, F> {
         };
     }
 
+    // The vertices sharing an edge with this one.
+    pub fn vertex_iter(self) -> VertexVertexIterator<'a, V, E, F> {
+        return VertexVertexIterator {
+            edge_iter: self.edge_iter()
+        };
+    }
+
+    // vertex_iter() follows the edge ring, whose order depends on how the
+    // edges were inserted. These give an order that doesn't.
+    pub fn sorted_vertices(&self) -> Vec<VertexIndex> {
+        let mut ret: Vec<VertexIndex> = self.vertex_iter().map(|v| v.index()).collect();
+        ret.sort();
+        return ret;
+    }
+
+    // The neighbors counter-clockwise around `normal`, starting from the
+    // lowest-indexed one.
+    pub fn vertices_ccw(&self, normal: Vec3) -> Vec<VertexIndex> where V: HasPosition {
+        let neighbors = self.sorted_vertices();
+        if neighbors.is_empty() {
+            return neighbors;
+        }
+        let n = geometry::normalize(normal);
+        let center = self.data().unwrap().position();
+        let tangent = |v: VertexIndex| {
+            let d = geometry::sub(self.mesh.vertex(v).data().unwrap().position(), center);
+            return geometry::sub(d, geometry::scale(n, geometry::dot(d, n)));
+        };
+        let reference = tangent(neighbors[0]);
+        let mut keyed: Vec<(f32, VertexIndex)> = neighbors.iter().map(|v| {
+            let t = tangent(*v);
+            let sin = geometry::dot(geometry::cross(reference, t), n);
+            let mut angle = sin.atan2(geometry::dot(reference, t));
+            if angle < 0.0 {
+                angle += 2.0 * std::f32::consts::PI;
+            }
+            return (angle, *v);
+        }).collect();
+        // Stable, so ties keep index order.
+        keyed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        return keyed.into_iter().map(|(_, v)| v).collect();
+    }
+
     pub fn is_valid(&self) -> bool {
         self.mesh.is_valid_vertex_index(self.index())
     }
 
-    pub fn index(&self) -> Index {
+    pub fn index(&self) -> VertexIndex {
         return self.vertex_index;
     }
 
@@ -58,21 +125,28 @@ impl<'a, V, E, F> VertexRef<'a, V, E, F> {
         return None;
     }
 
+    /// # Safety
+    /// The vertex must be valid, e.g. because it came from a mesh iterator.
+    pub unsafe fn data_unchecked(&self) -> &'a V {
+        return self.mesh.vertex_data_unchecked(self.vertex_index);
+    }
+
     // Private methods
-    fn vertex_info(&self) -> Option<&VertexInfo<Index, V>> {
+    fn vertex_info(&self) -> Option<&VertexInfo<EdgeIndex, V>> {
         // assume our index must exist.
         return self.mesh.vertex_info(self.index());
     }
 }
 
-#[derive(Clone)]
 pub struct VertexEdgeIterator<'a, V, E, F> {
     mesh: &'a Mesh<V, E, F>,
-    base_vertex_index: Index,
-    start_edge_index: Option<Index>,
-    current_edge_index: Option<Index>,
+    base_vertex_index: VertexIndex,
+    start_edge_index: Option<EdgeIndex>,
+    current_edge_index: Option<EdgeIndex>,
 }
 
+impl_clone!(VertexEdgeIterator { mesh, base_vertex_index, start_edge_index, current_edge_index });
+
 impl<'a, V, E, F> VertexEdgeIterator<'a, V, E, F> {
     pub fn new(base: VertexRef<'a, V, E, F>) -> Self {
         if base.is_valid() {
@@ -86,19 +160,19 @@ impl<'a, V, E, F> VertexEdgeIterator<'a, V, E, F> {
         } else {
             return VertexEdgeIterator {
                 mesh: base.mesh,
-                base_vertex_index: Index::max_value(),
+                base_vertex_index: VertexIndex::INVALID,
                 start_edge_index: None,
                 current_edge_index: None,
             }
         }
     }
 
-    pub fn vertex(&self) -> VertexRef<V, E, F> {
+    pub fn vertex(&self) -> VertexRef<'_, V, E, F> {
         // assume we have a valid vertex.
         return self.mesh.vertex(self.base_vertex_index);
     }
 
-    pub fn start_edge(&self) -> Option<EdgeRef<V, E, F>> {
+    pub fn start_edge(&self) -> Option<EdgeRef<'_, V, E, F>> {
         match self.start_edge_index {
             Some(index) => Some(EdgeRef{mesh: self.mesh, edge_index: index }),
             None => None
@@ -116,49 +190,78 @@ impl<'a, V, E, F> VertexEdgeIterator<'a, V, E, F> {
 impl<'a, V, E, F> Iterator for VertexEdgeIterator<'a, V, E, F> {
     type Item = EdgeRef<'a, V, E, F>;
 
-    // Iterates over the edges of a vertex.
+    // Iterates over the edges of a vertex, starting at its base edge.
     fn next(&mut self) -> Option<Self::Item> {
         match self.current_edge() {
             Some(edge) => {
-                let next_edge_index: Index;
-                    if edge.half_edge[0].vertex_index == self.base_vertex_index {
-                        next_edge_index = edge.half_edge[0].next_edge_index;
+                let current_edge_index = self.current_edge_index.unwrap();
+                let next_edge_index: EdgeIndex;
+                if edge.half_edge[0].vertex_index == self.base_vertex_index {
+                    next_edge_index = edge.half_edge[0].next_edge_index;
                 } else {
                     assert!(edge.half_edge[1].vertex_index == self.base_vertex_index,
                             "edge iterator reached an edge unconnected to the base vertex!");
-                        next_edge_index = edge.half_edge[1].next_edge_index;
+                    next_edge_index = edge.half_edge[1].next_edge_index;
                 }
 
                 // it doesn't make sense to have a next edge without a start edge,
                 // so using unwrap is fine here.
                 if self.start_edge_index.unwrap() == next_edge_index {
                     self.current_edge_index = None;
-                    return None;
                 } else {
                     self.current_edge_index = Some(next_edge_index);
-                    return Some(EdgeRef {
-                        mesh: self.mesh,
-                        edge_index: next_edge_index,
-                    });
                 }
+                return Some(EdgeRef {
+                    mesh: self.mesh,
+                    edge_index: current_edge_index,
+                });
             },
             None => return None
         }
     }
 }
 
-#[derive(Clone)]
+pub struct VertexVertexIterator<'a, V, E, F> {
+    edge_iter: VertexEdgeIterator<'a, V, E, F>,
+}
+
+impl_clone!(VertexVertexIterator { edge_iter });
+
+impl<'a, V, E, F> Iterator for VertexVertexIterator<'a, V, E, F> {
+    type Item = VertexRef<'a, V, E, F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(edge) = self.edge_iter.next() {
+            let edge_info = edge.edge_info().unwrap();
+            let vertex_index: VertexIndex;
+            if edge_info.half_edge[0].vertex_index == self.edge_iter.base_vertex_index {
+                vertex_index = edge_info.half_edge[1].vertex_index;
+            } else {
+                vertex_index = edge_info.half_edge[0].vertex_index;
+            }
+            return Some(VertexRef {
+                mesh: self.edge_iter.mesh,
+                vertex_index: vertex_index,
+            });
+        }
+        return None;
+    }
+}
+
 pub struct VertexFaceIterator<'a, V, E, F> {
     edge_iter: VertexEdgeIterator<'a, V, E, F>,
 }
 
+impl_clone!(VertexFaceIterator { edge_iter });
+
 impl<'a, V, E, F> Iterator for VertexFaceIterator<'a, V, E, F> {
     type Item = FaceRef<'a, V, E, F>;
 
+    // Each face around the vertex once; the edges facing a gap have no face.
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(maybe_edge) = self.edge_iter.next() {
+        while let Some(maybe_edge) = self.edge_iter.next() {
             if let Some(edge) = maybe_edge.edge_info() {
-                let face_index: Index;
+                let face_index: FaceIndex;
                 if edge.half_edge[0].vertex_index == self.edge_iter.base_vertex_index {
                     face_index = edge.half_edge[0].next_face_index;
                 } else {
@@ -166,10 +269,12 @@ impl<'a, V, E, F> Iterator for VertexFaceIterator<'a, V, E, F> {
                             "face iterator reached an face unconnected to the base vertex!");
                     face_index = edge.half_edge[1].next_face_index;
                 }
-                return Some(FaceRef {
-                    mesh: self.edge_iter.mesh,
-                    face_index: face_index,
-                });
+                if face_index.is_valid() {
+                    return Some(FaceRef {
+                        mesh: self.edge_iter.mesh,
+                        face_index: face_index,
+                    });
+                }
             }
         }
         return None;
@@ -181,19 +286,19 @@ impl<'a, V, E, F> Iterator for VertexFaceIterator<'a, V, E, F> {
  */
 #[derive(Copy, Clone)]
 struct HalfEdgeInfo {
-    vertex_index: Index,    // required.
-    next_face_index: Index, // optional. cw relative to base vertex
-    next_edge_index: Index, // optional. cw around base vertex
-    prev_edge_index: Index, // optional. ccw around base vertex
+    vertex_index: VertexIndex,  // required.
+    next_face_index: FaceIndex, // optional. the face this edge leaves the base vertex in.
+    next_edge_index: EdgeIndex, // optional. next around base vertex
+    prev_edge_index: EdgeIndex, // optional. previous around base vertex
 }
 
 impl HalfEdgeInfo {
     fn new() -> Self {
         HalfEdgeInfo {
-            vertex_index: Index::max_value(),
-            next_face_index: Index::max_value(),
-            next_edge_index: Index::max_value(),
-            prev_edge_index: Index::max_value(),
+            vertex_index: VertexIndex::INVALID,
+            next_face_index: FaceIndex::INVALID,
+            next_edge_index: EdgeIndex::INVALID,
+            prev_edge_index: EdgeIndex::INVALID,
         }
     }
 }
@@ -214,7 +319,7 @@ impl<E> EdgeInfo<E> {
         }
     }
 
-    fn next_edge_index_for_vertex(&self, base_vertex_index: Index) -> Index {
+    fn next_edge_index_for_vertex(&self, base_vertex_index: VertexIndex) -> EdgeIndex {
         if self.half_edge[0].vertex_index == base_vertex_index {
             return self.half_edge[0].next_edge_index;
         } else {
@@ -225,18 +330,18 @@ impl<E> EdgeInfo<E> {
         }
     }
 
-    fn previous_edge_index_for_vertex(&self, base_vertex_index: Index) -> Index {
+    fn previous_edge_index_for_vertex(&self, base_vertex_index: VertexIndex) -> EdgeIndex {
         if self.half_edge[0].vertex_index == base_vertex_index {
-            return self.half_edge[0].next_edge_index;
+            return self.half_edge[0].prev_edge_index;
         } else {
             assert!(self.half_edge[1].vertex_index == base_vertex_index,
                     "Attempt to call 'previous_edge_for_vertex' \
                     on edge that is not connected to vertex.");
-            return self.half_edge[1].next_edge_index;
+            return self.half_edge[1].prev_edge_index;
         }
     }
 
-    fn half_edge_for_vertex(&self, v : Index) -> &HalfEdgeInfo {
+    fn half_edge_for_vertex(&self, v : VertexIndex) -> &HalfEdgeInfo {
         if self.half_edge[0].vertex_index == v {
             return &self.half_edge[0];
         }
@@ -244,7 +349,7 @@ impl<E> EdgeInfo<E> {
         return &self.half_edge[1];
     }
 
-    fn half_edge_for_vertex_mut(&mut self, v : Index) -> &mut HalfEdgeInfo {
+    fn half_edge_for_vertex_mut(&mut self, v : VertexIndex) -> &mut HalfEdgeInfo {
         if self.half_edge[0].vertex_index == v {
             return &mut self.half_edge[0];
         }
@@ -253,14 +358,15 @@ impl<E> EdgeInfo<E> {
     }
 }
 
-#[derive(Copy, Clone)]
 pub struct EdgeRef<'a, V, E, F> {
     mesh: &'a Mesh<V, E, F>,
-    edge_index: Index
+    edge_index: EdgeIndex
 }
 
+impl_clone!(Copy for EdgeRef);
+
 impl<'a, V, E, F> EdgeRef<'a, V, E, F> {
-    pub fn new(mesh: &'a Mesh<V, E, F>, index: Index) -> EdgeRef<'a, V, E, F> {
+    pub fn new(mesh: &'a Mesh<V, E, F>, index: EdgeIndex) -> EdgeRef<'a, V, E, F> {
         EdgeRef { mesh: mesh, edge_index: index }
     }
 
@@ -280,17 +386,27 @@ impl<'a, V, E, F> EdgeRef<'a, V, E, F> {
         return None;
     }
 
-    pub fn index(&self) -> Index {
+    pub fn index(&self) -> EdgeIndex {
         return self.edge_index;
     }
 
+    /// # Safety
+    /// The edge must be valid, e.g. because it came from a mesh iterator.
+    pub unsafe fn data_unchecked(&self) -> &'a E {
+        return self.mesh.edge_data_unchecked(self.edge_index);
+    }
+
+    pub fn flags(&self) -> EdgeFlags {
+        return self.mesh.edge_flags(self.edge_index);
+    }
+
     // vector of size 0-2
-    pub fn faces(&self) -> Vec<Index> {
-        let mut ret: Vec<Index> = Vec::with_capacity(2);
+    pub fn faces(&self) -> Vec<FaceIndex> {
+        let mut ret: Vec<FaceIndex> = Vec::with_capacity(2);
         if self.is_valid() {
             let edge_info = self.edge_info().unwrap();
             for i in [0, 1].iter() {
-                let face_index = edge_info.half_edge[0].next_face_index;
+                let face_index = edge_info.half_edge[*i].next_face_index;
                 if self.mesh.is_valid_face_index(face_index) {
                     ret.push(face_index);
                 }
@@ -299,13 +415,18 @@ impl<'a, V, E, F> EdgeRef<'a, V, E, F> {
         return ret;
     }
 
+    // true for edges with fewer than two faces, including wire edges.
+    pub fn is_boundary(&self) -> bool {
+        return self.faces().len() < 2;
+    }
+
     // vector of size 2
-    pub fn vertices(&self) -> Vec<Index> {
-        let mut ret: Vec<Index> = Vec::with_capacity(2);
+    pub fn vertices(&self) -> Vec<VertexIndex> {
+        let mut ret: Vec<VertexIndex> = Vec::with_capacity(2);
         if self.is_valid() {
             let edge_info = self.edge_info().unwrap();
             for i in [0, 1].iter() {
-                let vertex_index = edge_info.half_edge[0].vertex_index;
+                let vertex_index = edge_info.half_edge[*i].vertex_index;
                 if self.mesh.is_valid_vertex_index(vertex_index) {
                     ret.push(vertex_index);
                 }
@@ -316,82 +437,317 @@ impl<'a, V, E, F> EdgeRef<'a, V, E, F> {
     }
 }
 
+// Per-edge marker bits. Stored one byte per edge on the mesh, and only
+// allocated once some edge is actually flagged.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct EdgeFlags(u8);
+
+impl EdgeFlags {
+    pub const NONE: EdgeFlags = EdgeFlags(0);
+    pub const SEAM: EdgeFlags = EdgeFlags(1 << 0);
+    pub const SHARP: EdgeFlags = EdgeFlags(1 << 1);
+    pub const HIDDEN: EdgeFlags = EdgeFlags(1 << 2);
+
+    pub fn bits(&self) -> u8 {
+        return self.0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0 == 0;
+    }
+
+    // true if every bit in `other` is set.
+    pub fn contains(&self, other: EdgeFlags) -> bool {
+        return self.0 & other.0 == other.0;
+    }
+
+    // true if any bit in `other` is set.
+    pub fn intersects(&self, other: EdgeFlags) -> bool {
+        return self.0 & other.0 != 0;
+    }
+
+    pub fn insert(&mut self, other: EdgeFlags) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: EdgeFlags) {
+        self.0 &= !other.0;
+    }
+}
+
+impl std::ops::BitOr for EdgeFlags {
+    type Output = EdgeFlags;
+
+    fn bitor(self, other: EdgeFlags) -> EdgeFlags {
+        return EdgeFlags(self.0 | other.0);
+    }
+}
+
 /*
  * Faces
  */
 #[derive(Copy, Clone)]
 struct FaceInfo<F> {
-    base_edge_index: Index, // required.
+    base_edge_index: EdgeIndex, // required.
     data: F,
 }
 
-#[derive(Clone)]
 pub struct FaceRef<'a, V, E, F> {
     mesh: &'a Mesh<V, E, F>,
-    face_index: Index,
+    face_index: FaceIndex,
 }
 
+impl_clone!(Copy for FaceRef);
+
 impl<'a, V, E, F> FaceRef<'a, V, E, F> {
     fn face_info(&self) -> &FaceInfo<F> {
         // assume our index must exist.
         return self.mesh.face_info(self.face_index).unwrap();
     }
 
+    pub fn index(&self) -> FaceIndex {
+        return self.face_index;
+    }
+
     pub fn data(&self) -> &F {
         return &self.face_info().data
     }
 
-    pub fn edge_iter(&self) -> FaceEdgeIterator<V, E, F> {
+    /// # Safety
+    /// The face must be valid, e.g. because it came from a mesh iterator.
+    pub unsafe fn data_unchecked(&self) -> &'a F {
+        return self.mesh.face_data_unchecked(self.face_index);
+    }
+
+    // The edges of the face in the order its vertices were given, starting
+    // at its base edge.
+    pub fn edge_iter(&self) -> FaceEdgeIterator<'a, V, E, F> {
         let edge_index = self.face_info().base_edge_index;
         assert!(self.mesh.is_valid_edge_index(edge_index));
+        let edge = &self.mesh.edges[edge_index.index()];
+        let half = if edge.half_edge[0].next_face_index == self.face_index { 0 } else { 1 };
         return FaceEdgeIterator {
             mesh: self.mesh,
             face_index: self.face_index,
             start_edge_index: edge_index,
-            current_edge_index: edge_index,
+            current_edge_index: Some(edge_index),
+            current_vertex_index: edge.half_edge[half].vertex_index,
+        }
+    }
+
+    // The vertices of the face in the order they were given, starting at the
+    // base edge.
+    pub fn vertex_iter(&self) -> FaceVertexIterator<'a, V, E, F> {
+        return FaceVertexIterator {
+            edge_iter: self.edge_iter(),
         }
     }
 }
 
-#[allow(dead_code)] // TODO: remove when implemented
-#[derive(Clone)]
 pub struct FaceEdgeIterator<'a, V, E, F> {
     mesh: &'a Mesh<V, E, F>,
-    face_index: Index,
-    start_edge_index: Index,
-    current_edge_index: Index,
+    face_index: FaceIndex,
+    start_edge_index: EdgeIndex,
+    current_edge_index: Option<EdgeIndex>,
+    current_vertex_index: VertexIndex, // where the current edge starts, in face order.
 }
 
+impl_clone!(FaceEdgeIterator { mesh, face_index, start_edge_index, current_edge_index, current_vertex_index });
+
 impl<'a, V, E, F> Iterator for FaceEdgeIterator<'a, V, E, F> {
     type Item = EdgeRef<'a, V, E, F>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        unimplemented!(); // TODO
+        let current_edge_index = self.current_edge_index?;
+        let (next_edge_index, next_vertex_index) =
+            self.mesh.next_face_edge(self.face_index, current_edge_index, self.current_vertex_index);
+        if next_edge_index == self.start_edge_index {
+            self.current_edge_index = None;
+        } else {
+            self.current_edge_index = Some(next_edge_index);
+        }
+        self.current_vertex_index = next_vertex_index;
+        return Some(EdgeRef {
+            mesh: self.mesh,
+            edge_index: current_edge_index,
+        });
     }
 }
 
-#[allow(dead_code)] // TODO: remove when implemented
-#[derive(Clone)]
 pub struct FaceVertexIterator<'a, V, E, F> {
     edge_iter: FaceEdgeIterator<'a, V, E, F>,
 }
 
+impl_clone!(FaceVertexIterator { edge_iter });
+
 impl<'a, V, E, F> Iterator for FaceVertexIterator<'a, V, E, F> {
     type Item = VertexRef<'a, V, E, F>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        unimplemented!(); // TODO
+        let vertex_index = self.edge_iter.current_vertex_index;
+        self.edge_iter.next()?;
+        return Some(VertexRef {
+            mesh: self.edge_iter.mesh,
+            vertex_index: vertex_index,
+        });
+    }
+}
+
+// What add_face_oriented() does with a face whose winding disagrees with a
+// neighbour's, i.e. that runs along a shared edge in the same direction as
+// the face already there.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WindingPolicy {
+    // Refuse the face.
+    Error,
+    // Add the face reversed, if that agrees with all its neighbours.
+    Flip,
+    // Add the face as given, cut loose from the neighbours it disagrees
+    // with: each such side gets an edge of its own beside the shared one,
+    // leaving a seam of boundary edges.
+    Accept,
+}
+
+// The side of a face that another face already runs along in the same
+// direction.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct WindingConflict {
+    pub from: VertexIndex,
+    pub to: VertexIndex,
+}
+
+impl std::fmt::Display for WindingConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return write!(f, "edge {} -> {} already has a face on that side", self.from, self.to);
+    }
+}
+
+impl std::error::Error for WindingConflict {}
+
+/*
+ * Boundaries
+ */
+
+// One side of an edge, running from one endpoint to the other.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct HalfEdge {
+    pub edge: EdgeIndex,
+    pub from: VertexIndex,
+    pub to: VertexIndex,
+}
+
+impl<V, E, F> Mesh<V, E, F> {
+    // The open side of an edge with exactly one face, which runs against the
+    // face's winding, or None if `e` doesn't have exactly one face.
+    pub fn boundary_half_edge(&self, e: EdgeIndex) -> Option<HalfEdge> {
+        assert!(self.is_valid_edge_index(e), "boundary_half_edge: invalid edge {}", e);
+        let vertices = self.edge(e).vertices();
+        let (a, b) = (vertices[0], vertices[1]);
+        return match (self.face_at(e, a).is_valid(), self.face_at(e, b).is_valid()) {
+            (false, true) => Some(HalfEdge { edge: e, from: a, to: b }),
+            (true, false) => Some(HalfEdge { edge: e, from: b, to: a }),
+            _ => None,
+        };
+    }
+
+    // The boundary half-edge leaving `h.to` that follows `h` round its hole:
+    // the first open side after h's edge in the ring at `h.to`, skipping
+    // edges with no faces at all. Panics if `h` isn't a boundary half-edge.
+    pub fn next_boundary_half_edge(&self, h: HalfEdge) -> HalfEdge {
+        assert_eq!(self.boundary_half_edge(h.edge), Some(h), "next_boundary_half_edge: not a boundary half-edge");
+        let mut e = h.edge;
+        loop {
+            e = self.ring_next(e, h.to);
+            let other = self.other_vertex(e, h.to);
+            if !self.face_at(e, h.to).is_valid() && self.face_at(e, other).is_valid() {
+                return HalfEdge { edge: e, from: h.to, to: other };
+            }
+            // a consistent mesh always finds one, since h's own edge qualifies
+            // from the far side.
+            assert!(e != h.edge, "next_boundary_half_edge: no open side at vertex {}", h.to);
+        }
+    }
+
+    // Walks the boundary loop through `e`, which must have exactly one face,
+    // starting with its open side. The half-edges' `from` vertices, in
+    // order, wind the way a face filling the hole would.
+    pub fn boundary_loop(&self, e: EdgeIndex) -> BoundaryLoopIterator<'_, V, E, F> {
+        let start = self.boundary_half_edge(e);
+        assert!(start.is_some(), "boundary_loop: edge {} doesn't have exactly one face", e);
+        return BoundaryLoopIterator {
+            mesh: self,
+            start: start.unwrap(),
+            current: start,
+            remaining: self.edges.len(),
+        };
+    }
+
+    // Every boundary loop, each once, starting from its lowest edge.
+    pub fn boundary_loops(&self) -> Vec<Vec<HalfEdge>> {
+        let mut seen = vec![false; self.edges.len()];
+        let mut ret = Vec::new();
+        for e in self.edge_iter() {
+            if seen[e.index().index()] || self.boundary_half_edge(e.index()).is_none() {
+                continue;
+            }
+            let boundary: Vec<HalfEdge> = self.boundary_loop(e.index()).collect();
+            for h in boundary.iter() {
+                seen[h.edge.index()] = true;
+            }
+            ret.push(boundary);
+        }
+        return ret;
+    }
+}
+
+pub struct BoundaryLoopIterator<'a, V, E, F> {
+    mesh: &'a Mesh<V, E, F>,
+    start: HalfEdge,
+    current: Option<HalfEdge>,
+    // an upper bound, so a ring that isn't in rotational order can't send
+    // the walk round forever.
+    remaining: usize,
+}
+
+impl_clone!(BoundaryLoopIterator { mesh, start, current, remaining });
+
+impl<'a, V, E, F> Iterator for BoundaryLoopIterator<'a, V, E, F> {
+    type Item = HalfEdge;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current?;
+        let next = self.mesh.next_boundary_half_edge(current);
+        self.remaining -= 1;
+        if next == self.start || self.remaining == 0 {
+            self.current = None;
+        } else {
+            self.current = Some(next);
+        }
+        return Some(current);
     }
 }
 
+impl<'a, V, E, F> std::iter::FusedIterator for BoundaryLoopIterator<'a, V, E, F> {}
+
 /*
  * Mesh
  */
+
+// A mesh with connectivity only; see the add_default_* methods.
+pub type TopologyMesh = Mesh<(), (), ()>;
+
 #[derive(Clone)]
 pub struct Mesh<V, E, F> {
-    verts: Vec<VertexInfo<Index, V>>,
+    verts: Vec<VertexInfo<EdgeIndex, V>>,
     edges: Vec<EdgeInfo<E>>,
     faces: Vec<FaceInfo<F>>,
+    edge_flags: Vec<EdgeFlags>, // empty, or one entry per edge.
+}
+
+impl<V, E, F> Default for Mesh<V, E, F> {
+    fn default() -> Self {
+        return Mesh::new();
+    }
 }
 
 impl<V, E, F> Mesh<V, E, F> {
@@ -400,174 +756,1588 @@ impl<V, E, F> Mesh<V, E, F> {
             verts: Vec::new(),
             edges: Vec::new(),
             faces: Vec::new(),
+            edge_flags: Vec::new(),
         }
     }
 
-    pub fn is_valid_vertex_index(&self, index: Index) -> bool {
-        return index < Index::max_value() && (index as usize) < self.verts.len();
+    pub fn is_valid_vertex_index(&self, index: VertexIndex) -> bool {
+        return index.is_valid() && index.index() < self.verts.len();
     }
 
-    pub fn is_valid_edge_index(&self, index: Index) -> bool {
-        return index < Index::max_value() && (index as usize) < self.edges.len();
+    pub fn is_valid_edge_index(&self, index: EdgeIndex) -> bool {
+        return index.is_valid() && index.index() < self.edges.len();
     }
 
-    pub fn is_valid_face_index(&self, index: Index) -> bool {
-        return index < Index::max_value() && (index as usize) < self.faces.len();
+    pub fn is_valid_face_index(&self, index: FaceIndex) -> bool {
+        return index.is_valid() && index.index() < self.faces.len();
     }
 
-    fn vertex_info(&self, index: Index) -> Option<&VertexInfo<Index, V>> {
+    fn vertex_info(&self, index: VertexIndex) -> Option<&VertexInfo<EdgeIndex, V>> {
         if self.is_valid_vertex_index(index) {
-            return Some(&self.verts[index as usize]);
+            return Some(&self.verts[index.index()]);
         }
         return None;
     }
 
-    fn vertex_info_mut(&mut self, index: Index) -> Option<&mut VertexInfo<Index, V>> {
+    fn vertex_info_mut(&mut self, index: VertexIndex) -> Option<&mut VertexInfo<EdgeIndex, V>> {
         if self.is_valid_vertex_index(index) {
-            return Some(&mut self.verts[index as usize]);
+            return Some(&mut self.verts[index.index()]);
         }
         return None;
     }
 
-    fn edge_info(&self, index: Index) -> Option<&EdgeInfo<E>> {
+    fn edge_info(&self, index: EdgeIndex) -> Option<&EdgeInfo<E>> {
         if self.is_valid_edge_index(index) {
-            return Some(&self.edges[index as usize]);
+            return Some(&self.edges[index.index()]);
         }
         return None;
     }
 
-    fn face_info(&self, index: Index) -> Option<&FaceInfo<F>> {
+    fn face_info(&self, index: FaceIndex) -> Option<&FaceInfo<F>> {
         if self.is_valid_face_index(index) {
-            return Some(&self.faces[index as usize]);
+            return Some(&self.faces[index.index()]);
         }
         return None;
     }
 
-    pub fn vertex(&self, index: Index) -> VertexRef<V, E, F> {
+    pub fn vertex_data_mut(&mut self, index: VertexIndex) -> Option<&mut V> {
+        return self.vertex_info_mut(index).map(|info| &mut info.data);
+    }
+
+    // Unchecked accessors for hot loops. These skip the validity checks the
+    // rest of the API performs on every access.
+
+    /// # Safety
+    /// `index` must be a valid vertex index.
+    pub unsafe fn vertex_data_unchecked(&self, index: VertexIndex) -> &V {
+        return &self.verts.get_unchecked(index.index()).data;
+    }
+
+    /// # Safety
+    /// `index` must be a valid vertex index.
+    pub unsafe fn vertex_data_unchecked_mut(&mut self, index: VertexIndex) -> &mut V {
+        return &mut self.verts.get_unchecked_mut(index.index()).data;
+    }
+
+    /// # Safety
+    /// `index` must be a valid edge index.
+    pub unsafe fn edge_data_unchecked(&self, index: EdgeIndex) -> &E {
+        return &self.edges.get_unchecked(index.index()).data;
+    }
+
+    /// # Safety
+    /// `index` must be a valid edge index.
+    pub unsafe fn edge_data_unchecked_mut(&mut self, index: EdgeIndex) -> &mut E {
+        return &mut self.edges.get_unchecked_mut(index.index()).data;
+    }
+
+    /// # Safety
+    /// `index` must be a valid face index.
+    pub unsafe fn face_data_unchecked(&self, index: FaceIndex) -> &F {
+        return &self.faces.get_unchecked(index.index()).data;
+    }
+
+    /// # Safety
+    /// `index` must be a valid face index.
+    pub unsafe fn face_data_unchecked_mut(&mut self, index: FaceIndex) -> &mut F {
+        return &mut self.faces.get_unchecked_mut(index.index()).data;
+    }
+
+    pub fn vertex(&self, index: VertexIndex) -> VertexRef<'_, V, E, F> {
         return VertexRef{mesh: self, vertex_index: index};
     }
 
-    pub fn edge(&self, index: Index) -> EdgeRef<V, E, F> {
+    pub fn edge(&self, index: EdgeIndex) -> EdgeRef<'_, V, E, F> {
         return EdgeRef{mesh: self, edge_index: index};
     }
 
-    pub fn face(&self, index: Index) -> FaceRef<V, E, F> {
+    pub fn face(&self, index: FaceIndex) -> FaceRef<'_, V, E, F> {
         return FaceRef{mesh: self, face_index: index};
     }
 
-    pub fn vertex_iter(&self) -> MeshVertexIterator<V, E, F> {
-        MeshVertexIterator { mesh: self, vertex_index: 0 }
+    pub fn vertex_iter(&self) -> MeshVertexIterator<'_, V, E, F> {
+        MeshVertexIterator { mesh: self, vertex_index: 0, end_index: Index::new(self.verts.len()) }
+    }
+
+    pub fn edge_iter(&self) -> MeshEdgeIterator<'_, V, E, F> {
+        MeshEdgeIterator { mesh: self, edge_index: 0, end_index: Index::new(self.edges.len()) }
+    }
+
+    pub fn face_iter(&self) -> MeshFaceIterator<'_, V, E, F> {
+        MeshFaceIterator { mesh: self, face_index: 0, end_index: Index::new(self.faces.len()) }
+    }
+
+    // Views over each element kind, for `for v in &mesh.vertices()` loops and
+    // `mesh.vertices()[i]` payload access.
+    pub fn vertices(&self) -> Vertices<'_, V, E, F> {
+        Vertices { mesh: self }
+    }
+
+    pub fn edges(&self) -> Edges<'_, V, E, F> {
+        Edges { mesh: self }
+    }
+
+    pub fn faces(&self) -> Faces<'_, V, E, F> {
+        Faces { mesh: self }
+    }
+
+    // Pairs each vertex with the element of `data` at the same index, for
+    // per-vertex attributes kept outside the mesh. None if the lengths differ.
+    pub fn zip_vertex_data<'b, T>(&self, data: &'b [T]) -> Option<ZipVertices<'_, 'b, V, E, F, T>> {
+        if data.len() != self.verts.len() {
+            return None;
+        }
+        return Some(ZipVertices { mesh: self, data: data });
+    }
+
+    pub fn zip_vertex_data_mut<'b, T>(&self, data: &'b mut [T])
+        -> Option<ZipVertexIteratorMut<'_, 'b, V, E, F, T>> {
+        if data.len() != self.verts.len() {
+            return None;
+        }
+        return Some(self.vertex_iter().zip(data.iter_mut()));
+    }
+
+    // (index, payload) pairs, without going through element refs.
+    pub fn vertex_data_iter(&self) -> MeshVertexDataIterator<'_, V> {
+        MeshVertexDataIterator { iter: self.verts.iter().enumerate() }
     }
 
-    pub fn edge_iter(&self) -> MeshEdgeIterator<V, E, F> {
-        MeshEdgeIterator { edge: EdgeRef::new(self, 0) }
+    pub fn edge_data_iter(&self) -> MeshEdgeDataIterator<'_, E> {
+        MeshEdgeDataIterator { iter: self.edges.iter().enumerate() }
     }
 
-    pub fn face_iter(&self) -> MeshFaceIterator<V, E, F> {
-        MeshFaceIterator { mesh: self, face_index: 0 }
+    pub fn face_data_iter(&self) -> MeshFaceDataIterator<'_, F> {
+        MeshFaceDataIterator { iter: self.faces.iter().enumerate() }
     }
 
-    pub fn add_vertex(&mut self, v: V) -> Index {
-        let index = Index::new(self.verts.len());
+    pub fn add_vertex(&mut self, v: V) -> VertexIndex {
+        let index = VertexIndex::new(self.verts.len());
         self.verts.push(VertexInfo::new(v));
         return index;
     }
 
-    pub fn add_edge(&mut self, e: E, v1: Index, v2: Index) -> Index {
-        let new_index = Index::new(self.edges.len());
+    // A mesh with the same connectivity and each payload passed through the
+    // matching closure.
+    pub fn map_data<V2, E2, F2, FV, FE, FF>(&self, mut vf: FV, mut ef: FE, mut ff: FF)
+        -> Mesh<V2, E2, F2>
+        where FV: FnMut(&V) -> V2, FE: FnMut(&E) -> E2, FF: FnMut(&F) -> F2 {
+        return Mesh {
+            verts: self.verts.iter().map(|v| VertexInfo {
+                base_edge_index: v.base_edge_index,
+                data: vf(&v.data),
+            }).collect(),
+            edges: self.edges.iter().map(|e| EdgeInfo {
+                half_edge: e.half_edge,
+                data: ef(&e.data),
+            }).collect(),
+            faces: self.faces.iter().map(|f| FaceInfo {
+                base_edge_index: f.base_edge_index,
+                data: ff(&f.data),
+            }).collect(),
+            edge_flags: self.edge_flags.clone(),
+        };
+    }
+
+    // The connectivity (and edge flags) without any payloads, for running
+    // topological algorithms on meshes whose payloads are big or not Clone.
+    pub fn clone_topology(&self) -> TopologyMesh {
+        return self.map_data(|_| (), |_| (), |_| ());
+    }
+
+    // Bytes allocated for the element tables, including payloads stored
+    // inline but not anything the payloads themselves point to.
+    pub fn memory_usage(&self) -> usize {
+        return self.verts.capacity() * std::mem::size_of::<VertexInfo<EdgeIndex, V>>()
+            + self.edges.capacity() * std::mem::size_of::<EdgeInfo<E>>()
+            + self.faces.capacity() * std::mem::size_of::<FaceInfo<F>>()
+            + self.edge_flags.capacity() * std::mem::size_of::<EdgeFlags>();
+    }
+
+    // Payload-less insertion, mostly for TopologyMesh.
+    pub fn add_default_vertex(&mut self) -> VertexIndex where V: Default {
+        return self.add_vertex(V::default());
+    }
+
+    pub fn add_default_edge(&mut self, v1: VertexIndex, v2: VertexIndex) -> EdgeIndex
+        where E: Default {
+        return self.add_edge(E::default(), v1, v2);
+    }
+
+    // Edges the face needs get default payloads too.
+    pub fn add_default_face(&mut self, vertices: &[VertexIndex]) -> FaceIndex
+        where E: Default, F: Default {
+        return self.add_face_with(F::default(), vertices, |_, _| E::default());
+    }
+
+    pub fn add_edge(&mut self, e: E, v1: VertexIndex, v2: VertexIndex) -> EdgeIndex {
+        let new_index = EdgeIndex::new(self.edges.len());
         let mut new_edge: EdgeInfo<E> = EdgeInfo::new(e);
 
-        // The edge list is basically a doubley linked list.
-        // Insert the new edge at the end of each edge list.
+        assert!(v1 != v2, "an edge must connect two distinct vertices");
+
+        // The edge list is a circular doubly linked list.
+        // Insert the new edge at the end of each edge list, or if the vertex
+        // has faces, at the last gap between them so no face's corner is split.
         for (i, v) in [v1, v2].iter().enumerate() {
             assert!(self.is_valid_vertex_index(*v));
-            let base_edge_index = self.verts[*v as usize].base_edge_index;
+            let base_edge_index = self.verts[v.index()].base_edge_index;
             if self.is_valid_edge_index(base_edge_index) {
-                let base_edge = &self.edges[base_edge_index as usize];
-                let prev_edge_index = base_edge.previous_edge_index_for_vertex(*v);
-                if self.is_valid_edge_index(prev_edge_index) {
-                    let prev_edge = &mut self.edges[prev_edge_index as usize];
-                    let mut prev_half_edge = prev_edge.half_edge_for_vertex_mut(*v);
-                    prev_half_edge.next_edge_index = new_index;
-                    new_edge.half_edge[i].prev_edge_index = new_index;
-                }
+                let prev_edge_index = self.last_gap(*v);
+                let next_edge_index = self.edges[prev_edge_index.index()].next_edge_index_for_vertex(*v);
+                let prev_edge = &mut self.edges[prev_edge_index.index()];
+                prev_edge.half_edge_for_vertex_mut(*v).next_edge_index = new_index;
+                let next_edge = &mut self.edges[next_edge_index.index()];
+                next_edge.half_edge_for_vertex_mut(*v).prev_edge_index = new_index;
+                new_edge.half_edge[i].next_edge_index = next_edge_index;
+                new_edge.half_edge[i].prev_edge_index = prev_edge_index;
             } else {
-                self.verts[*v as usize].base_edge_index = new_index;
+                self.verts[v.index()].base_edge_index = new_index;
+                new_edge.half_edge[i].next_edge_index = new_index;
+                new_edge.half_edge[i].prev_edge_index = new_index;
             }
-            new_edge.half_edge[i].next_edge_index = base_edge_index;
             new_edge.half_edge[i].vertex_index = *v;
-            //TODO: Deal with faces as well!!!
         }
 
         self.edges.push(new_edge);
+        if !self.edge_flags.is_empty() {
+            self.edge_flags.push(EdgeFlags::NONE);
+        }
+        self.anchor_to_boundary(v1);
+        self.anchor_to_boundary(v2);
         return new_index;
     }
 
-    pub fn add_face(&mut self, f: F, verts: Vec<Index>) -> Index {
-        let new_index = Index::new(self.faces.len());
-        assert!(verts.len() > 3, "a face must have more than 3 vertices");
-
-        unimplemented!();
-        //TODO: add face.
-        return new_index;
+    // Makes `e` the edge that v's circulators start from. The ring order
+    // itself is unchanged.
+    pub fn set_base_edge(&mut self, v: VertexIndex, e: EdgeIndex) {
+        assert!(self.is_valid_vertex_index(v) && self.is_valid_edge_index(e));
+        assert!(self.edge(e).vertices().contains(&v), "base edge {} is not incident to vertex {}", e, v);
+        self.verts[v.index()].base_edge_index = e;
     }
-}
 
-#[derive(Clone)]
-pub struct MeshVertexIterator<'a, V, E, F> {
-    mesh: &'a Mesh<V, E, F>,
-    vertex_index: Index,
-}
+    // Moves v's base edge to the first boundary edge in ring order, unless it
+    // is on the boundary already. Mutating operators call this for every
+    // vertex they touch, so circulators around a boundary vertex always
+    // start on the boundary. Returns false if v has no boundary edge.
+    pub fn anchor_to_boundary(&mut self, v: VertexIndex) -> bool {
+        let boundary = self.vertex(v).edge_iter().find(|e| e.is_boundary()).map(|e| e.index());
+        match boundary {
+            Some(e) => {
+                self.verts[v.index()].base_edge_index = e;
+                return true;
+            },
+            None => return false,
+        }
+    }
 
-impl<'a, V, E, F> Iterator for MeshVertexIterator<'a, V, E, F> {
-    type Item = VertexRef<'a, V, E, F>;
+    pub fn edge_flags(&self, index: EdgeIndex) -> EdgeFlags {
+        match self.edge_flags.get(index.index()) {
+            Some(flags) => *flags,
+            None => EdgeFlags::NONE,
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.mesh.is_valid_vertex_index(self.vertex_index) {
-            let ret = Some(VertexRef {
-                mesh: self.mesh,
-                vertex_index: self.vertex_index
-            });
-            self.vertex_index += 1;
-            return ret;
+    pub fn set_edge_flags(&mut self, index: EdgeIndex, flags: EdgeFlags) {
+        assert!(self.is_valid_edge_index(index));
+        if self.edge_flags.is_empty() {
+            if flags.is_empty() {
+                return;
+            }
+            self.edge_flags.resize(self.edges.len(), EdgeFlags::NONE);
         }
-        return None;
+        self.edge_flags[index.index()] = flags;
     }
-}
 
-#[derive(Clone)]
-pub struct MeshEdgeIterator<'a, V, E, F> {
-    edge: EdgeRef<'a, V, E, F>
-}
+    pub fn insert_edge_flags(&mut self, index: EdgeIndex, flags: EdgeFlags) {
+        let mut current = self.edge_flags(index);
+        current.insert(flags);
+        self.set_edge_flags(index, current);
+    }
 
-impl<'a, V, E, F> Iterator for MeshEdgeIterator<'a, V, E, F> {
-    type Item = EdgeRef<'a, V, E, F>;
+    pub fn remove_edge_flags(&mut self, index: EdgeIndex, flags: EdgeFlags) {
+        let mut current = self.edge_flags(index);
+        current.remove(flags);
+        self.set_edge_flags(index, current);
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.edge.is_valid() {
-            let mut tmp = EdgeRef::new(self.edge.mesh, self.edge.edge_index + 1);
-            std::mem::swap(&mut self.edge, &mut tmp);
-            return Some(tmp);
+    // Edges which have any of the bits in `flags` set.
+    pub fn edges_with_flags(&self, flags: EdgeFlags) -> Vec<EdgeIndex> {
+        let mut ret = Vec::new();
+        for (i, f) in self.edge_flags.iter().enumerate() {
+            if f.intersects(flags) {
+                ret.push(EdgeIndex::new(i));
+            }
         }
-        return None;
+        return ret;
     }
-}
 
-#[derive(Clone)]
-pub struct MeshFaceIterator<'a, V, E, F> {
-    mesh: &'a Mesh<V, E, F>,
-    face_index: Index,
-}
+    // Stable-sorts the vertices by `compare` and fixes every reference to them.
+    // Returns the new index of each old vertex.
+    pub fn sort_vertices_by<C>(&mut self, mut compare: C) -> Vec<VertexIndex>
+        where C: FnMut(VertexRef<'_, V, E, F>, VertexRef<'_, V, E, F>) -> std::cmp::Ordering {
+        let mut order: Vec<VertexIndex> = (0..self.verts.len()).map(VertexIndex::new).collect();
+        order.sort_by(|a, b| compare(self.vertex(*a), self.vertex(*b)));
+        let remap = inverse_permutation(&order);
+        self.verts = permute(std::mem::take(&mut self.verts), &order);
+        for edge in self.edges.iter_mut() {
+            for half_edge in edge.half_edge.iter_mut() {
+                half_edge.vertex_index = remap[half_edge.vertex_index.index()];
+            }
+        }
+        return remap;
+    }
 
-impl<'a, V, E, F> Iterator for MeshFaceIterator<'a, V, E, F> {
-    type Item = FaceRef<'a, V, E, F>;
+    // Stable-sorts the faces by `compare` and fixes every reference to them.
+    // Returns the new index of each old face.
+    pub fn sort_faces_by<C>(&mut self, mut compare: C) -> Vec<FaceIndex>
+        where C: FnMut(FaceRef<'_, V, E, F>, FaceRef<'_, V, E, F>) -> std::cmp::Ordering {
+        let mut order: Vec<FaceIndex> = (0..self.faces.len()).map(FaceIndex::new).collect();
+        order.sort_by(|a, b| compare(self.face(*a), self.face(*b)));
+        let remap = inverse_permutation(&order);
+        self.faces = permute(std::mem::take(&mut self.faces), &order);
+        for edge in self.edges.iter_mut() {
+            for half_edge in edge.half_edge.iter_mut() {
+                if half_edge.next_face_index.is_valid() {
+                    half_edge.next_face_index = remap[half_edge.next_face_index.index()];
+                }
+            }
+        }
+        return remap;
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.mesh.is_valid_face_index(self.face_index) {
-            let ret = Some(FaceRef { mesh: self.mesh, face_index: self.face_index });
-            self.face_index += 1;
-            return ret;
+    // The edge between `a` and `b`, if any.
+    pub fn find_edge(&self, a: VertexIndex, b: VertexIndex) -> Option<EdgeIndex> {
+        return self.vertex(a).edge_iter().find(|e| e.vertices().contains(&b)).map(|e| e.index());
+    }
+
+    // Whether add_face_with() would take `vertices`: at least 3 distinct,
+    // valid vertices, with no side already faced in that direction.
+    pub fn can_add_face(&self, vertices: &[VertexIndex]) -> bool {
+        let n = vertices.len();
+        if n < 3 {
+            return false;
         }
-        return None;
+        for (i, v) in vertices.iter().enumerate() {
+            if !self.is_valid_vertex_index(*v) || vertices[..i].contains(v) {
+                return false;
+            }
+        }
+        return self.winding_conflict(vertices).is_none();
+    }
+
+    // Adds a face bounded by `vertices` in order, over edges that must all
+    // exist already; see add_face_with() or add_default_face() to create
+    // them as needed.
+    pub fn add_face(&mut self, f: F, vertices: &[VertexIndex]) -> FaceIndex {
+        return self.add_face_with(f, vertices, |a, b| panic!("add_face: there is no edge {} -> {}", a, b));
+    }
+
+    // Adds a face bounded by `vertices` in order, creating any edges that
+    // don't exist yet with the payload `edge_data(a, b)` returns for the side
+    // a -> b. Each edge stores the face on its half at the vertex it leaves
+    // in that order, so two faces sharing an edge must list it in opposite
+    // directions, as consistently oriented faces do.
+    //
+    // The edge rings of the face's vertices are reordered, where that doesn't
+    // split another face's corner, so the two edges of each corner are
+    // adjacent: this keeps rings in rotational order for manifold meshes.
+    pub fn add_face_with<D>(&mut self, f: F, vertices: &[VertexIndex], edge_data: D) -> FaceIndex
+        where D: FnMut(VertexIndex, VertexIndex) -> E {
+        self.check_face_vertices(vertices);
+        if let Some(conflict) = self.winding_conflict(vertices) {
+            panic!("{}", conflict);
+        }
+        return self.insert_face(f, vertices, edge_data);
+    }
+
+    // Like add_face_with(), but a face that disagrees with a neighbour's
+    // winding is handled by `policy` rather than panicking, so faces from an
+    // unordered triangle soup can be added one at a time and still come out
+    // consistently oriented. Returns the first conflicting side when the
+    // face is refused; a flipped face lists its vertices in reverse.
+    pub fn add_face_oriented<D>(&mut self, f: F, vertices: &[VertexIndex], policy: WindingPolicy,
+                                edge_data: D) -> Result<FaceIndex, WindingConflict>
+        where D: FnMut(VertexIndex, VertexIndex) -> E {
+        self.check_face_vertices(vertices);
+        let conflict = match self.winding_conflict(vertices) {
+            Some(conflict) => conflict,
+            None => return Ok(self.insert_face(f, vertices, edge_data)),
+        };
+        match policy {
+            WindingPolicy::Error => return Err(conflict),
+            WindingPolicy::Flip => {
+                let reversed: Vec<VertexIndex> = vertices.iter().rev().cloned().collect();
+                if self.winding_conflict(&reversed).is_some() {
+                    return Err(conflict);
+                }
+                return Ok(self.insert_face(f, &reversed, edge_data));
+            }
+            WindingPolicy::Accept => return Ok(self.insert_face(f, vertices, edge_data)),
+        }
+    }
+
+    fn check_face_vertices(&self, vertices: &[VertexIndex]) {
+        assert!(vertices.len() >= 3, "a face must have at least 3 vertices");
+        for (i, v) in vertices.iter().enumerate() {
+            assert!(self.is_valid_vertex_index(*v), "face vertex {} is invalid", v);
+            assert!(!vertices[..i].contains(v), "face vertex {} is repeated", v);
+        }
+    }
+
+    // The first side of a face over `vertices` that can't be added because
+    // every edge along it already has a face on that side.
+    fn winding_conflict(&self, vertices: &[VertexIndex]) -> Option<WindingConflict> {
+        let n = vertices.len();
+        for i in 0..n {
+            let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+            if self.find_edge(a, b).is_some() && self.open_edge(a, b).is_none() {
+                return Some(WindingConflict { from: a, to: b });
+            }
+        }
+        return None;
+    }
+
+    // An edge between `a` and `b` with no face on its side leaving `a`.
+    fn open_edge(&self, a: VertexIndex, b: VertexIndex) -> Option<EdgeIndex> {
+        return self.vertex(a).edge_iter()
+            .map(|e| e.index())
+            .find(|e| self.other_vertex(*e, a) == b && !self.face_at(*e, a).is_valid());
+    }
+
+    // Adds a face over checked vertices, along an open edge for each side
+    // where there is one and over a new edge where there isn't.
+    fn insert_face<D>(&mut self, f: F, vertices: &[VertexIndex], mut edge_data: D) -> FaceIndex
+        where D: FnMut(VertexIndex, VertexIndex) -> E {
+        let n = vertices.len();
+        let new_index = FaceIndex::new(self.faces.len());
+        let mut edges: Vec<EdgeIndex> = Vec::with_capacity(n);
+        for i in 0..n {
+            let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+            let e = match self.open_edge(a, b) {
+                Some(e) => e,
+                None => self.add_edge(edge_data(a, b), a, b),
+            };
+            edges.push(e);
+        }
+        // only now, so a panicking edge_data leaves no half-added face.
+        for (e, a) in edges.iter().zip(vertices.iter()) {
+            self.edges[e.index()].half_edge_for_vertex_mut(*a).next_face_index = new_index;
+        }
+        self.faces.push(FaceInfo { base_edge_index: edges[0], data: f });
+
+        for i in 0..n {
+            self.join_corner(vertices[i], edges[i], edges[(i + n - 1) % n]);
+        }
+        for v in vertices.iter() {
+            self.anchor_to_boundary(*v);
+        }
+        return new_index;
+    }
+
+    fn face_at(&self, e: EdgeIndex, v: VertexIndex) -> FaceIndex {
+        return self.edges[e.index()].half_edge_for_vertex(v).next_face_index;
+    }
+
+    fn ring_next(&self, e: EdgeIndex, v: VertexIndex) -> EdgeIndex {
+        return self.edges[e.index()].next_edge_index_for_vertex(v);
+    }
+
+    fn ring_prev(&self, e: EdgeIndex, v: VertexIndex) -> EdgeIndex {
+        return self.edges[e.index()].previous_edge_index_for_vertex(v);
+    }
+
+    fn link(&mut self, prev: EdgeIndex, next: EdgeIndex, v: VertexIndex) {
+        self.edges[prev.index()].half_edge_for_vertex_mut(v).next_edge_index = next;
+        self.edges[next.index()].half_edge_for_vertex_mut(v).prev_edge_index = prev;
+    }
+
+    fn other_vertex(&self, e: EdgeIndex, v: VertexIndex) -> VertexIndex {
+        let edge = &self.edges[e.index()];
+        if edge.half_edge[0].vertex_index == v {
+            return edge.half_edge[1].vertex_index;
+        }
+        return edge.half_edge[0].vertex_index;
+    }
+
+    // The edge after `e` in face `f`, given that `e` leaves `from` in f's
+    // order, and the vertex it leaves from. In a rotationally ordered ring
+    // it is the edge before `e` around the far vertex.
+    fn next_face_edge(&self, f: FaceIndex, e: EdgeIndex, from: VertexIndex) -> (EdgeIndex, VertexIndex) {
+        let to = self.other_vertex(e, from);
+        let candidate = self.ring_prev(e, to);
+        if self.face_at(candidate, to) == f {
+            return (candidate, to);
+        }
+        let next = self.vertex(to).edge_iter().map(|x| x.index()).find(|x| self.face_at(*x, to) == f);
+        return (next.expect("face is not closed"), to);
+    }
+
+    // The edge of the face `e` leaves `v` in that arrives at `v`.
+    fn corner_partner(&self, e: EdgeIndex, v: VertexIndex) -> Option<EdgeIndex> {
+        let f = self.face_at(e, v);
+        if !f.is_valid() {
+            return None;
+        }
+        return self.vertex(v).edge_iter().map(|x| x.index())
+            .find(|x| self.face_at(*x, self.other_vertex(*x, v)) == f);
+    }
+
+    // The last edge around `v`, searching back from the end of its ring,
+    // that has no face after it.
+    fn last_gap(&self, v: VertexIndex) -> EdgeIndex {
+        let base = self.verts[v.index()].base_edge_index;
+        let last = self.ring_prev(base, v);
+        let mut e = last;
+        loop {
+            if !self.face_at(e, v).is_valid() {
+                return e;
+            }
+            e = self.ring_prev(e, v);
+            if e == last {
+                return last;
+            }
+        }
+    }
+
+    // Moves `arriving` to right after `leaving` in v's ring, along with the
+    // run of edges joined to it by faces' corners. Does nothing if that run
+    // reaches `leaving`, since the ring can't be reordered without splitting
+    // a corner.
+    fn join_corner(&mut self, v: VertexIndex, leaving: EdgeIndex, arriving: EdgeIndex) {
+        if self.ring_next(leaving, v) == arriving {
+            return;
+        }
+        let mut last = arriving;
+        while let Some(partner) = self.corner_partner(last, v) {
+            if partner != self.ring_next(last, v) || partner == arriving {
+                break;
+            }
+            if partner == leaving {
+                return;
+            }
+            last = partner;
+        }
+        let before = self.ring_prev(arriving, v);
+        let after = self.ring_next(last, v);
+        self.link(before, after, v);
+        let next = self.ring_next(leaving, v);
+        self.link(leaving, arriving, v);
+        self.link(last, next, v);
+    }
+}
+
+/*
+ * Removal
+ */
+
+// Elements are stored in dense tables, so removing one moves the last
+// element of its kind into the freed index, like Vec::swap_remove. Indices
+// held across a removal need to account for that: remove_vertex() and
+// remove_face() return the old index of the element they moved, which
+// Scene, FaceIds, SelectionSet and SpatialHash can follow with their
+// swap_remove().
+impl<V, E, F> Mesh<V, E, F> {
+    // Removes the vertex with all its edges and the faces around it, and
+    // re-anchors the vertices left behind to their new boundaries. The last
+    // vertex takes over `index`; its old index is returned, or None if
+    // `index` was the last. Edges and faces move the same way as they go.
+    pub fn remove_vertex(&mut self, index: VertexIndex) -> Option<VertexIndex> {
+        assert!(self.is_valid_vertex_index(index), "remove_vertex: invalid vertex {}", index);
+        let mut touched: Vec<VertexIndex> = self.vertex(index).vertex_iter().map(|v| v.index()).collect();
+        for f in self.vertex(index).face_iter() {
+            touched.extend(f.vertex_iter().map(|v| v.index()));
+        }
+        touched.retain(|v| *v != index);
+        while let Some(f) = self.vertex(index).face_iter().next().map(|f| f.index()) {
+            self.detach_face(f);
+        }
+        while self.verts[index.index()].base_edge_index.is_valid() {
+            self.detach_edge(self.verts[index.index()].base_edge_index);
+        }
+
+        let last = VertexIndex::new(self.verts.len() - 1);
+        let moved: Vec<EdgeIndex> = self.vertex(last).edge_iter().map(|e| e.index()).collect();
+        self.verts.swap_remove(index.index());
+        if index != last {
+            for e in moved {
+                self.edges[e.index()].half_edge_for_vertex_mut(last).vertex_index = index;
+            }
+        }
+        for v in touched {
+            self.anchor_to_boundary(if v == last { index } else { v });
+        }
+        if index == last {
+            return None;
+        }
+        return Some(last);
+    }
+
+    // Removes the face, leaving a hole bounded by its edges. With
+    // `remove_wire_edges`, edges that no other face uses are removed too
+    // (vertices are always kept). The last face takes over `index`; its old
+    // index is returned, or None if `index` was the last.
+    pub fn remove_face(&mut self, index: FaceIndex, remove_wire_edges: bool) -> Option<FaceIndex> {
+        assert!(self.is_valid_face_index(index), "remove_face: invalid face {}", index);
+        let last = FaceIndex::new(self.faces.len() - 1);
+        let sides = self.face_sides(index);
+        self.detach_face(index);
+        if remove_wire_edges {
+            let mut wire: Vec<EdgeIndex> = sides.iter().map(|(e, _)| *e)
+                .filter(|e| self.edge(*e).faces().is_empty())
+                .collect();
+            // highest first, so the edge moved into a freed index is never
+            // one still to be removed.
+            wire.sort_by(|a, b| b.cmp(a));
+            for e in wire {
+                self.detach_edge(e);
+            }
+        }
+        for (_, v) in sides {
+            self.anchor_to_boundary(v);
+        }
+        if index == last {
+            return None;
+        }
+        return Some(last);
+    }
+
+    // Removes a face, leaving its edges in place. The last face takes over
+    // its index.
+    fn detach_face(&mut self, f: FaceIndex) {
+        let sides: Vec<(EdgeIndex, VertexIndex)> = self.face_sides(f);
+        for (e, v) in sides {
+            self.edges[e.index()].half_edge_for_vertex_mut(v).next_face_index = FaceIndex::INVALID;
+        }
+        let last = FaceIndex::new(self.faces.len() - 1);
+        if f != last {
+            for (e, v) in self.face_sides(last) {
+                self.edges[e.index()].half_edge_for_vertex_mut(v).next_face_index = f;
+            }
+        }
+        self.faces.swap_remove(f.index());
+    }
+
+    // Each edge of the face with the vertex it leaves in the face's order.
+    fn face_sides(&self, f: FaceIndex) -> Vec<(EdgeIndex, VertexIndex)> {
+        let face = self.face(f);
+        return face.edge_iter().map(|e| e.index()).zip(face.vertex_iter().map(|v| v.index())).collect();
+    }
+
+    // Removes an edge that has no faces. The last edge takes over its index.
+    fn detach_edge(&mut self, e: EdgeIndex) {
+        for half in 0..2 {
+            assert!(!self.edges[e.index()].half_edge[half].next_face_index.is_valid(),
+                    "detach_edge: edge {} still has a face", e);
+        }
+        self.unlink_edge(e);
+        self.swap_remove_edge(e);
+    }
+
+    // Takes the edge out of its endpoints' rings.
+    fn unlink_edge(&mut self, e: EdgeIndex) {
+        for half in 0..2 {
+            let v = self.edges[e.index()].half_edge[half].vertex_index;
+            let next = self.ring_next(e, v);
+            if next == e {
+                self.verts[v.index()].base_edge_index = EdgeIndex::INVALID;
+                continue;
+            }
+            self.link(self.ring_prev(e, v), next, v);
+            if self.verts[v.index()].base_edge_index == e {
+                self.verts[v.index()].base_edge_index = next;
+            }
+        }
+    }
+
+    // Drops an edge that no ring or face refers to, moving the last edge
+    // into its index.
+    fn swap_remove_edge(&mut self, e: EdgeIndex) {
+        let last = EdgeIndex::new(self.edges.len() - 1);
+        self.edges.swap_remove(e.index());
+        if !self.edge_flags.is_empty() {
+            self.edge_flags.swap_remove(e.index());
+        }
+        if e == last {
+            return;
+        }
+        for half in 0..2 {
+            let half_edge = &mut self.edges[e.index()].half_edge[half];
+            let v = half_edge.vertex_index;
+            let f = half_edge.next_face_index;
+            // a lone edge in its ring links to itself.
+            if half_edge.next_edge_index == last {
+                half_edge.next_edge_index = e;
+            }
+            if half_edge.prev_edge_index == last {
+                half_edge.prev_edge_index = e;
+            }
+            let (next, prev) = (half_edge.next_edge_index, half_edge.prev_edge_index);
+            self.edges[next.index()].half_edge_for_vertex_mut(v).prev_edge_index = e;
+            self.edges[prev.index()].half_edge_for_vertex_mut(v).next_edge_index = e;
+            if self.verts[v.index()].base_edge_index == last {
+                self.verts[v.index()].base_edge_index = e;
+            }
+            if f.is_valid() && self.faces[f.index()].base_edge_index == last {
+                self.faces[f.index()].base_edge_index = e;
+            }
+        }
+    }
+}
+
+/*
+ * Local edits
+ */
+impl<V, E, F> Mesh<V, E, F> {
+    // Whether collapse_edge(e) would leave every side of every edge with at
+    // most one face. Where the endpoints share a neighbour, their two edges
+    // to it merge, and that fails if both already have a face on the same
+    // side once the triangles on `e` are gone. Parallel edges between the
+    // endpoints can't be collapsed either, nor can an edge whose endpoints
+    // are both corners of some other face, which would pinch it in two.
+    pub fn is_collapsible(&self, e: EdgeIndex) -> bool {
+        assert!(self.is_valid_edge_index(e), "is_collapsible: invalid edge {}", e);
+        let (a, b) = (self.edges[e.index()].half_edge[0].vertex_index, self.edges[e.index()].half_edge[1].vertex_index);
+        let on_edge = self.edge(e).faces();
+        for f in self.vertex(a).face_iter().filter(|f| !on_edge.contains(&f.index())) {
+            if f.vertex_iter().any(|v| v.index() == b) {
+                return false;
+            }
+        }
+        let removed = self.collapsed_triangles(e);
+        let face = |x: EdgeIndex, v: VertexIndex| {
+            let f = self.face_at(x, v);
+            return f.is_valid() && !removed.contains(&f);
+        };
+        for x in self.vertex(b).edge_iter().map(|x| x.index()).filter(|x| *x != e) {
+            let c = self.other_vertex(x, b);
+            if c == a {
+                return false;
+            }
+            if let Some(y) = self.find_edge(a, c) {
+                if (face(x, b) && face(y, a)) || (face(x, c) && face(y, c)) {
+                    return false;
+                }
+            }
+        }
+        return true;
+    }
+
+    // Merges the endpoints of `e` into one vertex, which keeps the data of
+    // the edge's first vertex (move it afterwards, e.g. to the midpoint).
+    // Triangles on `e` are removed, larger faces lose a side, and edges to
+    // a shared neighbour are merged into one that keeps the first vertex's
+    // edge data and both edges' flags. Returns the merged vertex's index,
+    // which may have moved since the other endpoint is removed. Removed
+    // edges and faces are swap-removed too, so other edge and face indices
+    // can move as well.
+    pub fn collapse_edge(&mut self, e: EdgeIndex) -> VertexIndex {
+        assert!(self.is_collapsible(e), "collapse_edge: edge {} isn't collapsible", e);
+        let (a, b) = (self.edges[e.index()].half_edge[0].vertex_index, self.edges[e.index()].half_edge[1].vertex_index);
+
+        for f in self.edge(e).faces() {
+            if self.faces[f.index()].base_edge_index == e {
+                let next = self.face(f).edge_iter().map(|x| x.index()).find(|x| *x != e).unwrap();
+                self.faces[f.index()].base_edge_index = next;
+            }
+        }
+        while let Some(f) = self.collapsed_triangles(e).first() {
+            self.detach_face(*f);
+        }
+
+        // Edges from b to a's neighbours hand their faces to a's edges.
+        let ring: Vec<EdgeIndex> = self.vertex(b).edge_iter().map(|x| x.index()).filter(|x| *x != e).collect();
+        let mut dropped = vec![e];
+        for x in ring.iter() {
+            let c = self.other_vertex(*x, b);
+            if let Some(y) = self.find_edge(a, c) {
+                for (from, to) in [(b, a), (c, c)] {
+                    let f = self.face_at(*x, from);
+                    if f.is_valid() {
+                        self.edges[y.index()].half_edge_for_vertex_mut(to).next_face_index = f;
+                        self.edges[x.index()].half_edge_for_vertex_mut(from).next_face_index = FaceIndex::INVALID;
+                        if self.faces[f.index()].base_edge_index == *x {
+                            self.faces[f.index()].base_edge_index = y;
+                        }
+                    }
+                }
+                self.insert_edge_flags(y, self.edge_flags(*x));
+                dropped.push(*x);
+            }
+        }
+
+        // Splice b's ring into a's where e was, which keeps both in
+        // rotational order.
+        let (prev_a, next_a) = (self.ring_prev(e, a), self.ring_next(e, a));
+        let (prev_b, next_b) = (self.ring_prev(e, b), self.ring_next(e, b));
+        for x in ring.iter() {
+            self.edges[x.index()].half_edge_for_vertex_mut(b).vertex_index = a;
+        }
+        self.verts[b.index()].base_edge_index = EdgeIndex::INVALID;
+        if next_a == e && next_b == e {
+            self.verts[a.index()].base_edge_index = EdgeIndex::INVALID;
+        } else if next_a == e {
+            self.link(prev_b, next_b, a);
+            self.verts[a.index()].base_edge_index = next_b;
+        } else if next_b == e {
+            self.link(prev_a, next_a, a);
+            self.verts[a.index()].base_edge_index = next_a;
+        } else {
+            self.link(prev_a, next_b, a);
+            self.link(prev_b, next_a, a);
+            self.verts[a.index()].base_edge_index = next_a;
+        }
+
+        for x in dropped[1..].iter() {
+            self.unlink_edge(*x);
+        }
+        // highest first, so no edge still to be dropped is moved.
+        dropped.sort_by(|x, y| y.cmp(x));
+        for x in dropped {
+            self.swap_remove_edge(x);
+        }
+        let merged = if self.remove_vertex(b) == Some(a) { b } else { a };
+        let neighbors: Vec<VertexIndex> = self.vertex(merged).vertex_iter().map(|v| v.index()).collect();
+        for v in neighbors {
+            self.anchor_to_boundary(v);
+        }
+        self.anchor_to_boundary(merged);
+        return merged;
+    }
+
+    // Whether `e` is shared by two triangles whose far corners aren't
+    // joined already, so flip_edge(e) can turn it to join them.
+    pub fn is_flippable(&self, e: EdgeIndex) -> bool {
+        assert!(self.is_valid_edge_index(e), "is_flippable: invalid edge {}", e);
+        let faces = self.edge(e).faces();
+        if faces.len() != 2 || faces.iter().any(|f| self.face(*f).vertex_iter().count() != 3) {
+            return false;
+        }
+        let (c, d) = (self.opposite_corner(faces[0], e), self.opposite_corner(faces[1], e));
+        return c != d && self.find_edge(c, d).is_none();
+    }
+
+    // Turns the edge between two triangles a b c and b a d to join c and d
+    // instead, leaving the triangles c a d and d b c. The edge keeps its
+    // index, data and flags, and the faces their indices and data.
+    pub fn flip_edge(&mut self, e: EdgeIndex) {
+        assert!(self.is_flippable(e), "flip_edge: edge {} isn't between two triangles that can be flipped", e);
+        let (a, b) = (self.edges[e.index()].half_edge[0].vertex_index, self.edges[e.index()].half_edge[1].vertex_index);
+        let (f1, f2) = (self.face_at(e, a), self.face_at(e, b));
+        let (c, d) = (self.opposite_corner(f1, e), self.opposite_corner(f2, e));
+        // b -> c and c -> a from f1, a -> d and d -> b from f2.
+        let side = |mesh: &Self, f: FaceIndex, from: VertexIndex| {
+            return mesh.face_sides(f).into_iter().find(|s| s.1 == from).unwrap().0;
+        };
+        let (bc, ca) = (side(self, f1, b), side(self, f1, c));
+        let (ad, db) = (side(self, f2, a), side(self, f2, d));
+
+        self.unlink_edge(e);
+        for (half, v, after) in [(0, c, ca), (1, d, db)] {
+            let next = self.ring_next(after, v);
+            let half_edge = &mut self.edges[e.index()].half_edge[half];
+            half_edge.vertex_index = v;
+            half_edge.next_face_index = if v == c { f2 } else { f1 };
+            self.link(after, e, v);
+            self.link(e, next, v);
+        }
+        self.edges[ad.index()].half_edge_for_vertex_mut(a).next_face_index = f1;
+        self.edges[bc.index()].half_edge_for_vertex_mut(b).next_face_index = f2;
+        self.faces[f1.index()].base_edge_index = ca;
+        self.faces[f2.index()].base_edge_index = db;
+        for v in [a, b, c, d] {
+            self.anchor_to_boundary(v);
+        }
+    }
+
+    // The vertex of triangle `f` that isn't on `e`.
+    fn opposite_corner(&self, f: FaceIndex, e: EdgeIndex) -> VertexIndex {
+        let ends = self.edge(e).vertices();
+        return self.face(f).vertex_iter().map(|v| v.index()).find(|v| !ends.contains(v)).unwrap();
+    }
+
+    // The triangles on `e`, which collapsing it would flatten.
+    fn collapsed_triangles(&self, e: EdgeIndex) -> Vec<FaceIndex> {
+        return self.edge(e).faces().into_iter().filter(|f| self.face(*f).vertex_iter().count() == 3).collect();
+    }
+}
+
+/*
+ * Checkpoints
+ */
+
+// The saved state of part of a mesh; see Mesh::checkpoint_region().
+pub struct RegionCheckpoint<V, E, F> {
+    vertices: Vec<(VertexIndex, VertexInfo<EdgeIndex, V>)>,
+    edges: Vec<(EdgeIndex, EdgeInfo<E>, EdgeFlags)>,
+    faces: Vec<(FaceIndex, FaceInfo<F>)>,
+    counts: (usize, usize, usize),
+    // how many of the saved vertices and edges make up the region itself.
+    region_counts: (usize, usize),
+}
+
+impl<V, E, F> RegionCheckpoint<V, E, F> {
+    pub fn vertex_count(&self) -> usize {
+        return self.region_counts.0;
+    }
+
+    pub fn edge_count(&self) -> usize {
+        return self.region_counts.1;
+    }
+}
+
+impl<V: Clone, E: Clone, F: Clone> Mesh<V, E, F> {
+    // Saves `vertices` and their one-ring for a speculative local edit: the
+    // neighbouring vertices, every edge incident to any of them (which holds
+    // their ring links) and the faces on those edges. restore() puts those
+    // back and drops anything added since, which is far cheaper than cloning
+    // the mesh when the edit is rejected.
+    //
+    // Edits between the checkpoint and restore() must stay inside the saved
+    // region, e.g. flipping or collapsing an edge whose endpoints were given.
+    // They may remove elements of the region: removal moves the last element
+    // of its kind into the freed index, so as many elements as the region
+    // has from the end of each list are saved too, with their surroundings.
+    pub fn checkpoint_region(&self, vertices: &[VertexIndex]) -> RegionCheckpoint<V, E, F> {
+        for v in vertices {
+            assert!(self.is_valid_vertex_index(*v), "checkpoint_region: invalid vertex {}", v);
+        }
+        let (region_vertices, region_edges, region_faces) = self.region_around(vertices.to_vec());
+        let tail = |len: usize, count: usize| len - count.min(len)..len;
+        let mut seeds = vertices.to_vec();
+        seeds.extend(tail(self.verts.len(), region_vertices.len()).map(VertexIndex::new));
+        for e in tail(self.edges.len(), region_edges.len()) {
+            seeds.extend(self.edge(EdgeIndex::new(e)).vertices());
+        }
+        for f in tail(self.faces.len(), region_faces.len()) {
+            seeds.extend(self.face(FaceIndex::new(f)).vertex_iter().map(|v| v.index()));
+        }
+        let (saved_vertices, saved_edges, saved_faces) = self.region_around(seeds);
+
+        return RegionCheckpoint {
+            vertices: saved_vertices.into_iter().map(|v| (v, self.verts[v.index()].clone())).collect(),
+            edges: saved_edges.into_iter()
+                .map(|e| (e, self.edges[e.index()].clone(), self.edge_flags(e)))
+                .collect(),
+            faces: saved_faces.into_iter().map(|f| (f, self.faces[f.index()].clone())).collect(),
+            counts: (self.verts.len(), self.edges.len(), self.faces.len()),
+            region_counts: (region_vertices.len(), region_edges.len()),
+        };
+    }
+
+    // Rolls the region back to the checkpoint, removing every element added
+    // after it and putting back any removed from the region.
+    pub fn restore(&mut self, checkpoint: RegionCheckpoint<V, E, F>) {
+        let (vertex_count, edge_count, face_count) = checkpoint.counts;
+        self.verts.truncate(vertex_count);
+        self.edges.truncate(edge_count);
+        self.faces.truncate(face_count);
+        // saved entries are in index order, so removed ones are pushed back
+        // in order.
+        for (v, info) in checkpoint.vertices {
+            restore_slot(&mut self.verts, v.index(), info);
+        }
+        for (f, info) in checkpoint.faces {
+            restore_slot(&mut self.faces, f.index(), info);
+        }
+        let mut flags = Vec::new();
+        for (e, info, saved_flags) in checkpoint.edges {
+            restore_slot(&mut self.edges, e.index(), info);
+            flags.push((e, saved_flags));
+        }
+        assert!(self.verts.len() == vertex_count && self.edges.len() == edge_count
+                && self.faces.len() == face_count, "restore: elements were removed from outside the checkpoint");
+        if !self.edge_flags.is_empty() {
+            self.edge_flags.resize(edge_count, EdgeFlags::NONE);
+        }
+        for (e, saved_flags) in flags {
+            self.set_edge_flags(e, saved_flags);
+        }
+    }
+
+    // `seeds` and their neighbours, every edge incident to any of them and
+    // the faces on those edges, each sorted.
+    fn region_around(&self, seeds: Vec<VertexIndex>) -> (Vec<VertexIndex>, Vec<EdgeIndex>, Vec<FaceIndex>) {
+        let mut vertices: Vec<VertexIndex> = Vec::new();
+        for v in seeds {
+            vertices.push(v);
+            vertices.extend(self.vertex(v).vertex_iter().map(|n| n.index()));
+        }
+        vertices.sort();
+        vertices.dedup();
+
+        let mut edges: Vec<EdgeIndex> = vertices.iter()
+            .flat_map(|v| self.vertex(*v).edge_iter().map(|e| e.index()))
+            .collect();
+        edges.sort();
+        edges.dedup();
+
+        let mut faces: Vec<FaceIndex> = edges.iter()
+            .flat_map(|e| self.edge(*e).faces())
+            .collect();
+        faces.sort();
+        faces.dedup();
+        return (vertices, edges, faces);
+    }
+}
+
+// Overwrites items[i], or pushes the item if it is the next one.
+fn restore_slot<T>(items: &mut Vec<T>, i: usize, item: T) {
+    if i < items.len() {
+        items[i] = item;
+    } else {
+        assert!(i == items.len(), "restore: elements were removed from outside the checkpoint");
+        items.push(item);
+    }
+}
+
+/*
+ * Debug export
+ */
+impl<V, E, F> Mesh<V, E, F> {
+    // The connectivity as JSON, for golden-file tests. Payloads are left out.
+    // Elements appear in index order, one per line, and absent links are
+    // null, so the output only changes where the structure does.
+    pub fn to_debug_json(&self) -> String {
+        let vertices: Vec<String> = self.verts.iter()
+            .map(|v| format!("{{\"base_edge\": {}}}", json_index(v.base_edge_index)))
+            .collect();
+        let edges: Vec<String> = self.edges.iter().enumerate().map(|(i, e)| {
+            let half_edges: Vec<String> = e.half_edge.iter().map(|h| format!(
+                "{{\"vertex\": {}, \"next_face\": {}, \"next_edge\": {}, \"prev_edge\": {}}}",
+                json_index(h.vertex_index), json_index(h.next_face_index),
+                json_index(h.next_edge_index), json_index(h.prev_edge_index))).collect();
+            return format!("{{\"half_edges\": [{}], \"flags\": {}}}",
+                           half_edges.join(", "), self.edge_flags(EdgeIndex::new(i)).bits());
+        }).collect();
+        let faces: Vec<String> = self.faces.iter()
+            .map(|f| format!("{{\"base_edge\": {}}}", json_index(f.base_edge_index)))
+            .collect();
+        return format!("{{\n  \"vertices\": {},\n  \"edges\": {},\n  \"faces\": {}\n}}\n",
+                       json_array(&vertices), json_array(&edges), json_array(&faces));
+    }
+}
+
+fn json_index<I: IndexType + std::fmt::Display>(index: I) -> String {
+    if index.is_valid() {
+        return index.to_string();
+    }
+    return "null".to_string();
+}
+
+fn json_array(items: &[String]) -> String {
+    if items.is_empty() {
+        return "[]".to_string();
+    }
+    return format!("[\n    {}\n  ]", items.join(",\n    "));
+}
+
+// Moves items[order[i]] to position i.
+fn permute<T, I: IndexType>(items: Vec<T>, order: &[I]) -> Vec<T> {
+    let mut slots: Vec<Option<T>> = items.into_iter().map(Some).collect();
+    return order.iter().map(|i| slots[i.index()].take().unwrap()).collect();
+}
+
+fn inverse_permutation<I: IndexType>(order: &[I]) -> Vec<I> {
+    let mut ret = vec![I::default(); order.len()];
+    for (i, old) in order.iter().enumerate() {
+        ret[old.index()] = I::new(i);
+    }
+    return ret;
+}
+
+// The mesh iterators walk a half-open [front, back) index range, so they
+// know their exact length and can be consumed from either end.
+pub struct MeshVertexIterator<'a, V, E, F> {
+    mesh: &'a Mesh<V, E, F>,
+    vertex_index: Index,
+    end_index: Index,
+}
+
+impl_clone!(MeshVertexIterator { mesh, vertex_index, end_index });
+
+impl<'a, V, E, F> Iterator for MeshVertexIterator<'a, V, E, F> {
+    type Item = VertexRef<'a, V, E, F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.vertex_index < self.end_index {
+            let ret = Some(VertexRef {
+                mesh: self.mesh,
+                vertex_index: VertexIndex(self.vertex_index)
+            });
+            self.vertex_index += 1;
+            return ret;
+        }
+        return None;
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end_index - self.vertex_index) as usize;
+        return (len, Some(len));
+    }
+}
+
+impl<'a, V, E, F> DoubleEndedIterator for MeshVertexIterator<'a, V, E, F> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.vertex_index < self.end_index {
+            self.end_index -= 1;
+            return Some(VertexRef { mesh: self.mesh, vertex_index: VertexIndex(self.end_index) });
+        }
+        return None;
+    }
+}
+
+impl<'a, V, E, F> ExactSizeIterator for MeshVertexIterator<'a, V, E, F> {}
+impl<'a, V, E, F> std::iter::FusedIterator for MeshVertexIterator<'a, V, E, F> {}
+
+pub struct MeshEdgeIterator<'a, V, E, F> {
+    mesh: &'a Mesh<V, E, F>,
+    edge_index: Index,
+    end_index: Index,
+}
+
+impl_clone!(MeshEdgeIterator { mesh, edge_index, end_index });
+
+impl<'a, V, E, F> Iterator for MeshEdgeIterator<'a, V, E, F> {
+    type Item = EdgeRef<'a, V, E, F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.edge_index < self.end_index {
+            let ret = Some(EdgeRef::new(self.mesh, EdgeIndex(self.edge_index)));
+            self.edge_index += 1;
+            return ret;
+        }
+        return None;
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end_index - self.edge_index) as usize;
+        return (len, Some(len));
+    }
+}
+
+impl<'a, V, E, F> DoubleEndedIterator for MeshEdgeIterator<'a, V, E, F> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.edge_index < self.end_index {
+            self.end_index -= 1;
+            return Some(EdgeRef::new(self.mesh, EdgeIndex(self.end_index)));
+        }
+        return None;
+    }
+}
+
+impl<'a, V, E, F> ExactSizeIterator for MeshEdgeIterator<'a, V, E, F> {}
+impl<'a, V, E, F> std::iter::FusedIterator for MeshEdgeIterator<'a, V, E, F> {}
+
+pub struct MeshFaceIterator<'a, V, E, F> {
+    mesh: &'a Mesh<V, E, F>,
+    face_index: Index,
+    end_index: Index,
+}
+
+impl_clone!(MeshFaceIterator { mesh, face_index, end_index });
+
+impl<'a, V, E, F> Iterator for MeshFaceIterator<'a, V, E, F> {
+    type Item = FaceRef<'a, V, E, F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.face_index < self.end_index {
+            let ret = Some(FaceRef { mesh: self.mesh, face_index: FaceIndex(self.face_index) });
+            self.face_index += 1;
+            return ret;
+        }
+        return None;
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end_index - self.face_index) as usize;
+        return (len, Some(len));
+    }
+}
+
+impl<'a, V, E, F> DoubleEndedIterator for MeshFaceIterator<'a, V, E, F> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.face_index < self.end_index {
+            self.end_index -= 1;
+            return Some(FaceRef { mesh: self.mesh, face_index: FaceIndex(self.end_index) });
+        }
+        return None;
+    }
+}
+
+impl<'a, V, E, F> ExactSizeIterator for MeshFaceIterator<'a, V, E, F> {}
+impl<'a, V, E, F> std::iter::FusedIterator for MeshFaceIterator<'a, V, E, F> {}
+
+pub struct MeshVertexDataIterator<'a, V> {
+    iter: std::iter::Enumerate<std::slice::Iter<'a, VertexInfo<EdgeIndex, V>>>,
+}
+
+impl<'a, V> Clone for MeshVertexDataIterator<'a, V> {
+    fn clone(&self) -> Self {
+        return MeshVertexDataIterator { iter: self.iter.clone() };
+    }
+}
+
+impl<'a, V> Iterator for MeshVertexDataIterator<'a, V> {
+    type Item = (VertexIndex, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.iter.next().map(|(i, info)| (IndexType::new(i), &info.data));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.iter.size_hint();
+    }
+}
+
+impl<'a, V> DoubleEndedIterator for MeshVertexDataIterator<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        return self.iter.next_back().map(|(i, info)| (IndexType::new(i), &info.data));
+    }
+}
+
+impl<'a, V> ExactSizeIterator for MeshVertexDataIterator<'a, V> {}
+impl<'a, V> std::iter::FusedIterator for MeshVertexDataIterator<'a, V> {}
+
+pub struct MeshEdgeDataIterator<'a, E> {
+    iter: std::iter::Enumerate<std::slice::Iter<'a, EdgeInfo<E>>>,
+}
+
+impl<'a, E> Clone for MeshEdgeDataIterator<'a, E> {
+    fn clone(&self) -> Self {
+        return MeshEdgeDataIterator { iter: self.iter.clone() };
+    }
+}
+
+impl<'a, E> Iterator for MeshEdgeDataIterator<'a, E> {
+    type Item = (EdgeIndex, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.iter.next().map(|(i, info)| (IndexType::new(i), &info.data));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.iter.size_hint();
+    }
+}
+
+impl<'a, E> DoubleEndedIterator for MeshEdgeDataIterator<'a, E> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        return self.iter.next_back().map(|(i, info)| (IndexType::new(i), &info.data));
+    }
+}
+
+impl<'a, E> ExactSizeIterator for MeshEdgeDataIterator<'a, E> {}
+impl<'a, E> std::iter::FusedIterator for MeshEdgeDataIterator<'a, E> {}
+
+pub struct MeshFaceDataIterator<'a, F> {
+    iter: std::iter::Enumerate<std::slice::Iter<'a, FaceInfo<F>>>,
+}
+
+impl<'a, F> Clone for MeshFaceDataIterator<'a, F> {
+    fn clone(&self) -> Self {
+        return MeshFaceDataIterator { iter: self.iter.clone() };
+    }
+}
+
+impl<'a, F> Iterator for MeshFaceDataIterator<'a, F> {
+    type Item = (FaceIndex, &'a F);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.iter.next().map(|(i, info)| (IndexType::new(i), &info.data));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.iter.size_hint();
+    }
+}
+
+impl<'a, F> DoubleEndedIterator for MeshFaceDataIterator<'a, F> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        return self.iter.next_back().map(|(i, info)| (IndexType::new(i), &info.data));
+    }
+}
+
+impl<'a, F> ExactSizeIterator for MeshFaceDataIterator<'a, F> {}
+impl<'a, F> std::iter::FusedIterator for MeshFaceDataIterator<'a, F> {}
+
+/*
+ * Views
+ */
+pub struct Vertices<'a, V, E, F> {
+    mesh: &'a Mesh<V, E, F>,
+}
+
+impl_clone!(Copy for Vertices);
+
+impl<'a, V, E, F> Vertices<'a, V, E, F> {
+    pub fn len(&self) -> usize {
+        return self.mesh.verts.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.mesh.verts.is_empty();
+    }
+
+    pub fn get(&self, index: VertexIndex) -> Option<VertexRef<'a, V, E, F>> {
+        if self.mesh.is_valid_vertex_index(index) {
+            return Some(self.mesh.vertex(index));
+        }
+        return None;
+    }
+
+    pub fn iter(&self) -> MeshVertexIterator<'a, V, E, F> {
+        return self.mesh.vertex_iter();
+    }
+}
+
+impl<'a, V, E, F> IntoIterator for Vertices<'a, V, E, F> {
+    type Item = VertexRef<'a, V, E, F>;
+    type IntoIter = MeshVertexIterator<'a, V, E, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+impl<'a, V, E, F> IntoIterator for &Vertices<'a, V, E, F> {
+    type Item = VertexRef<'a, V, E, F>;
+    type IntoIter = MeshVertexIterator<'a, V, E, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+impl<'a, V, E, F> std::ops::Index<VertexIndex> for Vertices<'a, V, E, F> {
+    type Output = V;
+
+    fn index(&self, index: VertexIndex) -> &V {
+        assert!(self.mesh.is_valid_vertex_index(index), "invalid vertex index {}", index);
+        return &self.mesh.verts[index.index()].data;
+    }
+}
+
+pub struct Edges<'a, V, E, F> {
+    mesh: &'a Mesh<V, E, F>,
+}
+
+impl_clone!(Copy for Edges);
+
+impl<'a, V, E, F> Edges<'a, V, E, F> {
+    pub fn len(&self) -> usize {
+        return self.mesh.edges.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.mesh.edges.is_empty();
+    }
+
+    pub fn get(&self, index: EdgeIndex) -> Option<EdgeRef<'a, V, E, F>> {
+        if self.mesh.is_valid_edge_index(index) {
+            return Some(self.mesh.edge(index));
+        }
+        return None;
+    }
+
+    pub fn iter(&self) -> MeshEdgeIterator<'a, V, E, F> {
+        return self.mesh.edge_iter();
+    }
+}
+
+impl<'a, V, E, F> IntoIterator for Edges<'a, V, E, F> {
+    type Item = EdgeRef<'a, V, E, F>;
+    type IntoIter = MeshEdgeIterator<'a, V, E, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+impl<'a, V, E, F> IntoIterator for &Edges<'a, V, E, F> {
+    type Item = EdgeRef<'a, V, E, F>;
+    type IntoIter = MeshEdgeIterator<'a, V, E, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+impl<'a, V, E, F> std::ops::Index<EdgeIndex> for Edges<'a, V, E, F> {
+    type Output = E;
+
+    fn index(&self, index: EdgeIndex) -> &E {
+        assert!(self.mesh.is_valid_edge_index(index), "invalid edge index {}", index);
+        return &self.mesh.edges[index.index()].data;
+    }
+}
+
+pub struct Faces<'a, V, E, F> {
+    mesh: &'a Mesh<V, E, F>,
+}
+
+impl_clone!(Copy for Faces);
+
+impl<'a, V, E, F> Faces<'a, V, E, F> {
+    pub fn len(&self) -> usize {
+        return self.mesh.faces.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.mesh.faces.is_empty();
+    }
+
+    pub fn get(&self, index: FaceIndex) -> Option<FaceRef<'a, V, E, F>> {
+        if self.mesh.is_valid_face_index(index) {
+            return Some(self.mesh.face(index));
+        }
+        return None;
+    }
+
+    pub fn iter(&self) -> MeshFaceIterator<'a, V, E, F> {
+        return self.mesh.face_iter();
+    }
+}
+
+impl<'a, V, E, F> IntoIterator for Faces<'a, V, E, F> {
+    type Item = FaceRef<'a, V, E, F>;
+    type IntoIter = MeshFaceIterator<'a, V, E, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+impl<'a, V, E, F> IntoIterator for &Faces<'a, V, E, F> {
+    type Item = FaceRef<'a, V, E, F>;
+    type IntoIter = MeshFaceIterator<'a, V, E, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+impl<'a, V, E, F> std::ops::Index<FaceIndex> for Faces<'a, V, E, F> {
+    type Output = F;
+
+    fn index(&self, index: FaceIndex) -> &F {
+        assert!(self.mesh.is_valid_face_index(index), "invalid face index {}", index);
+        return &self.mesh.faces[index.index()].data;
+    }
+}
+
+// mesh[v], mesh[e] and mesh[f] read and write payloads directly.
+impl<V, E, F> std::ops::Index<VertexIndex> for Mesh<V, E, F> {
+    type Output = V;
+
+    fn index(&self, index: VertexIndex) -> &V {
+        assert!(self.is_valid_vertex_index(index), "invalid vertex index {}", index);
+        return &self.verts[index.index()].data;
+    }
+}
+
+impl<V, E, F> std::ops::IndexMut<VertexIndex> for Mesh<V, E, F> {
+    fn index_mut(&mut self, index: VertexIndex) -> &mut V {
+        assert!(self.is_valid_vertex_index(index), "invalid vertex index {}", index);
+        return &mut self.verts[index.index()].data;
+    }
+}
+
+impl<V, E, F> std::ops::Index<EdgeIndex> for Mesh<V, E, F> {
+    type Output = E;
+
+    fn index(&self, index: EdgeIndex) -> &E {
+        assert!(self.is_valid_edge_index(index), "invalid edge index {}", index);
+        return &self.edges[index.index()].data;
+    }
+}
+
+impl<V, E, F> std::ops::IndexMut<EdgeIndex> for Mesh<V, E, F> {
+    fn index_mut(&mut self, index: EdgeIndex) -> &mut E {
+        assert!(self.is_valid_edge_index(index), "invalid edge index {}", index);
+        return &mut self.edges[index.index()].data;
+    }
+}
+
+impl<V, E, F> std::ops::Index<FaceIndex> for Mesh<V, E, F> {
+    type Output = F;
+
+    fn index(&self, index: FaceIndex) -> &F {
+        assert!(self.is_valid_face_index(index), "invalid face index {}", index);
+        return &self.faces[index.index()].data;
+    }
+}
+
+impl<V, E, F> std::ops::IndexMut<FaceIndex> for Mesh<V, E, F> {
+    fn index_mut(&mut self, index: FaceIndex) -> &mut F {
+        assert!(self.is_valid_face_index(index), "invalid face index {}", index);
+        return &mut self.faces[index.index()].data;
+    }
+}
+
+pub type ZipVertexIterator<'a, 'b, V, E, F, T> =
+    std::iter::Zip<MeshVertexIterator<'a, V, E, F>, std::slice::Iter<'b, T>>;
+pub type ZipVertexIteratorMut<'a, 'b, V, E, F, T> =
+    std::iter::Zip<MeshVertexIterator<'a, V, E, F>, std::slice::IterMut<'b, T>>;
+
+// A vertex view paired with an external slice of the same length.
+pub struct ZipVertices<'a, 'b, V, E, F, T> {
+    mesh: &'a Mesh<V, E, F>,
+    data: &'b [T],
+}
+
+impl<'a, 'b, V, E, F, T> Copy for ZipVertices<'a, 'b, V, E, F, T> {}
+
+impl<'a, 'b, V, E, F, T> Clone for ZipVertices<'a, 'b, V, E, F, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, 'b, V, E, F, T> ZipVertices<'a, 'b, V, E, F, T> {
+    pub fn len(&self) -> usize {
+        return self.data.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.data.is_empty();
+    }
+
+    pub fn get(&self, index: VertexIndex) -> Option<(VertexRef<'a, V, E, F>, &'b T)> {
+        if self.mesh.is_valid_vertex_index(index) {
+            return Some((self.mesh.vertex(index), &self.data[index.index()]));
+        }
+        return None;
+    }
+
+    pub fn iter(&self) -> ZipVertexIterator<'a, 'b, V, E, F, T> {
+        return self.mesh.vertex_iter().zip(self.data.iter());
+    }
+}
+
+impl<'a, 'b, V, E, F, T> IntoIterator for ZipVertices<'a, 'b, V, E, F, T> {
+    type Item = (VertexRef<'a, V, E, F>, &'b T);
+    type IntoIter = ZipVertexIterator<'a, 'b, V, E, F, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+impl<'a, 'b, V, E, F, T> IntoIterator for &ZipVertices<'a, 'b, V, E, F, T> {
+    type Item = (VertexRef<'a, V, E, F>, &'b T);
+    type IntoIter = ZipVertexIterator<'a, 'b, V, E, F, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+impl<'a, 'b, V, E, F, T> std::ops::Index<VertexIndex> for ZipVertices<'a, 'b, V, E, F, T> {
+    type Output = T;
+
+    fn index(&self, index: VertexIndex) -> &T {
+        assert!(self.mesh.is_valid_vertex_index(index), "invalid vertex index {}", index);
+        return &self.data[index.index()];
     }
 }
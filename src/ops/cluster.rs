@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use crate::edge_map::{EdgeKey, EdgeSet};
+use crate::geometry::*;
+use crate::index::*;
+use crate::mesh::Mesh;
+
+use super::context::{Cancelled, OpContext};
+
+// A simplified mesh and, for every source vertex, the vertex it became.
+pub type Merged<V, E, F> = (Mesh<V, E, F>, Vec<VertexIndex>);
+
+// Simplifies a mesh by snapping its vertices to a uniform grid and merging
+// every vertex that lands in the same cell. Each cluster keeps the payload of
+// its first vertex, moved to the average position of the cluster. Edges that
+// end up inside a single cluster are dropped and duplicates are merged,
+// keeping the first edge's payload. Faces are carried over as in
+// merge_faces(): those left with fewer than 3 clusters are dropped.
+//
+// This touches each vertex, edge and face once, so it stays usable on meshes
+// far too big for an edge collapse queue.
+//
+// Returns the simplified mesh and, for every source vertex, the index of the
+// vertex it was merged into.
+pub fn cluster_vertices<V, E, F>(mesh: &Mesh<V, E, F>, cell_size: f32)
+                                -> Merged<V, E, F>
+    where V: HasPosition + Clone, E: Clone, F: Clone {
+    // can't fail without a cancel token.
+    return cluster_vertices_with_context(mesh, cell_size, &mut OpContext::new()).unwrap();
+}
+
+pub fn cluster_vertices_with_context<V, E, F>(mesh: &Mesh<V, E, F>, cell_size: f32,
+                                             ctx: &mut OpContext)
+                                             -> Result<Merged<V, E, F>, Cancelled>
+    where V: HasPosition + Clone, E: Clone, F: Clone {
+    assert!(cell_size > 0.0, "cluster cell size must be positive");
+    op_span!("cluster_vertices", vertices = mesh.vertex_iter().len(),
+             edges = mesh.edge_iter().len(), cell_size = cell_size);
+
+    let mut cells: HashMap<(i32, i32, i32), VertexIndex> = HashMap::new();
+    let mut sums: Vec<(Vec3, f32)> = Vec::new();
+    let mut remap: Vec<VertexIndex> = Vec::new();
+    let mut ret: Mesh<V, E, F> = Mesh::new();
+
+    let total = mesh.vertex_iter().len();
+    for (i, v) in mesh.vertex_iter().enumerate() {
+        ctx.step(i, total, 0.0, 0.5)?;
+        let data = v.data().unwrap();
+        let p = data.position();
+        let cell = (
+            (p[0] / cell_size).floor() as i32,
+            (p[1] / cell_size).floor() as i32,
+            (p[2] / cell_size).floor() as i32,
+        );
+        let cluster = *cells.entry(cell).or_insert_with(|| {
+            sums.push(([0.0; 3], 0.0));
+            return ret.add_vertex(data.clone());
+        });
+        let sum = &mut sums[cluster.index()];
+        sum.0 = add(sum.0, p);
+        sum.1 += 1.0;
+        remap.push(cluster);
+    }
+
+    for (i, (sum, count)) in sums.iter().enumerate() {
+        ret.vertex_data_mut(VertexIndex::new(i)).unwrap().set_position(scale(*sum, 1.0 / count));
+    }
+
+    merge_edges(mesh, &remap, &mut ret, ctx)?;
+    merge_faces(mesh, &remap, &mut ret, ctx)?;
+    op_event!("clustered", vertices = ret.vertex_iter().len(), edges = ret.edge_iter().len(),
+              faces = ret.face_iter().len());
+    ctx.report(1.0);
+    return Ok((ret, remap));
+}
+
+// Adds the edges of `mesh` to `ret` through a vertex remap, dropping the ones
+// that collapse to a point and keeping the first of any duplicates.
+// This is the third quarter of the operation as far as progress goes.
+pub(super) fn merge_edges<V, E: Clone, F>(mesh: &Mesh<V, E, F>, remap: &[VertexIndex],
+                                          ret: &mut Mesh<V, E, F>, ctx: &mut OpContext)
+                                          -> Result<(), Cancelled> {
+    let mut seen = EdgeSet::default();
+    let total = mesh.edge_iter().len();
+    for (i, e) in mesh.edge_iter().enumerate() {
+        ctx.step(i, total, 0.5, 0.75)?;
+        let vertices = e.vertices();
+        let a = remap[vertices[0].index()];
+        let b = remap[vertices[1].index()];
+        if a == b {
+            continue;
+        }
+        if seen.insert(EdgeKey::new(a, b)) {
+            ret.add_edge(e.data().unwrap().clone(), a, b);
+        }
+    }
+    return Ok(());
+}
+
+// Adds the faces of `mesh` to `ret` through the same remap, after
+// merge_edges(). Runs of corners merged into one vertex shrink to a single
+// corner; faces left with fewer than 3 corners, pinched through a repeated
+// vertex, or overlapping an earlier face on one of their sides are dropped.
+// This is the last quarter of the operation as far as progress goes.
+pub(super) fn merge_faces<V, E, F: Clone>(mesh: &Mesh<V, E, F>, remap: &[VertexIndex],
+                                          ret: &mut Mesh<V, E, F>, ctx: &mut OpContext)
+                                          -> Result<(), Cancelled> {
+    let total = mesh.face_iter().len();
+    for (i, f) in mesh.face_iter().enumerate() {
+        ctx.step(i, total, 0.75, 1.0)?;
+        let mut vertices: Vec<VertexIndex> = f.vertex_iter().map(|v| remap[v.index().index()]).collect();
+        vertices.dedup();
+        while vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        if ret.can_add_face(&vertices) {
+            // every side is a source edge that survived merge_edges().
+            ret.add_face(f.data().clone(), &vertices);
+        }
+    }
+    return Ok(());
+}
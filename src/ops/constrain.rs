@@ -0,0 +1,173 @@
+use crate::geometry::*;
+use crate::index::*;
+use crate::mesh::Mesh;
+use crate::tolerance::Tolerance;
+
+// A geometric relation to keep between vertices while editing.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Constraint {
+    // The two vertices stay this far apart.
+    Length(VertexIndex, VertexIndex, f32),
+    // The vertices lie on one line.
+    Collinear(Vec<VertexIndex>),
+    // The vertices, in polygon order, lie in one plane.
+    Coplanar(Vec<VertexIndex>),
+}
+
+impl Constraint {
+    // Keeps the edge at its current length.
+    pub fn keep_length<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, edge: EdgeIndex) -> Constraint {
+        let vertices = mesh.edge(edge).vertices();
+        let (a, b) = (vertices[0], vertices[1]);
+        let length = distance(mesh.vertices()[a].position(), mesh.vertices()[b].position());
+        return Constraint::Length(a, b, length);
+    }
+}
+
+// Moves unpinned vertices until every constraint holds within `tolerance`,
+// projecting onto one constraint at a time as in position-based dynamics.
+// Pinned vertices, typically the ones just edited, never move, and lines
+// and planes are fitted through the pinned vertices of a constraint when it
+// has enough of them.
+//
+// Returns false if the constraints still don't hold after max_iterations
+// sweeps, e.g. because the pinned vertices contradict them. The mesh keeps
+// the best positions found either way.
+pub fn solve_constraints<V: HasPosition, E, F>(mesh: &mut Mesh<V, E, F>, constraints: &[Constraint],
+                                               pinned: &[VertexIndex], tolerance: &Tolerance,
+                                               max_iterations: usize) -> bool {
+    op_span!("solve_constraints", constraints = constraints.len(), pinned = pinned.len());
+    let mut positions: Vec<Vec3> = mesh.vertex_iter()
+        .map(|v| v.data().unwrap().position())
+        .collect();
+    let mut is_pinned = vec![false; positions.len()];
+    for v in pinned {
+        assert!(mesh.is_valid_vertex_index(*v), "solve_constraints: invalid pinned vertex");
+        is_pinned[v.index()] = true;
+    }
+
+    let mut converged = false;
+    for _ in 0..max_iterations {
+        let mut worst: f32 = 0.0;
+        for constraint in constraints {
+            worst = worst.max(project(constraint, &mut positions, &is_pinned));
+        }
+        if tolerance.is_zero(worst) {
+            converged = true;
+            break;
+        }
+    }
+    op_event!("solved", converged = converged);
+
+    for (i, position) in positions.into_iter().enumerate() {
+        mesh.vertex_data_mut(VertexIndex::new(i)).unwrap().set_position(position);
+    }
+    return converged;
+}
+
+// Projects the free vertices of one constraint and returns how far it was
+// from holding beforehand.
+fn project(constraint: &Constraint, positions: &mut [Vec3], is_pinned: &[bool]) -> f32 {
+    match constraint {
+        Constraint::Length(a, b, target) => {
+            let (a, b) = (a.index(), b.index());
+            let delta = sub(positions[b], positions[a]);
+            let current = length(delta);
+            let error = current - target;
+            let weight = |i: usize| if is_pinned[i] { 0.0 } else { 1.0 };
+            let weights = (weight(a), weight(b));
+            // coincident endpoints give no direction to push apart along.
+            if current == 0.0 || weights.0 + weights.1 == 0.0 {
+                return error.abs();
+            }
+            let correction = scale(delta, error / current / (weights.0 + weights.1));
+            positions[a] = add(positions[a], scale(correction, weights.0));
+            positions[b] = sub(positions[b], scale(correction, weights.1));
+            return error.abs();
+        }
+        Constraint::Collinear(vertices) => {
+            if vertices.len() < 3 {
+                return 0.0;
+            }
+            let (all, anchors) = gather(vertices, positions, is_pinned);
+            let origin = centroid(&anchors).unwrap();
+            let direction = if anchors.len() >= 2 {
+                principal_axis(&anchors, origin)
+            } else {
+                principal_axis(&all, origin)
+            };
+            let on_line = |p: Vec3| add(origin, scale(direction, dot(sub(p, origin), direction)));
+            return move_free(vertices, positions, is_pinned, on_line);
+        }
+        Constraint::Coplanar(vertices) => {
+            if vertices.len() < 4 {
+                return 0.0;
+            }
+            let (all, anchors) = gather(vertices, positions, is_pinned);
+            let origin = centroid(&anchors).unwrap();
+            let mut normal = if anchors.len() >= 3 { newell_normal(&anchors) } else { [0.0; 3] };
+            if normal == [0.0; 3] {
+                normal = newell_normal(&all);
+            }
+            if normal == [0.0; 3] {
+                return 0.0;
+            }
+            let normal = normalize(normal);
+            let on_plane = |p: Vec3| sub(p, scale(normal, dot(sub(p, origin), normal)));
+            return move_free(vertices, positions, is_pinned, on_plane);
+        }
+    }
+}
+
+// The positions of a constraint's vertices, and of the pinned ones among
+// them or all of them if none are pinned.
+fn gather(vertices: &[VertexIndex], positions: &[Vec3], is_pinned: &[bool]) -> (Vec<Vec3>, Vec<Vec3>) {
+    let all: Vec<Vec3> = vertices.iter().map(|v| positions[v.index()]).collect();
+    let pinned: Vec<Vec3> = vertices.iter()
+        .filter(|v| is_pinned[v.index()])
+        .map(|v| positions[v.index()])
+        .collect();
+    if pinned.is_empty() {
+        return (all.clone(), all);
+    }
+    return (all, pinned);
+}
+
+// Moves every free vertex to its target and returns the largest distance
+// any vertex, pinned or not, was from its target.
+fn move_free<T: Fn(Vec3) -> Vec3>(vertices: &[VertexIndex], positions: &mut [Vec3],
+                                  is_pinned: &[bool], target: T) -> f32 {
+    let mut ret: f32 = 0.0;
+    for v in vertices {
+        let p = target(positions[v.index()]);
+        ret = ret.max(distance(p, positions[v.index()]));
+        if !is_pinned[v.index()] {
+            positions[v.index()] = p;
+        }
+    }
+    return ret;
+}
+
+// The direction of greatest spread of the points around `origin`, by power
+// iteration on their covariance.
+fn principal_axis(points: &[Vec3], origin: Vec3) -> Vec3 {
+    let mut covariance = [[0.0f32; 3]; 3];
+    for p in points {
+        let d = sub(*p, origin);
+        for (i, row) in covariance.iter_mut().enumerate() {
+            *row = add(*row, scale(d, d[i]));
+        }
+    }
+    let mut ret = sub(points[points.len() - 1], points[0]);
+    if ret == [0.0; 3] {
+        ret = [1.0, 1.0, 1.0];
+    }
+    for _ in 0..32 {
+        let next = [dot(covariance[0], ret), dot(covariance[1], ret), dot(covariance[2], ret)];
+        if next == [0.0; 3] {
+            break;
+        }
+        ret = normalize(next);
+    }
+    return normalize(ret);
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// Returned by an operator that stopped because its context was cancelled.
+// Operators leave their input untouched when they return this, except the
+// ones that say otherwise, like greedy(), whose edits so far stay.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "operation cancelled");
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+// A flag shared between the thread running an operator and whoever may want
+// to stop it, e.g. a GUI cancel button.
+#[derive(Clone, Default, Debug)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        return CancelToken::default();
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        return self.cancelled.load(Ordering::Relaxed);
+    }
+}
+
+const STEP_INTERVAL: usize = 1024;
+
+// Progress reporting and cancellation for long-running operators. The
+// default context reports nowhere and is never cancelled.
+#[derive(Default)]
+pub struct OpContext<'a> {
+    progress: Option<Box<dyn FnMut(f32) + 'a>>,
+    cancel: Option<CancelToken>,
+}
+
+impl<'a> OpContext<'a> {
+    pub fn new() -> Self {
+        return OpContext::default();
+    }
+
+    // `progress` is called with the completed fraction, from 0 to 1.
+    pub fn with_progress<P: FnMut(f32) + 'a>(mut self, progress: P) -> Self {
+        self.progress = Some(Box::new(progress));
+        return self;
+    }
+
+    pub fn with_cancel(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        return self;
+    }
+
+    pub fn report(&mut self, fraction: f32) {
+        if let Some(progress) = self.progress.as_mut() {
+            progress(fraction.clamp(0.0, 1.0));
+        }
+    }
+
+    pub fn check(&self) -> Result<(), Cancelled> {
+        match self.cancel {
+            Some(ref token) if token.is_cancelled() => Err(Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    // For per-element loops: every STEP_INTERVAL items, checks for
+    // cancellation and reports `done` out of `total` mapped onto the
+    // [start, end] part of the whole operation.
+    pub(crate) fn step(&mut self, done: usize, total: usize, start: f32, end: f32)
+                       -> Result<(), Cancelled> {
+        if !done.is_multiple_of(STEP_INTERVAL) {
+            return Ok(());
+        }
+        self.check()?;
+        self.report(start + (end - start) * done as f32 / total.max(1) as f32);
+        return Ok(());
+    }
+}
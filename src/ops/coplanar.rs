@@ -0,0 +1,225 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::edge_map::{EdgeKey, EdgeMap};
+use crate::geometry::*;
+use crate::index::*;
+use crate::mesh::Mesh;
+
+use super::cluster::Merged;
+use super::context::{Cancelled, OpContext};
+
+// Dissolves the edges between coplanar faces, as left by CAD tessellation.
+//
+// Regions are grown from each face in turn over edges with two faces, taking
+// in faces whose normal is within `angle_tolerance` radians of the first
+// face's, so a gently curved surface doesn't merge bit by bit. A region that
+// is a disk becomes one polygon bounded by its outline, keeping the data of
+// its first face; other regions keep their faces. Then the outline vertices
+// left with just two neighbours, which bend by no more than `angle_tolerance`
+// there, are dropped too, and the side before each keeps its edge's data.
+//
+// Returns the simplified mesh and, for every source vertex, the index of the
+// vertex it became, or an invalid index if it was dissolved.
+pub fn merge_coplanar_faces<V, E, F>(mesh: &Mesh<V, E, F>, angle_tolerance: f32) -> Merged<V, E, F>
+    where V: HasPosition + Clone, E: Clone, F: Clone {
+    // can't fail without a cancel token.
+    return merge_coplanar_faces_with_context(mesh, angle_tolerance, &mut OpContext::new()).unwrap();
+}
+
+pub fn merge_coplanar_faces_with_context<V, E, F>(mesh: &Mesh<V, E, F>, angle_tolerance: f32,
+                                                  ctx: &mut OpContext)
+                                                  -> Result<Merged<V, E, F>, Cancelled>
+    where V: HasPosition + Clone, E: Clone, F: Clone {
+    op_span!("merge_coplanar_faces", faces = mesh.face_iter().len(), angle_tolerance = angle_tolerance);
+    let min_cos = angle_tolerance.cos();
+    let positions: Vec<Vec3> = mesh.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+    let corners: Vec<Vec<VertexIndex>> = mesh.face_iter()
+        .map(|f| f.vertex_iter().map(|v| v.index()).collect())
+        .collect();
+    let normals: Vec<Vec3> = corners.iter()
+        .map(|c| normalize(newell_normal(&c.iter().map(|v| positions[v.index()]).collect::<Vec<Vec3>>())))
+        .collect();
+    let side = |a: VertexIndex, b: VertexIndex| mesh.find_edge(a, b).unwrap();
+
+    // Each polygon is its corners, each with the source edge of the side
+    // that starts there, and the face whose data it keeps.
+    let mut polygons: Vec<(FaceIndex, Vec<(VertexIndex, EdgeIndex)>)> = Vec::new();
+    let mut visited = vec![false; corners.len()];
+    let total = corners.len();
+    for seed in 0..corners.len() {
+        ctx.step(seed, total, 0.0, 0.5)?;
+        if visited[seed] {
+            continue;
+        }
+        visited[seed] = true;
+        let mut faces = vec![seed];
+        // degenerate faces stay on their own.
+        let mut queue = VecDeque::new();
+        if normals[seed] != [0.0; 3] {
+            queue.push_back(seed);
+        }
+        while let Some(f) = queue.pop_front() {
+            for e in mesh.face(FaceIndex::new(f)).edge_iter() {
+                for g in e.faces() {
+                    let g = g.index();
+                    if !visited[g] && dot(normals[seed], normals[g]) >= min_cos {
+                        visited[g] = true;
+                        faces.push(g);
+                        queue.push_back(g);
+                    }
+                }
+            }
+        }
+        let outline = if faces.len() > 1 { disk_outline(&corners, &faces) } else { None };
+        match outline {
+            Some(outline) => {
+                let sides = outline.iter().enumerate()
+                    .map(|(i, a)| (*a, side(*a, outline[(i + 1) % outline.len()])))
+                    .collect();
+                polygons.push((FaceIndex::new(seed), sides));
+            }
+            None => {
+                for f in faces {
+                    let c = &corners[f];
+                    let sides = c.iter().enumerate().map(|(i, a)| (*a, side(*a, c[(i + 1) % c.len()]))).collect();
+                    polygons.push((FaceIndex::new(f), sides));
+                }
+            }
+        }
+    }
+
+    // Neighbours along polygon sides and wire edges, to find the outline
+    // vertices only two sides meet at.
+    let wires: Vec<EdgeIndex> = mesh.edge_iter().filter(|e| e.faces().is_empty()).map(|e| e.index()).collect();
+    let mut neighbors: Vec<BTreeSet<VertexIndex>> = vec![BTreeSet::new(); positions.len()];
+    let mut link = |a: VertexIndex, b: VertexIndex| {
+        neighbors[a.index()].insert(b);
+        neighbors[b.index()].insert(a);
+    };
+    for (_, sides) in polygons.iter() {
+        for (i, (a, _)) in sides.iter().enumerate() {
+            link(*a, sides[(i + 1) % sides.len()].0);
+        }
+    }
+    for e in wires.iter() {
+        let ends = mesh.edge(*e).vertices();
+        link(ends[0], ends[1]);
+    }
+    let mut containing: Vec<Vec<usize>> = vec![Vec::new(); positions.len()];
+    for (p, (_, sides)) in polygons.iter().enumerate() {
+        for (a, _) in sides.iter() {
+            containing[a.index()].push(p);
+        }
+    }
+    let mut dropped = vec![false; positions.len()];
+    for v in 0..positions.len() {
+        ctx.step(v, positions.len(), 0.5, 0.75)?;
+        if neighbors[v].len() != 2 || containing[v].is_empty() {
+            continue;
+        }
+        let (p, q) = (*neighbors[v].iter().next().unwrap(), *neighbors[v].iter().next_back().unwrap());
+        let bend = dot(normalize(sub(positions[v], positions[p.index()])), normalize(sub(positions[q.index()], positions[v])));
+        // joining p and q mustn't double up an edge or leave a polygon with
+        // less than three corners.
+        if bend < min_cos || neighbors[p.index()].contains(&q)
+            || containing[v].iter().any(|i| polygons[*i].1.len() <= 3) {
+            continue;
+        }
+        for i in containing[v].iter() {
+            polygons[*i].1.retain(|(a, _)| a.index() != v);
+        }
+        neighbors[p.index()].remove(&VertexIndex::new(v));
+        neighbors[q.index()].remove(&VertexIndex::new(v));
+        neighbors[p.index()].insert(q);
+        neighbors[q.index()].insert(p);
+        neighbors[v].clear();
+        dropped[v] = true;
+    }
+
+    // Vertices are kept in order, except those dissolved inside a polygon
+    // or dropped from an outline.
+    let mut used = vec![false; positions.len()];
+    for v in mesh.vertex_iter() {
+        used[v.index().index()] = v.edge_iter().next().is_none();
+    }
+    for (_, sides) in polygons.iter() {
+        for (a, _) in sides.iter() {
+            used[a.index()] = true;
+        }
+    }
+    for e in wires.iter() {
+        for v in mesh.edge(*e).vertices() {
+            used[v.index()] = true;
+        }
+    }
+    let mut ret: Mesh<V, E, F> = Mesh::new();
+    let mut remap = vec![<VertexIndex as IndexType>::max(); positions.len()];
+    for v in mesh.vertex_iter() {
+        let i = v.index().index();
+        if used[i] && !dropped[i] {
+            remap[i] = ret.add_vertex(v.data().unwrap().clone());
+        }
+    }
+    for e in wires.iter() {
+        let ends = mesh.edge(*e).vertices();
+        ret.add_edge(mesh.edge(*e).data().unwrap().clone(), remap[ends[0].index()], remap[ends[1].index()]);
+    }
+    let total = polygons.len();
+    for (i, (f, sides)) in polygons.iter().enumerate() {
+        ctx.step(i, total, 0.75, 1.0)?;
+        let mut sources: EdgeMap<EdgeIndex> = EdgeMap::default();
+        for (j, (a, e)) in sides.iter().enumerate() {
+            sources.insert(EdgeKey::new(remap[a.index()], remap[sides[(j + 1) % sides.len()].0.index()]), *e);
+        }
+        let vertices: Vec<VertexIndex> = sides.iter().map(|(a, _)| remap[a.index()]).collect();
+        ret.add_face_with(mesh.face(*f).data().clone(), &vertices, |a, b| {
+            return mesh.edge(sources[&EdgeKey::new(a, b)]).data().unwrap().clone();
+        });
+    }
+    op_event!("merged", faces = ret.face_iter().len(), vertices = ret.vertex_iter().len());
+    ctx.report(1.0);
+    return Ok((ret, remap));
+}
+
+// The outline of a region of faces, in their winding, if the region is a
+// disk: its outward sides form a single loop that doesn't touch itself, and
+// V - E + F = 1 over the region.
+fn disk_outline(corners: &[Vec<VertexIndex>], faces: &[usize]) -> Option<Vec<VertexIndex>> {
+    let mut inside: HashSet<(VertexIndex, VertexIndex)> = HashSet::new();
+    for f in faces.iter() {
+        let c = &corners[*f];
+        for (i, a) in c.iter().enumerate() {
+            inside.insert((*a, c[(i + 1) % c.len()]));
+        }
+    }
+    // a side is on the outline unless the region has it the other way too.
+    let mut next: HashMap<VertexIndex, VertexIndex> = HashMap::new();
+    let mut vertices: BTreeSet<VertexIndex> = BTreeSet::new();
+    for (a, b) in inside.iter() {
+        vertices.insert(*a);
+        if inside.contains(&(*b, *a)) {
+            continue;
+        }
+        if next.insert(*a, *b).is_some() {
+            return None;
+        }
+    }
+    let edges = next.len() + (inside.len() - next.len()) / 2;
+    if vertices.len() + faces.len() != edges + 1 {
+        return None;
+    }
+    let start = *next.keys().min()?;
+    let mut outline = vec![start];
+    let mut v = next[&start];
+    while v != start {
+        if outline.len() > next.len() {
+            return None;
+        }
+        outline.push(v);
+        v = *next.get(&v)?;
+    }
+    if outline.len() != next.len() {
+        return None;
+    }
+    return Some(outline);
+}
@@ -0,0 +1,135 @@
+use crate::geometry::*;
+use crate::index::*;
+use crate::mesh::Mesh;
+
+use super::context::{Cancelled, OpContext};
+
+// Moves the selected vertices onto the smoothest surface that interpolates
+// the rest of the mesh, which is held fixed.
+//
+// continuity_order 0 solves the membrane equation L x = 0, 1 the thin plate
+// equation L^2 x = 0 and 2 the minimum curvature variation equation
+// L^3 x = 0, where L is the uniform graph Laplacian. Higher orders blend
+// more smoothly into the fixed region but need a wider fixed rim around the
+// selection to be well posed.
+pub fn fair<V: HasPosition, E, F>(mesh: &mut Mesh<V, E, F>, selection: &[VertexIndex],
+                                  continuity_order: usize) {
+    // can't fail without a cancel token.
+    fair_with_context(mesh, selection, continuity_order, &mut OpContext::new()).unwrap();
+}
+
+// The mesh is only written once all three axes are solved, so a cancelled
+// call leaves it as it was.
+pub fn fair_with_context<V: HasPosition, E, F>(mesh: &mut Mesh<V, E, F>, selection: &[VertexIndex],
+                                               continuity_order: usize, ctx: &mut OpContext)
+                                               -> Result<(), Cancelled> {
+    op_span!("fair", vertices = mesh.vertex_iter().len(), selected = selection.len(),
+             continuity_order = continuity_order);
+    let neighbors: Vec<Vec<usize>> = mesh.vertex_iter()
+        .map(|v| v.vertex_iter().map(|n| n.index().index()).collect())
+        .collect();
+    let n = neighbors.len();
+
+    let mut is_free = vec![false; n];
+    for v in selection {
+        assert!(mesh.is_valid_vertex_index(*v), "fair: invalid vertex in selection");
+        is_free[v.index()] = true;
+    }
+    let free: Vec<usize> = (0..n).filter(|i| is_free[*i]).collect();
+    if free.is_empty() {
+        return Ok(());
+    }
+
+    let power = continuity_order + 1;
+    let laplacian = |x: &[f64]| -> Vec<f64> {
+        let mut ret = x.to_vec();
+        for _ in 0..power {
+            let src = ret.clone();
+            for (i, r) in ret.iter_mut().enumerate() {
+                *r = neighbors[i].len() as f64 * src[i]
+                    - neighbors[i].iter().map(|j| src[*j]).sum::<f64>();
+            }
+        }
+        return ret;
+    };
+
+    let mut positions: Vec<Vec3> = mesh.vertex_iter()
+        .map(|v| v.data().unwrap().position())
+        .collect();
+
+    for axis in 0..3 {
+        let mut full: Vec<f64> = positions.iter().map(|p| p[axis] as f64).collect();
+        let initial: Vec<f64> = free.iter().map(|i| full[*i]).collect();
+
+        // Move the fixed vertices' contribution to the right hand side.
+        for i in free.iter() {
+            full[*i] = 0.0;
+        }
+        let fixed_term = laplacian(&full);
+        let rhs: Vec<f64> = free.iter().map(|i| -fixed_term[*i]).collect();
+
+        // (L^k) restricted to the free vertices. It is symmetric positive
+        // definite as long as every free region touches a fixed vertex.
+        let apply = |x: &[f64]| -> Vec<f64> {
+            let mut embedded = vec![0.0; n];
+            for (k, i) in free.iter().enumerate() {
+                embedded[*i] = x[k];
+            }
+            let y = laplacian(&embedded);
+            return free.iter().map(|i| y[*i]).collect();
+        };
+
+        let progress = (axis as f32 / 3.0, (axis + 1) as f32 / 3.0);
+        let solution = conjugate_gradient(apply, &rhs, initial, 10 * free.len() + 100,
+                                          ctx, progress)?;
+        for (k, i) in free.iter().enumerate() {
+            positions[*i][axis] = solution[k] as f32;
+        }
+    }
+
+    for i in free {
+        mesh.vertex_data_mut(VertexIndex::new(i)).unwrap().set_position(positions[i]);
+    }
+    ctx.report(1.0);
+    return Ok(());
+}
+
+// Progress is reported against max_iterations, mapped onto `progress`.
+fn conjugate_gradient<A>(apply: A, b: &[f64], x0: Vec<f64>, max_iterations: usize,
+                         ctx: &mut OpContext, progress: (f32, f32)) -> Result<Vec<f64>, Cancelled>
+    where A: Fn(&[f64]) -> Vec<f64> {
+    let dot = |a: &[f64], b: &[f64]| -> f64 { a.iter().zip(b).map(|(x, y)| x * y).sum() };
+
+    let mut x = x0;
+    let ax = apply(&x);
+    let mut r: Vec<f64> = b.iter().zip(ax.iter()).map(|(b, ax)| b - ax).collect();
+    let mut p = r.clone();
+    let mut rr = dot(&r, &r);
+    let tolerance = 1e-20 * dot(b, b).max(1.0);
+
+    let mut iterations = 0;
+    while iterations < max_iterations && rr > tolerance {
+        ctx.check()?;
+        ctx.report(progress.0 + (progress.1 - progress.0) * iterations as f32 / max_iterations as f32);
+        iterations += 1;
+        let ap = apply(&p);
+        let pap = dot(&p, &ap);
+        if pap <= 0.0 {
+            // singular system; keep what we have.
+            break;
+        }
+        let alpha = rr / pap;
+        for i in 0..x.len() {
+            x[i] += alpha * p[i];
+            r[i] -= alpha * ap[i];
+        }
+        let rr_next = dot(&r, &r);
+        let beta = rr_next / rr;
+        for i in 0..p.len() {
+            p[i] = r[i] + beta * p[i];
+        }
+        rr = rr_next;
+    }
+    op_event!("conjugate gradient finished", iterations = iterations, residual = rr.sqrt());
+    return Ok(x);
+}
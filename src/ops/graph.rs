@@ -0,0 +1,192 @@
+use std::any::Any;
+
+use crate::geometry::*;
+use crate::index::*;
+use crate::mesh::Mesh;
+use crate::tolerance::Tolerance;
+
+use super::{cluster_vertices, fair, weld_vertices};
+
+// One step of a parametric model. The parameters are the implementing
+// type's fields, so the graph can hand them back for editing and re-run the
+// step with new values.
+pub trait Operation<M>: Any {
+    // Builds the output from the outputs of the node's inputs, in order.
+    fn evaluate(&self, inputs: &[&M]) -> M;
+
+    fn name(&self) -> &str {
+        return std::any::type_name::<Self>();
+    }
+}
+
+pub type NodeId = usize;
+
+struct Node<M> {
+    operation: Box<dyn Operation<M>>,
+    inputs: Vec<NodeId>,
+    output: Option<M>, // None until evaluated, or after an upstream change.
+}
+
+// A history of operations as a DAG. Each node's output is cached until its
+// parameters, or those of anything upstream, change. Nodes can only take
+// earlier nodes as inputs, so node order is a valid evaluation order.
+pub struct OpGraph<M> {
+    nodes: Vec<Node<M>>,
+}
+
+impl<M: 'static> Default for OpGraph<M> {
+    fn default() -> Self {
+        OpGraph { nodes: Vec::new() }
+    }
+}
+
+impl<M: 'static> OpGraph<M> {
+    pub fn new() -> Self {
+        return OpGraph::default();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.nodes.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.is_empty();
+    }
+
+    pub fn add<O: Operation<M>>(&mut self, operation: O, inputs: &[NodeId]) -> NodeId {
+        let id = self.nodes.len();
+        assert!(inputs.iter().all(|i| *i < id), "operation inputs must be existing nodes");
+        self.nodes.push(Node { operation: Box::new(operation), inputs: inputs.to_vec(), output: None });
+        return id;
+    }
+
+    pub fn inputs(&self, id: NodeId) -> &[NodeId] {
+        return &self.nodes[id].inputs;
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        return self.nodes[id].operation.name();
+    }
+
+    // The node's operation, if it is an `O`.
+    pub fn operation<O: Operation<M>>(&self, id: NodeId) -> Option<&O> {
+        let operation: &dyn Any = &*self.nodes[id].operation;
+        return operation.downcast_ref::<O>();
+    }
+
+    // Edits the parameters of an `O` node and invalidates it and everything
+    // downstream. Returns false, changing nothing, if the node isn't an `O`.
+    pub fn update<O: Operation<M>, U: FnOnce(&mut O)>(&mut self, id: NodeId, update: U) -> bool {
+        let operation: &mut dyn Any = &mut *self.nodes[id].operation;
+        match operation.downcast_mut::<O>() {
+            Some(operation) => update(operation),
+            None => return false,
+        }
+        self.invalidate(id);
+        return true;
+    }
+
+    pub fn replace<O: Operation<M>>(&mut self, id: NodeId, operation: O) {
+        self.nodes[id].operation = Box::new(operation);
+        self.invalidate(id);
+    }
+
+    // Drops the cached output of `id` and of everything that depends on it.
+    pub fn invalidate(&mut self, id: NodeId) {
+        let mut dirty = vec![false; self.nodes.len()];
+        dirty[id] = true;
+        for i in id..self.nodes.len() {
+            if dirty[i] || self.nodes[i].inputs.iter().any(|input| dirty[*input]) {
+                dirty[i] = true;
+                self.nodes[i].output = None;
+            }
+        }
+    }
+
+    pub fn is_evaluated(&self, id: NodeId) -> bool {
+        return self.nodes[id].output.is_some();
+    }
+
+    // The cached output, without evaluating anything.
+    pub fn output(&self, id: NodeId) -> Option<&M> {
+        return self.nodes[id].output.as_ref();
+    }
+
+    // Evaluates whatever `id` needs that isn't cached, then returns its output.
+    pub fn evaluate(&mut self, id: NodeId) -> &M {
+        let mut needed = vec![false; id + 1];
+        needed[id] = true;
+        for i in (0..=id).rev() {
+            if needed[i] && self.nodes[i].output.is_none() {
+                for input in self.nodes[i].inputs.iter() {
+                    needed[*input] = true;
+                }
+            }
+        }
+        for (i, is_needed) in needed.iter().enumerate() {
+            if !is_needed || self.nodes[i].output.is_some() {
+                continue;
+            }
+            // inputs are always earlier nodes, and already evaluated.
+            let (before, rest) = self.nodes.split_at_mut(i);
+            let node = &mut rest[0];
+            let inputs: Vec<&M> = node.inputs.iter()
+                .map(|input| before[*input].output.as_ref().unwrap())
+                .collect();
+            node.output = Some(node.operation.evaluate(&inputs));
+        }
+        return self.nodes[id].output.as_ref().unwrap();
+    }
+}
+
+/*
+ * Operations
+ */
+
+// A fixed input, e.g. an imported mesh.
+pub struct Source<M>(pub M);
+
+impl<M: Clone + 'static> Operation<M> for Source<M> {
+    fn evaluate(&self, _inputs: &[&M]) -> M {
+        return self.0.clone();
+    }
+}
+
+// See ops::fair.
+pub struct Fair {
+    pub selection: Vec<VertexIndex>,
+    pub continuity_order: usize,
+}
+
+impl<V, E, F> Operation<Mesh<V, E, F>> for Fair
+    where V: HasPosition + Clone + 'static, E: Clone + 'static, F: Clone + 'static {
+    fn evaluate(&self, inputs: &[&Mesh<V, E, F>]) -> Mesh<V, E, F> {
+        let mut ret = inputs[0].clone();
+        fair(&mut ret, &self.selection, self.continuity_order);
+        return ret;
+    }
+}
+
+// See ops::cluster_vertices.
+pub struct ClusterVertices {
+    pub cell_size: f32,
+}
+
+impl<V, E, F> Operation<Mesh<V, E, F>> for ClusterVertices
+    where V: HasPosition + Clone + 'static, E: Clone + 'static, F: Clone + 'static {
+    fn evaluate(&self, inputs: &[&Mesh<V, E, F>]) -> Mesh<V, E, F> {
+        return cluster_vertices(inputs[0], self.cell_size).0;
+    }
+}
+
+// See ops::weld_vertices.
+pub struct WeldVertices {
+    pub tolerance: Tolerance,
+}
+
+impl<V, E, F> Operation<Mesh<V, E, F>> for WeldVertices
+    where V: HasPosition + Clone + 'static, E: Clone + 'static, F: Clone + 'static {
+    fn evaluate(&self, inputs: &[&Mesh<V, E, F>]) -> Mesh<V, E, F> {
+        return weld_vertices(inputs[0], &self.tolerance).0;
+    }
+}
@@ -0,0 +1,127 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::index::*;
+
+use super::context::{Cancelled, OpContext};
+
+// A local edit that greedy() applies cheapest first, e.g. an edge collapse
+// scored by a custom error metric. The operator owns its metric; the queue
+// handles ordering and keeps stale costs from ever being acted on.
+pub trait LocalOperator<M> {
+    type Element: IndexType;
+
+    // The cost of applying the operator at `element`, or None if it can't
+    // be applied there. greedy() asks again before applying, by which time
+    // the element may no longer exist: removals move other elements into
+    // freed indices and can leave `element` past the end.
+    fn cost(&mut self, mesh: &M, element: Self::Element) -> Option<f64>;
+
+    // Applies the operator at `element` and returns the elements whose cost
+    // may have changed, including any it created.
+    fn apply(&mut self, mesh: &mut M, element: Self::Element) -> Vec<Self::Element>;
+}
+
+struct Entry<I> {
+    cost: f64,
+    element: I,
+    version: u32,
+}
+
+impl<I: IndexType> PartialEq for Entry<I> {
+    fn eq(&self, other: &Self) -> bool {
+        return self.cmp(other) == Ordering::Equal;
+    }
+}
+
+impl<I: IndexType> Eq for Entry<I> {}
+
+impl<I: IndexType> PartialOrd for Entry<I> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+// Reversed, so the max-heap pops the lowest cost first, then the lowest index.
+impl<I: IndexType> Ord for Entry<I> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return other.cost.total_cmp(&self.cost)
+            .then_with(|| other.element.index().cmp(&self.element.index()));
+    }
+}
+
+// Repeatedly applies `operator` at the cheapest of `candidates` until the
+// cheapest cost exceeds `max_cost` or `max_steps` edits were made. Returns
+// the number of edits.
+//
+// After each edit only the elements the operator reports are re-scored.
+// Their old queue entries are left in place but marked stale by a version
+// counter and skipped when they surface, so no entry has to be found and
+// removed from the heap. An entry that surfaces is scored once more before
+// it is applied, since an edit can also change elements it didn't report,
+// e.g. by moving one into an index it freed; if it got dearer it goes back
+// in the queue.
+pub fn greedy<M, O: LocalOperator<M>>(mesh: &mut M, operator: &mut O, candidates: &[O::Element],
+                                      max_cost: f64, max_steps: usize) -> usize {
+    // can't fail without a cancel token.
+    return greedy_with_context(mesh, operator, candidates, max_cost, max_steps, &mut OpContext::new())
+        .unwrap();
+}
+
+// Progress is reported as the fraction of max_steps done. A cancelled run
+// keeps the edits made so far.
+pub fn greedy_with_context<M, O: LocalOperator<M>>(mesh: &mut M, operator: &mut O,
+                                                   candidates: &[O::Element], max_cost: f64,
+                                                   max_steps: usize, ctx: &mut OpContext)
+                                                   -> Result<usize, Cancelled> {
+    op_span!("greedy", candidates = candidates.len(), max_steps = max_steps);
+    let mut versions: Vec<u32> = Vec::new();
+    let mut heap: BinaryHeap<Entry<O::Element>> = BinaryHeap::new();
+    for element in candidates {
+        push(&mut heap, &mut versions, operator, mesh, *element);
+    }
+
+    let mut steps = 0;
+    while steps < max_steps {
+        let entry = match heap.pop() {
+            Some(entry) => entry,
+            None => break,
+        };
+        if entry.version != versions[entry.element.index()] {
+            continue;
+        }
+        let cost = match operator.cost(mesh, entry.element) {
+            Some(cost) => cost,
+            None => continue,
+        };
+        if cost > entry.cost {
+            heap.push(Entry { cost: cost, ..entry });
+            continue;
+        }
+        if cost > max_cost {
+            break;
+        }
+        ctx.step(steps, max_steps, 0.0, 1.0)?;
+        // anything still queued for this element is stale now.
+        versions[entry.element.index()] += 1;
+        for element in operator.apply(mesh, entry.element) {
+            push(&mut heap, &mut versions, operator, mesh, element);
+        }
+        steps += 1;
+    }
+    op_event!("applied", steps = steps);
+    ctx.report(1.0);
+    return Ok(steps);
+}
+
+// Scores `element` and queues it, superseding any entry already queued.
+fn push<M, O: LocalOperator<M>>(heap: &mut BinaryHeap<Entry<O::Element>>, versions: &mut Vec<u32>,
+                                operator: &mut O, mesh: &M, element: O::Element) {
+    if versions.len() <= element.index() {
+        versions.resize(element.index() + 1, 0);
+    }
+    versions[element.index()] += 1;
+    if let Some(cost) = operator.cost(mesh, element) {
+        heap.push(Entry { cost: cost, element: element, version: versions[element.index()] });
+    }
+}
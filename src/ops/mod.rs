@@ -0,0 +1,39 @@
+// With the "tracing" feature, opens an info span named after the operator
+// that lasts until the end of the enclosing block. Without it the fields
+// aren't evaluated at all.
+macro_rules! op_span {
+    ($name:literal $(, $field:ident = $value:expr)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!($name $(, $field = $value)*).entered();
+    };
+}
+
+// A debug event inside the current operator span, for result counts.
+macro_rules! op_event {
+    ($message:literal $(, $field:ident = $value:expr)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($field = $value,)* $message);
+    };
+}
+
+mod cluster;
+mod constrain;
+mod coplanar;
+mod context;
+mod fair;
+mod graph;
+mod greedy;
+mod path;
+mod stack;
+mod weld;
+
+pub use self::cluster::{cluster_vertices, cluster_vertices_with_context, Merged};
+pub use self::constrain::{solve_constraints, Constraint};
+pub use self::coplanar::{merge_coplanar_faces, merge_coplanar_faces_with_context};
+pub use self::context::{Cancelled, CancelToken, OpContext};
+pub use self::fair::{fair, fair_with_context};
+pub use self::graph::{ClusterVertices, Fair, NodeId, OpGraph, Operation, Source, WeldVertices};
+pub use self::greedy::{greedy, greedy_with_context, LocalOperator};
+pub use self::path::simplify_path;
+pub use self::stack::{Modifier, ModifierStack};
+pub use self::weld::{weld_vertices, weld_vertices_by, weld_vertices_by_with_context, weld_vertices_with_context};
@@ -0,0 +1,91 @@
+use crate::geometry::*;
+use crate::index::*;
+use crate::mesh::Mesh;
+
+// Simplifies a chain of edges, such as a boundary loop or a feature curve,
+// with Douglas-Peucker: interior vertices that lie within `tolerance` of the
+// simplified curve are collapsed along the chain into the vertex before
+// them, which keeps its data, so the surrounding faces follow the curve.
+//
+// `path` lists the chain's vertices in order, each joined to the next by an
+// edge; a closed chain repeats its first vertex at the end. Returns the
+// simplified chain in the same form, with the indices the vertices have
+// afterwards. A vertex whose edge to the one before it can't be collapsed
+// stays on the chain.
+pub fn simplify_path<V: HasPosition + Clone, E, F>(mesh: &mut Mesh<V, E, F>, path: &[VertexIndex],
+                                                   tolerance: f32) -> Vec<VertexIndex> {
+    op_span!("simplify_path", vertices = path.len());
+    for pair in path.windows(2) {
+        assert!(mesh.find_edge(pair[0], pair[1]).is_some(), "simplify_path: {} and {} aren't joined", pair[0], pair[1]);
+    }
+    if path.len() < 3 {
+        return path.to_vec();
+    }
+    let closed = path[0] == path[path.len() - 1];
+    let positions: Vec<Vec3> = path.iter().map(|v| mesh.vertex(*v).data().unwrap().position()).collect();
+    let mut keep = vec![false; path.len()];
+    keep[0] = true;
+    keep[path.len() - 1] = true;
+    if closed {
+        // split the loop at the vertex farthest from its start.
+        let far = (1..path.len() - 1)
+            .max_by(|i, j| distance(positions[0], positions[*i]).total_cmp(&distance(positions[0], positions[*j])))
+            .unwrap();
+        keep[far] = true;
+        douglas_peucker(&positions, 0, far, tolerance, &mut keep);
+        douglas_peucker(&positions, far, path.len() - 1, tolerance, &mut keep);
+    } else {
+        douglas_peucker(&positions, 0, path.len() - 1, tolerance, &mut keep);
+    }
+
+    // Collapsing moves the last vertex into the freed index, so the chain's
+    // indices are kept up to date as it goes.
+    let mut chain = path.to_vec();
+    let mut anchor = 0;
+    for i in 1..chain.len() - 1 {
+        if keep[i] {
+            anchor = i;
+            continue;
+        }
+        let e = mesh.find_edge(chain[anchor], chain[i]).unwrap();
+        if !mesh.is_collapsible(e) {
+            keep[i] = true;
+            anchor = i;
+            continue;
+        }
+        let data = mesh.vertex(chain[anchor]).data().unwrap().clone();
+        let ends = mesh.edge(e).vertices();
+        let last = VertexIndex::new(mesh.vertex_iter().len() - 1);
+        let merged = mesh.collapse_edge(e);
+        *mesh.vertex_data_mut(merged).unwrap() = data;
+        let freed = if merged == ends[0] { ends[1] } else { ends[0] };
+        for v in chain.iter_mut() {
+            if *v == ends[0] || *v == ends[1] {
+                *v = merged;
+            } else if *v == last {
+                *v = freed;
+            }
+        }
+    }
+    let ret: Vec<VertexIndex> = (0..chain.len()).filter(|i| keep[*i]).map(|i| chain[i]).collect();
+    op_event!("simplified", kept = ret.len());
+    return ret;
+}
+
+// Marks the point between `start` and `end` farthest from the segment
+// between them to keep, if it's farther than `tolerance`, and recurses on
+// either side of it.
+fn douglas_peucker(positions: &[Vec3], start: usize, end: usize, tolerance: f32, keep: &mut [bool]) {
+    let (a, b) = (positions[start], positions[end]);
+    let offset = |i: usize| distance(positions[i], closest_point_on_segment(positions[i], a, b).0);
+    let far = match (start + 1..end).max_by(|i, j| offset(*i).total_cmp(&offset(*j))) {
+        Some(far) => far,
+        None => return,
+    };
+    if offset(far) <= tolerance {
+        return;
+    }
+    keep[far] = true;
+    douglas_peucker(positions, start, far, tolerance, keep);
+    douglas_peucker(positions, far, end, tolerance, keep);
+}
@@ -0,0 +1,151 @@
+use std::any::Any;
+
+use super::graph::Operation;
+
+// A single-input step of a ModifierStack. Every graph Operation is one,
+// taking the previous stage's output as its only input.
+pub trait Modifier<M>: Any {
+    fn apply(&self, input: &M) -> M;
+
+    fn name(&self) -> &str {
+        return std::any::type_name::<Self>();
+    }
+}
+
+impl<M, O: Operation<M>> Modifier<M> for O {
+    fn apply(&self, input: &M) -> M {
+        return self.evaluate(&[input]);
+    }
+
+    fn name(&self) -> &str {
+        return Operation::name(self);
+    }
+}
+
+struct Stage<M> {
+    modifier: Box<dyn Modifier<M>>,
+    enabled: bool,
+    output: Option<M>, // None until evaluated, after an upstream change, or while disabled.
+}
+
+// A base mesh followed by a chain of modifiers, like a DCC modifier stack.
+// Each stage's output is cached until it or something above it changes, so
+// editing the last stage only re-runs the last stage. Disabled stages pass
+// their input through.
+pub struct ModifierStack<M> {
+    base: M,
+    stages: Vec<Stage<M>>,
+}
+
+impl<M: 'static> ModifierStack<M> {
+    pub fn new(base: M) -> Self {
+        return ModifierStack { base: base, stages: Vec::new() };
+    }
+
+    pub fn len(&self) -> usize {
+        return self.stages.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.stages.is_empty();
+    }
+
+    pub fn base(&self) -> &M {
+        return &self.base;
+    }
+
+    // Replaces the base mesh, which invalidates every stage.
+    pub fn set_base(&mut self, base: M) {
+        self.base = base;
+        self.invalidate(0);
+    }
+
+    pub fn push<O: Modifier<M>>(&mut self, modifier: O) -> usize {
+        self.stages.push(Stage { modifier: Box::new(modifier), enabled: true, output: None });
+        return self.stages.len() - 1;
+    }
+
+    pub fn insert<O: Modifier<M>>(&mut self, index: usize, modifier: O) {
+        self.stages.insert(index, Stage { modifier: Box::new(modifier), enabled: true, output: None });
+        self.invalidate(index);
+    }
+
+    pub fn remove(&mut self, index: usize) -> Box<dyn Modifier<M>> {
+        let ret = self.stages.remove(index).modifier;
+        self.invalidate(index);
+        return ret;
+    }
+
+    pub fn name(&self, index: usize) -> &str {
+        return self.stages[index].modifier.name();
+    }
+
+    // The stage's modifier, if it is an `O`.
+    pub fn modifier<O: Modifier<M>>(&self, index: usize) -> Option<&O> {
+        let modifier: &dyn Any = &*self.stages[index].modifier;
+        return modifier.downcast_ref::<O>();
+    }
+
+    // Edits the parameters of an `O` stage and invalidates it and everything
+    // below. Returns false, changing nothing, if the stage isn't an `O`.
+    pub fn update<O: Modifier<M>, U: FnOnce(&mut O)>(&mut self, index: usize, update: U) -> bool {
+        let modifier: &mut dyn Any = &mut *self.stages[index].modifier;
+        match modifier.downcast_mut::<O>() {
+            Some(modifier) => update(modifier),
+            None => return false,
+        }
+        self.invalidate(index);
+        return true;
+    }
+
+    pub fn is_enabled(&self, index: usize) -> bool {
+        return self.stages[index].enabled;
+    }
+
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        if self.stages[index].enabled != enabled {
+            self.stages[index].enabled = enabled;
+            self.invalidate(index);
+        }
+    }
+
+    pub fn is_evaluated(&self, index: usize) -> bool {
+        return self.stages[index].output.is_some();
+    }
+
+    // The cached output of a stage, without evaluating anything.
+    pub fn output(&self, index: usize) -> Option<&M> {
+        return self.stages[index].output.as_ref();
+    }
+
+    // Drops the cached output of `index` and of every stage below it.
+    pub fn invalidate(&mut self, index: usize) {
+        for stage in self.stages.iter_mut().skip(index) {
+            stage.output = None;
+        }
+    }
+
+    // Evaluates the stages that aren't cached, then returns the result of
+    // the whole stack.
+    pub fn evaluate(&mut self) -> &M {
+        let mut last: Option<usize> = None; // the stage feeding the next one, or the base.
+        for i in 0..self.stages.len() {
+            if !self.stages[i].enabled {
+                continue;
+            }
+            if self.stages[i].output.is_none() {
+                let (before, rest) = self.stages.split_at_mut(i);
+                let input = match last {
+                    Some(j) => before[j].output.as_ref().unwrap(),
+                    None => &self.base,
+                };
+                rest[0].output = Some(rest[0].modifier.apply(input));
+            }
+            last = Some(i);
+        }
+        return match last {
+            Some(j) => self.stages[j].output.as_ref().unwrap(),
+            None => &self.base,
+        };
+    }
+}
@@ -0,0 +1,77 @@
+use crate::geometry::*;
+use crate::index::*;
+use crate::mesh::Mesh;
+use crate::spatial::SpatialHash;
+use crate::tolerance::Tolerance;
+
+use super::cluster::{merge_edges, merge_faces, Merged};
+use super::context::{Cancelled, OpContext};
+
+// Merges vertices that are coincident within `tolerance`. Vertices are
+// visited in index order, and each one joins the nearest earlier kept vertex
+// in range. Kept vertices do not move, so unlike cluster_vertices() a chain
+// of close points cannot drift. Edges and faces are carried over as in
+// cluster_vertices(), dropping those that collapse.
+//
+// Returns the welded mesh and, for every source vertex, the index of the
+// vertex it was merged into.
+pub fn weld_vertices<V, E, F>(mesh: &Mesh<V, E, F>, tolerance: &Tolerance)
+                             -> Merged<V, E, F>
+    where V: HasPosition + Clone, E: Clone, F: Clone {
+    // can't fail without a cancel token.
+    return weld_vertices_with_context(mesh, tolerance, &mut OpContext::new()).unwrap();
+}
+
+pub fn weld_vertices_with_context<V, E, F>(mesh: &Mesh<V, E, F>, tolerance: &Tolerance,
+                                          ctx: &mut OpContext)
+                                          -> Result<Merged<V, E, F>, Cancelled>
+    where V: HasPosition + Clone, E: Clone, F: Clone {
+    return weld_vertices_by_with_context(mesh, tolerance, |_, _| true, ctx);
+}
+
+// Like weld_vertices(), but a vertex only joins a kept vertex whose payload
+// is `compatible` with its own, e.g. one with matching UVs and normals, so
+// attribute seams survive the weld. Coincident vertices on either side of a
+// seam each keep their own copy.
+pub fn weld_vertices_by<V, E, F, C>(mesh: &Mesh<V, E, F>, tolerance: &Tolerance, compatible: C)
+                                   -> Merged<V, E, F>
+    where V: HasPosition + Clone, E: Clone, F: Clone, C: Fn(&V, &V) -> bool {
+    // can't fail without a cancel token.
+    return weld_vertices_by_with_context(mesh, tolerance, compatible, &mut OpContext::new()).unwrap();
+}
+
+pub fn weld_vertices_by_with_context<V, E, F, C>(mesh: &Mesh<V, E, F>, tolerance: &Tolerance,
+                                                compatible: C, ctx: &mut OpContext)
+                                                -> Result<Merged<V, E, F>, Cancelled>
+    where V: HasPosition + Clone, E: Clone, F: Clone, C: Fn(&V, &V) -> bool {
+    op_span!("weld_vertices", vertices = mesh.vertex_iter().len(),
+             edges = mesh.edge_iter().len(), distance = tolerance.distance);
+    let cell_size = if tolerance.distance > 0.0 { tolerance.distance } else { 1.0 };
+    let mut kept = SpatialHash::new(cell_size);
+    let mut remap: Vec<VertexIndex> = Vec::new();
+    let mut ret: Mesh<V, E, F> = Mesh::new();
+
+    let total = mesh.vertex_iter().len();
+    for (i, v) in mesh.vertex_iter().enumerate() {
+        ctx.step(i, total, 0.0, 0.5)?;
+        let data = v.data().unwrap();
+        let p = data.position();
+        let target = match kept.nearest_filtered(p, tolerance.distance,
+                                                 |k| compatible(data, &ret.vertices()[k])) {
+            Some(target) => target,
+            None => {
+                let index = ret.add_vertex(data.clone());
+                kept.insert(index, p);
+                index
+            }
+        };
+        remap.push(target);
+    }
+
+    merge_edges(mesh, &remap, &mut ret, ctx)?;
+    merge_faces(mesh, &remap, &mut ret, ctx)?;
+    op_event!("welded", vertices = ret.vertex_iter().len(), edges = ret.edge_iter().len(),
+              faces = ret.face_iter().len());
+    ctx.report(1.0);
+    return Ok((ret, remap));
+}
@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+
+use super::mesh::Mesh;
+use super::selection::Selectable;
+
+// An assignment of every element to one of `len()` parts, with each part's
+// halo: the elements of other parts adjacent to it, which a worker
+// processing the part needs read access to.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Partition<I> {
+    parts: Vec<usize>,
+    halos: Vec<Vec<I>>,
+    cut_size: usize,
+}
+
+impl<I: Selectable> Partition<I> {
+    // The number of parts.
+    pub fn len(&self) -> usize {
+        return self.halos.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.halos.is_empty();
+    }
+
+    pub fn part(&self, element: I) -> usize {
+        return self.parts[element.index()];
+    }
+
+    // The part of each element, by index.
+    pub fn parts(&self) -> &[usize] {
+        return &self.parts;
+    }
+
+    // The elements of a part, in ascending index order.
+    pub fn elements(&self, part: usize) -> Vec<I> {
+        return (0..self.parts.len()).filter(|i| self.parts[*i] == part).map(I::new).collect();
+    }
+
+    // In ascending index order.
+    pub fn halo(&self, part: usize) -> &[I] {
+        return &self.halos[part];
+    }
+
+    // The number of adjacent element pairs in different parts.
+    pub fn cut_size(&self) -> usize {
+        return self.cut_size;
+    }
+}
+
+// Splits the elements into `k` parts whose sizes differ by at most one, for
+// distributing work. Parts are made by recursive bisection along
+// breadth-first orderings that start from a far-away element, which keeps
+// parts connected and compact and the cut small, though not as small as a
+// multilevel partitioner like METIS would get it. Deterministic.
+pub fn partition<I: Selectable, V, E, F>(mesh: &Mesh<V, E, F>, k: usize) -> Partition<I> {
+    assert!(k > 0, "partition needs at least one part");
+    let adjacency = I::adjacency(mesh);
+    let n = adjacency.len();
+    let mut parts = vec![0; n];
+    bisect(&adjacency, (0..n).collect(), 0, k, &mut parts);
+
+    let mut halos: Vec<Vec<I>> = vec![Vec::new(); k];
+    let mut cut_size = 0;
+    for (i, neighbors) in adjacency.iter().enumerate() {
+        for j in neighbors.iter() {
+            if parts[i] != parts[j.index()] {
+                halos[parts[i]].push(*j);
+                if i < j.index() {
+                    cut_size += 1;
+                }
+            }
+        }
+    }
+    for halo in halos.iter_mut() {
+        halo.sort();
+        halo.dedup();
+    }
+    return Partition { parts: parts, halos: halos, cut_size: cut_size };
+}
+
+// Assigns `elements` to parts first..first + k.
+fn bisect<I: Selectable>(adjacency: &[Vec<I>], elements: Vec<usize>, first: usize, k: usize,
+                         parts: &mut [usize]) {
+    if k == 1 {
+        for i in elements {
+            parts[i] = first;
+        }
+        return;
+    }
+    let order = breadth_first_order(adjacency, &elements);
+    let left_k = k / 2;
+    let split = elements.len() * left_k / k;
+    let (left, right) = order.split_at(split);
+    bisect(adjacency, left.to_vec(), first, left_k, parts);
+    bisect(adjacency, right.to_vec(), first + left_k, k - left_k, parts);
+}
+
+// Every element of the subset, in breadth-first order over the adjacency
+// restricted to the subset. Each component starts from its element
+// farthest from its lowest index, so the order sweeps it end to end.
+fn breadth_first_order<I: Selectable>(adjacency: &[Vec<I>], elements: &[usize]) -> Vec<usize> {
+    let mut in_subset = vec![false; adjacency.len()];
+    for i in elements {
+        in_subset[*i] = true;
+    }
+    let mut visited = vec![false; adjacency.len()];
+    let mut ret = Vec::with_capacity(elements.len());
+    for start in elements {
+        if visited[*start] {
+            continue;
+        }
+        let component = sweep(adjacency, &in_subset, *start);
+        let far = *component.last().unwrap();
+        for i in sweep(adjacency, &in_subset, far) {
+            visited[i] = true;
+            ret.push(i);
+        }
+    }
+    return ret;
+}
+
+fn sweep<I: Selectable>(adjacency: &[Vec<I>], in_subset: &[bool], start: usize) -> Vec<usize> {
+    let mut seen = vec![false; adjacency.len()];
+    let mut ret = Vec::new();
+    let mut queue = VecDeque::new();
+    seen[start] = true;
+    queue.push_back(start);
+    while let Some(i) = queue.pop_front() {
+        ret.push(i);
+        for j in adjacency[i].iter() {
+            if in_subset[j.index()] && !seen[j.index()] {
+                seen[j.index()] = true;
+                queue.push_back(j.index());
+            }
+        }
+    }
+    return ret;
+}
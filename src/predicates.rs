@@ -0,0 +1,220 @@
+use super::geometry::{convert, Vec3};
+
+// Robust geometric predicates after Shewchuk, "Adaptive Precision
+// Floating-Point Arithmetic and Fast Robust Geometric Predicates".
+//
+// Each predicate first evaluates the determinant in plain f64 and returns it
+// if it is larger than the worst-case rounding error. Only near-degenerate
+// inputs fall through to the exact path, which redoes the computation with
+// floating-point expansions (sums of non-overlapping f64s, smallest first).
+// Either way the sign of the result is exact.
+
+pub type Point2 = [f64; 2];
+pub type Point3 = [f64; 3];
+
+pub fn to_point3(v: Vec3) -> Point3 {
+    return convert(v);
+}
+
+const EPSILON: f64 = f64::EPSILON * 0.5;
+const SPLITTER: f64 = 134217729.0; // 2^27 + 1
+const ORIENT2D_BOUND: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const ORIENT3D_BOUND: f64 = (7.0 + 56.0 * EPSILON) * EPSILON;
+const INCIRCLE_BOUND: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+
+// Positive if a, b, c are in counter-clockwise order, negative if clockwise
+// and zero if collinear. The magnitude is twice the signed triangle area.
+pub fn orient2d(a: Point2, b: Point2, c: Point2) -> f64 {
+    let left = (a[0] - c[0]) * (b[1] - c[1]);
+    let right = (a[1] - c[1]) * (b[0] - c[0]);
+    let det = left - right;
+    let bound = ORIENT2D_BOUND * (left.abs() + right.abs());
+    if det > bound || -det > bound {
+        return det;
+    }
+
+    let acx = diff(a[0], c[0]);
+    let acy = diff(a[1], c[1]);
+    let bcx = diff(b[0], c[0]);
+    let bcy = diff(b[1], c[1]);
+    return estimate(&sub(&mul(&acx, &bcy), &mul(&acy, &bcx)));
+}
+
+// Positive if d lies below the plane through a, b, c, where "below" means
+// a, b, c appear counter-clockwise when viewed from above. Negative if d is
+// above and zero if the four points are coplanar.
+pub fn orient3d(a: Point3, b: Point3, c: Point3, d: Point3) -> f64 {
+    let (adx, ady, adz) = (a[0] - d[0], a[1] - d[1], a[2] - d[2]);
+    let (bdx, bdy, bdz) = (b[0] - d[0], b[1] - d[1], b[2] - d[2]);
+    let (cdx, cdy, cdz) = (c[0] - d[0], c[1] - d[1], c[2] - d[2]);
+    let (bdxcdy, cdxbdy) = (bdx * cdy, cdx * bdy);
+    let (cdxady, adxcdy) = (cdx * ady, adx * cdy);
+    let (adxbdy, bdxady) = (adx * bdy, bdx * ady);
+    let det = adz * (bdxcdy - cdxbdy) + bdz * (cdxady - adxcdy) + cdz * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * adz.abs()
+        + (cdxady.abs() + adxcdy.abs()) * bdz.abs()
+        + (adxbdy.abs() + bdxady.abs()) * cdz.abs();
+    let bound = ORIENT3D_BOUND * permanent;
+    if det > bound || -det > bound {
+        return det;
+    }
+
+    let (adx, ady, adz) = (diff(a[0], d[0]), diff(a[1], d[1]), diff(a[2], d[2]));
+    let (bdx, bdy, bdz) = (diff(b[0], d[0]), diff(b[1], d[1]), diff(b[2], d[2]));
+    let (cdx, cdy, cdz) = (diff(c[0], d[0]), diff(c[1], d[1]), diff(c[2], d[2]));
+    let bc = sub(&mul(&bdx, &cdy), &mul(&cdx, &bdy));
+    let ca = sub(&mul(&cdx, &ady), &mul(&adx, &cdy));
+    let ab = sub(&mul(&adx, &bdy), &mul(&bdx, &ady));
+    let ret = add(&add(&mul(&adz, &bc), &mul(&bdz, &ca)), &mul(&cdz, &ab));
+    return estimate(&ret);
+}
+
+// Positive if d lies inside the circle through a, b, c, negative if outside
+// and zero if the four points are cocircular. a, b, c must be in
+// counter-clockwise order, otherwise the sign is reversed.
+pub fn incircle(a: Point2, b: Point2, c: Point2, d: Point2) -> f64 {
+    let (adx, ady) = (a[0] - d[0], a[1] - d[1]);
+    let (bdx, bdy) = (b[0] - d[0], b[1] - d[1]);
+    let (cdx, cdy) = (c[0] - d[0], c[1] - d[1]);
+    let (bdxcdy, cdxbdy) = (bdx * cdy, cdx * bdy);
+    let (cdxady, adxcdy) = (cdx * ady, adx * cdy);
+    let (adxbdy, bdxady) = (adx * bdy, bdx * ady);
+    let alift = adx * adx + ady * ady;
+    let blift = bdx * bdx + bdy * bdy;
+    let clift = cdx * cdx + cdy * cdy;
+    let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+        + (cdxady.abs() + adxcdy.abs()) * blift
+        + (adxbdy.abs() + bdxady.abs()) * clift;
+    let bound = INCIRCLE_BOUND * permanent;
+    if det > bound || -det > bound {
+        return det;
+    }
+
+    let (adx, ady) = (diff(a[0], d[0]), diff(a[1], d[1]));
+    let (bdx, bdy) = (diff(b[0], d[0]), diff(b[1], d[1]));
+    let (cdx, cdy) = (diff(c[0], d[0]), diff(c[1], d[1]));
+    let alift = add(&mul(&adx, &adx), &mul(&ady, &ady));
+    let blift = add(&mul(&bdx, &bdx), &mul(&bdy, &bdy));
+    let clift = add(&mul(&cdx, &cdx), &mul(&cdy, &cdy));
+    let bc = sub(&mul(&bdx, &cdy), &mul(&cdx, &bdy));
+    let ca = sub(&mul(&cdx, &ady), &mul(&adx, &cdy));
+    let ab = sub(&mul(&adx, &bdy), &mul(&bdx, &ady));
+    let ret = add(&add(&mul(&alift, &bc), &mul(&blift, &ca)), &mul(&clift, &ab));
+    return estimate(&ret);
+}
+
+/*
+ * Expansion arithmetic
+ */
+
+// a + b = x + y exactly, with x the rounded sum. Requires |a| >= |b|.
+fn fast_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    return (x, b - b_virtual);
+}
+
+// a + b = x + y exactly, with x the rounded sum.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    return (x, (a - a_virtual) + (b - b_virtual));
+}
+
+// Splits a into two halves of at most 26 significant bits each.
+fn split(a: f64) -> (f64, f64) {
+    let c = SPLITTER * a;
+    let a_big = c - a;
+    let hi = c - a_big;
+    return (hi, a - hi);
+}
+
+// a * b = x + y exactly, with x the rounded product.
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    let (ahi, alo) = split(a);
+    let (bhi, blo) = split(b);
+    let err1 = x - ahi * bhi;
+    let err2 = err1 - alo * bhi;
+    let err3 = err2 - ahi * blo;
+    return (x, alo * blo - err3);
+}
+
+// a - b as an expansion.
+fn diff(a: f64, b: f64) -> Vec<f64> {
+    let (x, y) = two_sum(a, -b);
+    if y == 0.0 {
+        return vec![x];
+    }
+    return vec![y, x];
+}
+
+// e + b, dropping zero components.
+fn grow(e: &[f64], b: f64) -> Vec<f64> {
+    let mut ret = Vec::with_capacity(e.len() + 1);
+    let mut q = b;
+    for component in e {
+        let (sum, h) = two_sum(q, *component);
+        if h != 0.0 {
+            ret.push(h);
+        }
+        q = sum;
+    }
+    if q != 0.0 || ret.is_empty() {
+        ret.push(q);
+    }
+    return ret;
+}
+
+fn add(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let mut ret = e.to_vec();
+    for component in f {
+        ret = grow(&ret, *component);
+    }
+    return ret;
+}
+
+fn sub(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let negated: Vec<f64> = f.iter().map(|x| -x).collect();
+    return add(e, &negated);
+}
+
+// e * b, dropping zero components.
+fn scale(e: &[f64], b: f64) -> Vec<f64> {
+    let mut ret = Vec::with_capacity(2 * e.len());
+    let (mut q, h) = two_product(e[0], b);
+    if h != 0.0 {
+        ret.push(h);
+    }
+    for component in &e[1..] {
+        let (product, product_err) = two_product(*component, b);
+        let (sum, h) = two_sum(q, product_err);
+        if h != 0.0 {
+            ret.push(h);
+        }
+        let (sum, h) = fast_two_sum(product, sum);
+        if h != 0.0 {
+            ret.push(h);
+        }
+        q = sum;
+    }
+    if q != 0.0 || ret.is_empty() {
+        ret.push(q);
+    }
+    return ret;
+}
+
+fn mul(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let mut ret = vec![0.0];
+    for component in f {
+        ret = add(&ret, &scale(e, *component));
+    }
+    return ret;
+}
+
+// Components are summed smallest first, so the sign matches the exact value.
+fn estimate(e: &[f64]) -> f64 {
+    return e.iter().sum();
+}
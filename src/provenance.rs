@@ -0,0 +1,175 @@
+use std::collections::{HashMap, HashSet};
+
+use super::index::*;
+
+// An id that stays with a face (or, after a split, with its pieces) while
+// face indices get reused and reordered by edits.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct PersistentId(pub u64);
+
+#[derive(Clone, Default)]
+struct Record {
+    face: Option<FaceIndex>, // None once the face was split, merged or removed.
+    parents: Vec<PersistentId>,
+    children: Vec<PersistentId>,
+    tags: Vec<String>,
+}
+
+// Persistent face ids with parent/child provenance. The mesh doesn't know
+// about it; operators that split, merge, remove or reorder faces report
+// that here, like Scene::swap_remove_face() for scene nodes. A reference
+// such as "the faces made by extrude #3" is then an id (or a tag), and
+// resolve() finds whatever faces it has turned into.
+#[derive(Clone, Default)]
+pub struct FaceIds {
+    records: HashMap<PersistentId, Record>,
+    by_face: Vec<Option<PersistentId>>,
+    next_id: u64,
+}
+
+impl FaceIds {
+    pub fn new() -> Self {
+        return FaceIds::default();
+    }
+
+    // Gives `face` a fresh id with no parents, replacing any id it had.
+    pub fn assign(&mut self, face: FaceIndex) -> PersistentId {
+        return self.create(face, Vec::new());
+    }
+
+    pub fn id(&self, face: FaceIndex) -> Option<PersistentId> {
+        return self.by_face.get(face.index()).cloned().flatten();
+    }
+
+    // The face an id currently names, if it hasn't been split or removed.
+    pub fn face(&self, id: PersistentId) -> Option<FaceIndex> {
+        return self.records.get(&id).and_then(|r| r.face);
+    }
+
+    pub fn parents(&self, id: PersistentId) -> &[PersistentId] {
+        return self.records.get(&id).map_or(&[], |r| &r.parents);
+    }
+
+    pub fn children(&self, id: PersistentId) -> &[PersistentId] {
+        return self.records.get(&id).map_or(&[], |r| &r.children);
+    }
+
+    // `face` was replaced by `pieces`, which may include `face` itself if the
+    // operator reused its index. Returns the new ids, in order.
+    pub fn split(&mut self, face: FaceIndex, pieces: &[FaceIndex]) -> Vec<PersistentId> {
+        let parent = self.retire(face);
+        return pieces.iter().map(|p| self.create(*p, parent.into_iter().collect())).collect();
+    }
+
+    // `faces` were replaced by the single face `into`.
+    pub fn merge(&mut self, faces: &[FaceIndex], into: FaceIndex) -> PersistentId {
+        let parents: Vec<PersistentId> = faces.iter().filter_map(|f| self.retire(*f)).collect();
+        return self.create(into, parents);
+    }
+
+    // The face is gone. Its id stays resolvable to nothing.
+    pub fn remove(&mut self, face: FaceIndex) {
+        self.retire(face);
+    }
+
+    // Follows Mesh::remove_face(face), which returned `moved`: the face is
+    // gone, and the moved face keeps its id under its new index.
+    pub fn swap_remove(&mut self, face: FaceIndex, moved: Option<FaceIndex>) {
+        self.retire(face);
+        if let Some(moved) = moved {
+            if let Some(id) = self.id(moved) {
+                self.by_face[moved.index()] = None;
+                if self.by_face.len() <= face.index() {
+                    self.by_face.resize(face.index() + 1, None);
+                }
+                self.by_face[face.index()] = Some(id);
+                self.records.get_mut(&id).unwrap().face = Some(face);
+            }
+        }
+    }
+
+    // Applies a face reordering, e.g. the result of Mesh::sort_faces_by.
+    pub fn remap(&mut self, remap: &[FaceIndex]) {
+        let mut by_face: Vec<Option<PersistentId>> = Vec::new();
+        for (old, id) in self.by_face.iter().enumerate() {
+            if let Some(id) = id {
+                let new = remap[old];
+                if by_face.len() <= new.index() {
+                    by_face.resize(new.index() + 1, None);
+                }
+                by_face[new.index()] = Some(*id);
+                self.records.get_mut(id).unwrap().face = Some(new);
+            }
+        }
+        self.by_face = by_face;
+    }
+
+    // The live faces an id has become: itself if it is still alive,
+    // otherwise the live faces of its descendants, in index order.
+    pub fn resolve(&self, id: PersistentId) -> Vec<FaceIndex> {
+        let mut ret = Vec::new();
+        let mut stack = vec![id];
+        let mut visited: HashSet<PersistentId> = HashSet::new();
+        while let Some(current) = stack.pop() {
+            // a merge makes the graph a DAG, so a face can be reached twice.
+            if !visited.insert(current) {
+                continue;
+            }
+            if let Some(record) = self.records.get(&current) {
+                match record.face {
+                    Some(face) => ret.push(face),
+                    None => stack.extend(record.children.iter().cloned()),
+                }
+            }
+        }
+        ret.sort();
+        ret.dedup();
+        return ret;
+    }
+
+    pub fn add_tag(&mut self, id: PersistentId, tag: &str) {
+        if let Some(record) = self.records.get_mut(&id) {
+            if !record.tags.iter().any(|t| t == tag) {
+                record.tags.push(tag.to_string());
+            }
+        }
+    }
+
+    pub fn tags(&self, id: PersistentId) -> Vec<&str> {
+        return self.records.get(&id).map_or(Vec::new(), |r| r.tags.iter().map(|t| t.as_str()).collect());
+    }
+
+    // The live faces descended from every id carrying `tag`.
+    pub fn resolve_tag(&self, tag: &str) -> Vec<FaceIndex> {
+        let mut ret: Vec<FaceIndex> = self.records.iter()
+            .filter(|(_, r)| r.tags.iter().any(|t| t == tag))
+            .flat_map(|(id, _)| self.resolve(*id))
+            .collect();
+        ret.sort();
+        ret.dedup();
+        return ret;
+    }
+
+    fn create(&mut self, face: FaceIndex, parents: Vec<PersistentId>) -> PersistentId {
+        self.retire(face);
+        let id = PersistentId(self.next_id);
+        self.next_id += 1;
+        for parent in parents.iter() {
+            self.records.get_mut(parent).unwrap().children.push(id);
+        }
+        self.records.insert(id, Record { face: Some(face), parents: parents, ..Record::default() });
+        if self.by_face.len() <= face.index() {
+            self.by_face.resize(face.index() + 1, None);
+        }
+        self.by_face[face.index()] = Some(id);
+        return id;
+    }
+
+    // Detaches whatever id `face` had from it.
+    fn retire(&mut self, face: FaceIndex) -> Option<PersistentId> {
+        let id = self.id(face)?;
+        self.by_face[face.index()] = None;
+        self.records.get_mut(&id).unwrap().face = None;
+        return Some(id);
+    }
+}
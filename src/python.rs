@@ -0,0 +1,177 @@
+// pyo3 0.22's generated method wrappers trip this lint.
+#![allow(clippy::useless_conversion)]
+
+use pyo3::exceptions::{PyIndexError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyFloat, PyInt, PyString, PyTuple};
+
+use super::dynamic::{DynMesh, Value};
+use super::geometry::*;
+use super::index::*;
+use super::tolerance::Tolerance;
+
+// Python bindings over DynMesh, built as the "wedge" extension module with
+// the "python" feature. Elements are plain integers on the Python side and
+// bad ones raise IndexError instead of panicking.
+#[pyclass(name = "Mesh", module = "wedge")]
+#[derive(Clone, Default)]
+pub struct PyMesh {
+    mesh: DynMesh,
+}
+
+impl PyMesh {
+    fn vertex_index(&self, v: u32) -> PyResult<VertexIndex> {
+        let index = VertexIndex(v);
+        if !self.mesh.is_valid_vertex_index(index) {
+            return Err(PyIndexError::new_err(format!("invalid vertex index {}", v)));
+        }
+        return Ok(index);
+    }
+}
+
+#[pymethods]
+impl PyMesh {
+    #[new]
+    fn new() -> Self {
+        return PyMesh::default();
+    }
+
+    fn add_vertex(&mut self, position: Vec3) -> u32 {
+        let mut data = super::dynamic::Attributes::new();
+        data.set_position(position);
+        return self.mesh.add_vertex(data).0;
+    }
+
+    fn add_edge(&mut self, a: u32, b: u32) -> PyResult<u32> {
+        let (a, b) = (self.vertex_index(a)?, self.vertex_index(b)?);
+        if a == b {
+            return Err(PyValueError::new_err("an edge must connect two distinct vertices"));
+        }
+        return Ok(self.mesh.add_edge(Default::default(), a, b).0);
+    }
+
+    fn vertex_count(&self) -> usize {
+        return self.mesh.vertex_iter().len();
+    }
+
+    fn edge_count(&self) -> usize {
+        return self.mesh.edge_iter().len();
+    }
+
+    fn face_count(&self) -> usize {
+        return self.mesh.face_iter().len();
+    }
+
+    fn position(&self, v: u32) -> PyResult<Vec3> {
+        let v = self.vertex_index(v)?;
+        return Ok(self.mesh.vertices()[v].position());
+    }
+
+    fn set_position(&mut self, v: u32, position: Vec3) -> PyResult<()> {
+        let v = self.vertex_index(v)?;
+        self.mesh.vertex_data_mut(v).unwrap().set_position(position);
+        return Ok(());
+    }
+
+    // The one-ring, sorted by index.
+    fn neighbors(&self, v: u32) -> PyResult<Vec<u32>> {
+        let v = self.vertex_index(v)?;
+        return Ok(self.mesh.vertex(v).sorted_vertices().iter().map(|n| n.0).collect());
+    }
+
+    // Every edge as a pair of vertex indices, in edge order.
+    fn edges(&self) -> Vec<(u32, u32)> {
+        return self.mesh.edge_iter().map(|e| {
+            let vertices = e.vertices();
+            return (vertices[0].0, vertices[1].0);
+        }).collect();
+    }
+
+    fn get_attribute(&self, py: Python<'_>, v: u32, name: &str) -> PyResult<PyObject> {
+        let v = self.vertex_index(v)?;
+        return Ok(match self.mesh.vertices()[v].get(name) {
+            Some(value) => value_to_py(py, value),
+            None => py.None(),
+        });
+    }
+
+    fn set_attribute(&mut self, v: u32, name: &str, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let v = self.vertex_index(v)?;
+        let value = value_from_py(value)?;
+        self.mesh.vertex_data_mut(v).unwrap().set(name, value);
+        return Ok(());
+    }
+
+    fn weld(&self, distance: f32) -> PyResult<PyMesh> {
+        if distance < 0.0 {
+            return Err(PyValueError::new_err("weld distance must not be negative"));
+        }
+        let (mesh, _) = super::ops::weld_vertices(&self.mesh, &Tolerance::new(distance));
+        return Ok(PyMesh { mesh: mesh });
+    }
+
+    fn cluster(&self, cell_size: f32) -> PyResult<PyMesh> {
+        if cell_size <= 0.0 {
+            return Err(PyValueError::new_err("cluster cell size must be positive"));
+        }
+        let (mesh, _) = super::ops::cluster_vertices(&self.mesh, cell_size);
+        return Ok(PyMesh { mesh: mesh });
+    }
+
+    #[pyo3(signature = (selection, continuity_order = 1))]
+    fn fair(&mut self, selection: Vec<u32>, continuity_order: usize) -> PyResult<()> {
+        let selection = selection.into_iter()
+            .map(|v| self.vertex_index(v))
+            .collect::<PyResult<Vec<VertexIndex>>>()?;
+        super::ops::fair(&mut self.mesh, &selection, continuity_order);
+        return Ok(());
+    }
+
+    fn summary(&self) -> String {
+        return self.mesh.summary_with_bounds().to_string();
+    }
+
+    fn to_debug_json(&self) -> String {
+        return self.mesh.to_debug_json();
+    }
+
+    fn __repr__(&self) -> String {
+        return format!("<wedge.Mesh {}>", self.mesh.summary());
+    }
+}
+
+fn value_to_py(py: Python<'_>, value: &Value) -> PyObject {
+    return match value {
+        Value::Bool(x) => x.into_py(py),
+        Value::Int(x) => x.into_py(py),
+        Value::Float(x) => x.into_py(py),
+        Value::Vector(x) => PyTuple::new_bound(py, x.iter()).into_py(py),
+        Value::Text(x) => x.into_py(py),
+    };
+}
+
+fn value_from_py(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    // bool first, since Python bools are also ints.
+    if value.is_instance_of::<PyBool>() {
+        return Ok(Value::Bool(value.extract()?));
+    }
+    if value.is_instance_of::<PyInt>() {
+        return Ok(Value::Int(value.extract()?));
+    }
+    if value.is_instance_of::<PyFloat>() {
+        return Ok(Value::Float(value.extract()?));
+    }
+    if value.is_instance_of::<PyString>() {
+        return Ok(Value::Text(value.extract()?));
+    }
+    if let Ok(vector) = value.extract::<Vec3>() {
+        return Ok(Value::Vector(vector));
+    }
+    return Err(PyTypeError::new_err("attributes must be bool, int, float, str or a 3-vector"));
+}
+
+#[pymodule]
+fn wedge(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyMesh>()?;
+    return Ok(());
+}
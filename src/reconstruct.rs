@@ -0,0 +1,198 @@
+use std::collections::VecDeque;
+
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+use super::spatial::SpatialHash;
+
+// Reconstructs a surface from points with outward normals by ball pivoting
+// (Bernardini et al., "The Ball-Pivoting Algorithm for Surface
+// Reconstruction"). A ball of each radius in `radii`, smallest first, is
+// rolled over the points: it starts on a seed triangle whose three points it
+// touches with no other point inside, and pivots around each open edge until
+// it touches another point, which makes the next triangle.
+//
+// Vertex i of the result is points[i]; points no ball reached stay as
+// isolated vertices. Triangles wind counter-clockwise seen from the side the
+// normals point to. Gaps between samples wider than the largest ball's
+// diameter are left as holes.
+pub fn ball_pivot(points: &[Vec3], normals: &[Vec3], radii: &[f32]) -> Mesh<Vec3, (), ()> {
+    assert_eq!(points.len(), normals.len(), "ball_pivot: need one normal per point");
+    assert!(radii.windows(2).all(|r| r[0] < r[1]), "ball_pivot: radii must increase");
+    let mut mesh: Mesh<Vec3, (), ()> = Mesh::new();
+    for p in points.iter() {
+        mesh.add_vertex(*p);
+    }
+    let max_radius = match radii.last() {
+        Some(r) => *r,
+        None => return mesh,
+    };
+    let hash = SpatialHash::from_mesh(&mesh, 2.0 * max_radius);
+    let mut pivot = Pivot { normals: normals, hash: hash, mesh: mesh, front: VecDeque::new() };
+    for r in radii.iter() {
+        assert!(*r > 0.0, "ball_pivot: radii must be positive");
+        // a larger ball may get past edges the smaller one couldn't.
+        pivot.front = pivot.mesh.edge_iter().map(|e| e.index()).collect();
+        pivot.expand(*r);
+        for v in 0..points.len() {
+            if pivot.seed(VertexIndex::new(v), *r) {
+                pivot.expand(*r);
+            }
+        }
+    }
+    return pivot.mesh;
+}
+
+struct Pivot<'a> {
+    normals: &'a [Vec3],
+    hash: SpatialHash,
+    mesh: Mesh<Vec3, (), ()>,
+    // Edges the ball may pivot around; ones that were closed since they
+    // were queued are skipped.
+    front: VecDeque<EdgeIndex>,
+}
+
+impl<'a> Pivot<'a> {
+    fn position(&self, v: VertexIndex) -> Vec3 {
+        return self.mesh[v];
+    }
+
+    // Unused points, and points on the front, can take another triangle.
+    fn is_free(&self, v: VertexIndex) -> bool {
+        let vertex = self.mesh.vertex(v);
+        return vertex.edge_iter().next().is_none() || vertex.edge_iter().any(|e| e.faces().len() == 1);
+    }
+
+    // Whether the counter-clockwise triangle's normal agrees with its
+    // points' normals.
+    fn is_oriented(&self, triangle: [VertexIndex; 3]) -> bool {
+        let normal = self.normal(triangle);
+        return triangle.iter().all(|v| dot(normal, self.normals[v.index()]) > 0.0);
+    }
+
+    fn normal(&self, triangle: [VertexIndex; 3]) -> Vec3 {
+        let [a, b, c] = triangle.map(|v| self.position(v));
+        return cross(sub(b, a), sub(c, a));
+    }
+
+    // The center of the ball of radius `r` touching the triangle's points,
+    // on the side its normal points to, if the ball is large enough.
+    fn ball_center(&self, triangle: [VertexIndex; 3], r: f32) -> Option<Vec3> {
+        let [a, b, c] = triangle.map(|v| self.position(v));
+        let (u, v) = (sub(b, a), sub(c, a));
+        let w = cross(u, v);
+        let w_squared = dot(w, w);
+        if w_squared == 0.0 {
+            return None;
+        }
+        let offset = scale(add(scale(cross(v, w), dot(u, u)), scale(cross(w, u), dot(v, v))), 0.5 / w_squared);
+        let height_squared = r * r - dot(offset, offset);
+        if height_squared < 0.0 {
+            return None;
+        }
+        return Some(add(add(a, offset), scale(normalize(w), height_squared.sqrt())));
+    }
+
+    fn add_triangle(&mut self, triangle: [VertexIndex; 3]) {
+        self.mesh.add_default_face(&triangle);
+        for k in 0..3 {
+            self.front.push_back(self.mesh.find_edge(triangle[k], triangle[(k + 1) % 3]).unwrap());
+        }
+    }
+
+    // Looks for a seed triangle at the unused point `v`: the first pair of
+    // unused neighbours, nearest first, that an empty ball touches along
+    // with it.
+    fn seed(&mut self, v: VertexIndex, r: f32) -> bool {
+        if self.mesh.vertex(v).edge_iter().next().is_some() {
+            return false;
+        }
+        let p = self.position(v);
+        let mut near: Vec<VertexIndex> = self.hash.query_radius(p, 2.0 * r).into_iter()
+            .filter(|u| *u != v && self.mesh.vertex(*u).edge_iter().next().is_none())
+            .collect();
+        near.sort_by(|a, b| distance(p, self.position(*a)).total_cmp(&distance(p, self.position(*b)))
+            .then_with(|| a.cmp(b)));
+        for i in 0..near.len() {
+            for j in i + 1..near.len() {
+                let mut triangle = [v, near[i], near[j]];
+                if dot(self.normal(triangle), self.normals[v.index()]) < 0.0 {
+                    triangle.swap(1, 2);
+                }
+                if !self.is_oriented(triangle) {
+                    continue;
+                }
+                let center = match self.ball_center(triangle, r) {
+                    Some(center) => center,
+                    None => continue,
+                };
+                if self.is_empty(center, r, &triangle) {
+                    self.add_triangle(triangle);
+                    return true;
+                }
+            }
+        }
+        return false;
+    }
+
+    // Whether no point but the triangle's is inside the ball, allowing for
+    // points on its surface.
+    fn is_empty(&self, center: Vec3, r: f32, triangle: &[VertexIndex; 3]) -> bool {
+        return self.hash.query_radius(center, r * (1.0 - 1e-4)).iter().all(|u| triangle.contains(u));
+    }
+
+    // Pivots around front edges until there are none left.
+    fn expand(&mut self, r: f32) {
+        while let Some(e) = self.front.pop_front() {
+            if let Some(triangle) = self.pivot(e, r) {
+                if self.mesh.can_add_face(&triangle) {
+                    self.add_triangle(triangle);
+                }
+            }
+        }
+    }
+
+    // The triangle the ball touches first as it rolls over the open side
+    // of `e`, if it does so within a turn.
+    fn pivot(&self, e: EdgeIndex, r: f32) -> Option<[VertexIndex; 3]> {
+        let h = self.mesh.boundary_half_edge(e)?;
+        let face = self.mesh.edge(e).faces()[0];
+        let opposite = self.mesh.face(face).vertex_iter().map(|v| v.index()).find(|v| *v != h.from && *v != h.to)?;
+        // the face runs h.to -> h.from.
+        let center = self.ball_center([h.to, h.from, opposite], r)?;
+        let (a, b) = (self.position(h.from), self.position(h.to));
+        let middle = scale(add(a, b), 0.5);
+        // the ball starts out along x and rolls towards y, away from the face.
+        let x = normalize(sub(center, middle));
+        let outward = cross(sub(a, b), self.normal([h.to, h.from, opposite]));
+        let y = normalize(sub(outward, scale(x, dot(outward, x))));
+
+        let mut best: Option<(f32, VertexIndex)> = None;
+        for k in self.hash.query_radius(middle, 2.0 * r) {
+            if k == h.from || k == h.to || k == opposite || !self.is_free(k) {
+                continue;
+            }
+            let triangle = [h.from, h.to, k];
+            if !self.is_oriented(triangle) {
+                continue;
+            }
+            let c = match self.ball_center(triangle, r) {
+                Some(c) => c,
+                None => continue,
+            };
+            let d = sub(c, middle);
+            let mut angle = dot(d, y).atan2(dot(d, x));
+            if angle < 0.0 {
+                angle += 2.0 * std::f32::consts::PI;
+            }
+            // a point on the same sphere is touched without rolling at all.
+            if angle > 2.0 * std::f32::consts::PI - 1e-4 {
+                angle = 0.0;
+            }
+            if best.is_none_or(|(a, v)| angle < a || angle == a && k < v) {
+                best = Some((angle, k));
+            }
+        }
+        return best.map(|(_, k)| [h.from, h.to, k]);
+    }
+}
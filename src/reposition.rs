@@ -0,0 +1,121 @@
+use std::fmt;
+
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+use super::predicates::{orient3d, to_point3, Point3};
+
+// What try_move_vertex() verifies before moving a vertex.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct MoveChecks {
+    // No face around the vertex turns over or collapses.
+    pub inversion: bool,
+    // No two faces around the vertex cross each other.
+    pub self_intersection: bool,
+}
+
+impl MoveChecks {
+    pub const NONE: MoveChecks = MoveChecks { inversion: false, self_intersection: false };
+    pub const ALL: MoveChecks = MoveChecks { inversion: true, self_intersection: true };
+}
+
+impl Default for MoveChecks {
+    fn default() -> Self {
+        return MoveChecks::ALL;
+    }
+}
+
+// Why try_move_vertex() left a vertex where it was.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MoveError {
+    // The face's normal would point against where it points now, or vanish.
+    Inverted(FaceIndex),
+    // The two faces would cross.
+    Intersecting(FaceIndex, FaceIndex),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            MoveError::Inverted(face) => write!(f, "face {} would be inverted", face),
+            MoveError::Intersecting(a, b) => write!(f, "faces {} and {} would intersect", a, b),
+        };
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+impl<V: HasPosition, E, F> Mesh<V, E, F> {
+    // Moves `v` to `position` unless that breaks one of `checks` among the
+    // faces around it, in which case nothing changes. Faces that are already
+    // degenerate can't invert, and faces that only touch, or cross within a
+    // shared plane, don't count as intersecting. Faces with more than three
+    // vertices are fanned from their first vertex.
+    pub fn try_move_vertex(&mut self, v: VertexIndex, position: Vec3, checks: MoveChecks) -> Result<(), MoveError> {
+        assert!(self.is_valid_vertex_index(v), "try_move_vertex: invalid vertex {}", v);
+        let mut faces: Vec<FaceIndex> = self.vertex(v).face_iter().map(|f| f.index()).collect();
+        faces.sort();
+        faces.dedup();
+        let corners: Vec<Vec<VertexIndex>> = faces.iter()
+            .map(|f| self.face(*f).vertex_iter().map(|u| u.index()).collect())
+            .collect();
+        let old = |u: VertexIndex| self.vertex(u).data().unwrap().position();
+        let new = |u: VertexIndex| if u == v { position } else { old(u) };
+
+        if checks.inversion {
+            for (f, corners) in faces.iter().zip(corners.iter()) {
+                let before = newell_normal(&corners.iter().map(|u| old(*u)).collect::<Vec<Vec3>>());
+                let after = newell_normal(&corners.iter().map(|u| new(*u)).collect::<Vec<Vec3>>());
+                if before != [0.0; 3] && dot(before, after) <= 0.0 {
+                    return Err(MoveError::Inverted(*f));
+                }
+            }
+        }
+
+        if checks.self_intersection {
+            let mut triangles: Vec<(FaceIndex, [VertexIndex; 3])> = Vec::new();
+            for (f, corners) in faces.iter().zip(corners.iter()) {
+                for j in 1..corners.len() - 1 {
+                    triangles.push((*f, [corners[0], corners[j], corners[j + 1]]));
+                }
+            }
+            for (i, a) in triangles.iter().enumerate() {
+                for b in triangles[i + 1..].iter() {
+                    // only triangles through `v` move.
+                    if a.0 == b.0 || !(a.1.contains(&v) || b.1.contains(&v)) {
+                        continue;
+                    }
+                    if crosses(&a.1, &b.1, &new) || crosses(&b.1, &a.1, &new) {
+                        return Err(MoveError::Intersecting(a.0, b.0));
+                    }
+                }
+            }
+        }
+
+        self.vertex_data_mut(v).unwrap().set_position(position);
+        return Ok(());
+    }
+}
+
+// true if a side of `a` that shares no vertex with `b` passes through b's
+// interior. A side with a shared end can only meet `b` there, unless the
+// two are coplanar.
+fn crosses<P: Fn(VertexIndex) -> Vec3>(a: &[VertexIndex; 3], b: &[VertexIndex; 3], position: &P) -> bool {
+    let t: Vec<Point3> = b.iter().map(|u| to_point3(position(*u))).collect();
+    for k in 0..3 {
+        let (p, q) = (a[k], a[(k + 1) % 3]);
+        if b.contains(&p) || b.contains(&q) {
+            continue;
+        }
+        let (p, q) = (to_point3(position(p)), to_point3(position(q)));
+        let (sp, sq) = (orient3d(t[0], t[1], t[2], p), orient3d(t[0], t[1], t[2], q));
+        if !(sp > 0.0 && sq < 0.0 || sp < 0.0 && sq > 0.0) {
+            continue;
+        }
+        let sides = [orient3d(p, q, t[0], t[1]), orient3d(p, q, t[1], t[2]), orient3d(p, q, t[2], t[0])];
+        if sides.iter().all(|s| *s > 0.0) || sides.iter().all(|s| *s < 0.0) {
+            return true;
+        }
+    }
+    return false;
+}
@@ -0,0 +1,121 @@
+use super::geometry::*;
+use super::index::*;
+
+// A named node in the scene hierarchy. Each node owns a selection of faces
+// in the underlying mesh and a transform relative to its parent, which is
+// enough to represent OBJ "o"/"g" groups and glTF nodes without flattening.
+#[derive(Clone)]
+pub struct Node {
+    pub name: String,
+    pub transform: Mat4,
+    pub faces: Vec<FaceIndex>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+impl Node {
+    pub fn parent(&self) -> Option<usize> {
+        return self.parent;
+    }
+
+    pub fn children(&self) -> &[usize] {
+        return &self.children;
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Scene {
+    nodes: Vec<Node>,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Scene { nodes: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        return self.nodes.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.is_empty();
+    }
+
+    // Adds an empty node with an identity transform and returns its id.
+    pub fn add_node(&mut self, name: &str, parent: Option<usize>) -> usize {
+        let id = self.nodes.len();
+        if let Some(p) = parent {
+            assert!(p < id, "parent node does not exist");
+            self.nodes[p].children.push(id);
+        }
+        self.nodes.push(Node {
+            name: name.to_string(),
+            transform: IDENTITY,
+            faces: Vec::new(),
+            parent: parent,
+            children: Vec::new(),
+        });
+        return id;
+    }
+
+    pub fn node(&self, id: usize) -> &Node {
+        return &self.nodes[id];
+    }
+
+    pub fn node_mut(&mut self, id: usize) -> &mut Node {
+        return &mut self.nodes[id];
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        return &self.nodes;
+    }
+
+    pub fn roots(&self) -> Vec<usize> {
+        return (0..self.nodes.len()).filter(|i| self.nodes[*i].parent.is_none()).collect();
+    }
+
+    // First node with the given name, in insertion order.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        return self.nodes.iter().position(|n| n.name == name);
+    }
+
+    // The node that owns a face, if any.
+    pub fn node_of_face(&self, face: FaceIndex) -> Option<usize> {
+        return self.nodes.iter().position(|n| n.faces.contains(&face));
+    }
+
+    // The transform from node space to scene space.
+    pub fn world_transform(&self, id: usize) -> Mat4 {
+        let mut m = self.nodes[id].transform;
+        let mut current = self.nodes[id].parent;
+        while let Some(p) = current {
+            m = mat4_mul(&self.nodes[p].transform, &m);
+            current = self.nodes[p].parent;
+        }
+        return m;
+    }
+
+    // Follows Mesh::remove_face(face), which returned `moved`: drops `face`
+    // from its node and renames the moved face to it.
+    pub fn swap_remove_face(&mut self, face: FaceIndex, moved: Option<FaceIndex>) {
+        for node in self.nodes.iter_mut() {
+            node.faces.retain(|f| *f != face);
+            if let Some(moved) = moved {
+                for f in node.faces.iter_mut() {
+                    if *f == moved {
+                        *f = face;
+                    }
+                }
+            }
+        }
+    }
+
+    // Applies a face reordering, e.g. the result of Mesh::sort_faces_by.
+    pub fn remap_faces(&mut self, remap: &[FaceIndex]) {
+        for node in self.nodes.iter_mut() {
+            for f in node.faces.iter_mut() {
+                *f = remap[f.index()];
+            }
+        }
+    }
+}
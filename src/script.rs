@@ -0,0 +1,124 @@
+use std::fmt;
+
+use rhai::{Array, Dynamic, Engine, Scope, AST};
+
+use super::dynamic::Value;
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+
+// A script failed to compile, failed on a vertex, or returned something
+// that isn't a Value (or, for displace, isn't a position).
+#[derive(Clone, PartialEq, Debug)]
+pub struct ScriptError {
+    pub vertex: Option<VertexIndex>,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self.vertex {
+            Some(v) => write!(f, "vertex {}: {}", v, self.message),
+            None => write!(f, "{}", self.message),
+        };
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+// A Rhai script run once per vertex, e.g. for procedural displacement.
+// It is compiled once and the same scope is reused for every vertex, which
+// sees these constants:
+//
+//   index           the vertex index
+//   x, y, z         its position
+//   position        the same as an array [x, y, z]
+//   valence         the number of neighbors
+//
+// The value of the script's last expression is the result for that vertex:
+// a bool, int, float, string, or an array of three numbers for a vector.
+pub struct VertexScript {
+    engine: Engine,
+    ast: AST,
+}
+
+impl VertexScript {
+    pub fn compile(source: &str) -> Result<Self, ScriptError> {
+        let engine = Engine::new();
+        let ast = engine.compile(source)
+            .map_err(|e| ScriptError { vertex: None, message: e.to_string() })?;
+        return Ok(VertexScript { engine: engine, ast: ast });
+    }
+
+    // The script's result for every vertex, indexed by vertex.
+    pub fn evaluate<V: HasPosition, E, F>(&self, mesh: &Mesh<V, E, F>) -> Result<Vec<Value>, ScriptError> {
+        let mut ret = Vec::with_capacity(mesh.vertex_iter().len());
+        let mut scope = Scope::new();
+        for v in mesh.vertex_iter() {
+            let position = v.data().unwrap().position();
+            let [x, y, z]: DVec3 = convert(position);
+            scope.clear();
+            scope.push_constant("index", v.index().0 as i64);
+            scope.push_constant("x", x);
+            scope.push_constant("y", y);
+            scope.push_constant("z", z);
+            scope.push_constant("position", vec![Dynamic::from(x), Dynamic::from(y), Dynamic::from(z)]);
+            scope.push_constant("valence", v.vertex_iter().count() as i64);
+            let error = |message: String| ScriptError { vertex: Some(v.index()), message: message };
+            let result: Dynamic = self.engine.eval_ast_with_scope(&mut scope, &self.ast)
+                .map_err(|e| error(e.to_string()))?;
+            let type_name = result.type_name();
+            ret.push(to_value(result).ok_or_else(|| error(format!("unsupported result type {}", type_name)))?);
+        }
+        return Ok(ret);
+    }
+
+    // Moves every vertex to the position the script returns for it. On an
+    // error the mesh is left untouched.
+    pub fn displace<V: HasPosition, E, F>(&self, mesh: &mut Mesh<V, E, F>) -> Result<(), ScriptError> {
+        let positions = self.evaluate(mesh)?.into_iter().enumerate().map(|(i, value)| {
+            return match value {
+                Value::Vector(p) => Ok(p),
+                other => Err(ScriptError {
+                    vertex: Some(VertexIndex::new(i)),
+                    message: format!("expected a position, got {:?}", other),
+                }),
+            };
+        }).collect::<Result<Vec<Vec3>, ScriptError>>()?;
+        for (i, position) in positions.into_iter().enumerate() {
+            mesh.vertex_data_mut(VertexIndex::new(i)).unwrap().set_position(position);
+        }
+        return Ok(());
+    }
+}
+
+fn to_value(result: Dynamic) -> Option<Value> {
+    if result.is::<bool>() {
+        return result.as_bool().ok().map(Value::Bool);
+    }
+    if result.is::<i64>() {
+        return result.as_int().ok().map(Value::Int);
+    }
+    if result.is::<f64>() {
+        return result.as_float().ok().map(Value::Float);
+    }
+    if result.is_string() {
+        return result.into_string().ok().map(Value::Text);
+    }
+    if result.is_array() {
+        let array: Array = result.cast();
+        if array.len() != 3 {
+            return None;
+        }
+        let mut ret = [0.0f32; 3];
+        for (i, component) in array.into_iter().enumerate() {
+            ret[i] = match to_value(component)? {
+                Value::Int(x) => x as f32,
+                Value::Float(x) => x as f32,
+                _ => return None,
+            };
+        }
+        return Some(Value::Vector(ret));
+    }
+    return None;
+}
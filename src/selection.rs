@@ -0,0 +1,287 @@
+use std::collections::BTreeSet;
+
+use super::bake::{Bvh, Triangles};
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+use super::tolerance::Tolerance;
+
+// Element kinds that can be selected. Vertices and faces are adjacent when
+// they share an edge, edges when they share a vertex.
+pub trait Selectable: IndexType + Ord {
+    fn adjacency<V, E, F>(mesh: &Mesh<V, E, F>) -> Vec<Vec<Self>>;
+}
+
+impl Selectable for VertexIndex {
+    fn adjacency<V, E, F>(mesh: &Mesh<V, E, F>) -> Vec<Vec<Self>> {
+        return mesh.vertex_iter()
+            .map(|v| v.vertex_iter().map(|n| n.index()).collect())
+            .collect();
+    }
+}
+
+impl Selectable for EdgeIndex {
+    fn adjacency<V, E, F>(mesh: &Mesh<V, E, F>) -> Vec<Vec<Self>> {
+        return mesh.edge_iter().map(|e| {
+            let mut ret: Vec<EdgeIndex> = e.vertices().iter()
+                .flat_map(|v| mesh.vertex(*v).edge_iter().map(|n| n.index()))
+                .filter(|n| *n != e.index())
+                .collect();
+            ret.sort();
+            ret.dedup();
+            return ret;
+        }).collect();
+    }
+}
+
+impl Selectable for FaceIndex {
+    fn adjacency<V, E, F>(mesh: &Mesh<V, E, F>) -> Vec<Vec<Self>> {
+        let mut ret = vec![Vec::new(); mesh.face_iter().len()];
+        for e in mesh.edge_iter() {
+            let faces = e.faces();
+            if faces.len() == 2 && faces[0] != faces[1] {
+                ret[faces[0].index()].push(faces[1]);
+                ret[faces[1].index()].push(faces[0]);
+            }
+        }
+        return ret;
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SelectionSet<I: Selectable> {
+    elements: BTreeSet<I>,
+}
+
+pub type VertexSelection = SelectionSet<VertexIndex>;
+pub type EdgeSelection = SelectionSet<EdgeIndex>;
+pub type FaceSelection = SelectionSet<FaceIndex>;
+
+impl<I: Selectable> SelectionSet<I> {
+    pub fn new() -> Self {
+        SelectionSet { elements: BTreeSet::new() }
+    }
+
+    pub fn from_slice(elements: &[I]) -> Self {
+        let mut ret = SelectionSet::new();
+        ret.elements.extend(elements.iter().cloned());
+        return ret;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.elements.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.elements.is_empty();
+    }
+
+    pub fn contains(&self, index: I) -> bool {
+        return self.elements.contains(&index);
+    }
+
+    pub fn insert(&mut self, index: I) -> bool {
+        return self.elements.insert(index);
+    }
+
+    pub fn remove(&mut self, index: I) -> bool {
+        return self.elements.remove(&index);
+    }
+
+    // Follows a removal that moved the element `moved` into `index`, like
+    // Mesh::remove_vertex or Mesh::remove_face.
+    pub fn swap_remove(&mut self, index: I, moved: Option<I>) {
+        self.elements.remove(&index);
+        if let Some(moved) = moved {
+            if self.elements.remove(&moved) {
+                self.elements.insert(index);
+            }
+        }
+    }
+
+    // Elements in ascending index order.
+    pub fn iter(&self) -> impl Iterator<Item = I> + '_ {
+        return self.elements.iter().cloned();
+    }
+
+    pub fn to_vec(&self) -> Vec<I> {
+        return self.iter().collect();
+    }
+
+    // Adds every element within `n` steps of the selection.
+    pub fn grow<V, E, F>(&mut self, mesh: &Mesh<V, E, F>, n: usize) {
+        let adjacency = I::adjacency(mesh);
+        for _ in 0..n {
+            let mut added = Vec::new();
+            for i in self.elements.iter() {
+                for j in adjacency[i.index()].iter() {
+                    if !self.elements.contains(j) {
+                        added.push(*j);
+                    }
+                }
+            }
+            if added.is_empty() {
+                break;
+            }
+            self.elements.extend(added);
+        }
+    }
+
+    // Removes every element within `n` steps of an unselected element.
+    pub fn shrink<V, E, F>(&mut self, mesh: &Mesh<V, E, F>, n: usize) {
+        let adjacency = I::adjacency(mesh);
+        for _ in 0..n {
+            let removed = self.border_elements(&adjacency);
+            if removed.is_empty() {
+                break;
+            }
+            for i in removed {
+                self.elements.remove(&i);
+            }
+        }
+    }
+
+    // The selected elements that have at least one unselected neighbour.
+    pub fn boundary<V, E, F>(&self, mesh: &Mesh<V, E, F>) -> SelectionSet<I> {
+        let adjacency = I::adjacency(mesh);
+        let mut ret = SelectionSet::new();
+        ret.elements.extend(self.border_elements(&adjacency));
+        return ret;
+    }
+
+    fn border_elements(&self, adjacency: &[Vec<I>]) -> Vec<I> {
+        return self.elements.iter()
+            .filter(|i| adjacency[i.index()].iter().any(|j| !self.elements.contains(j)))
+            .cloned()
+            .collect();
+    }
+}
+
+/*
+ * Screen-space selection
+ */
+
+// A box or lasso in the 2D space a view-projection matrix maps to, usually
+// normalized device coordinates.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ScreenRegion {
+    Rect([f32; 2], [f32; 2]), // opposite corners, in any order.
+    Lasso(Vec<[f32; 2]>),     // a closed polygon, which may self-intersect.
+}
+
+impl ScreenRegion {
+    pub fn contains(&self, p: [f32; 2]) -> bool {
+        match self {
+            ScreenRegion::Rect(a, b) => {
+                return (0..2).all(|i| a[i].min(b[i]) <= p[i] && p[i] <= a[i].max(b[i]));
+            }
+            ScreenRegion::Lasso(points) => {
+                // even-odd rule.
+                let mut inside = false;
+                for (i, a) in points.iter().enumerate() {
+                    let b = points[(i + 1) % points.len()];
+                    if (a[1] > p[1]) != (b[1] > p[1])
+                        && p[0] < a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]) {
+                        inside = !inside;
+                    }
+                }
+                return inside;
+            }
+        }
+    }
+}
+
+// Whether each vertex projects into the region. Vertices behind a
+// perspective camera never do. With `visible_only`, vertices hidden behind
+// the mesh's faces don't either.
+fn vertices_in_region<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, view_projection: &Mat4,
+                                            region: &ScreenRegion, visible_only: bool) -> Vec<bool> {
+    let mut ret: Vec<bool> = mesh.vertex_iter().map(|v| {
+        return match project_point(view_projection, v.data().unwrap().position()) {
+            Some(p) => region.contains([p[0], p[1]]),
+            None => false,
+        };
+    }).collect();
+    if !visible_only {
+        return ret;
+    }
+
+    let triangles = Triangles::new(mesh);
+    let bvh = Bvh::new(&triangles);
+    let eye = camera(view_projection);
+    // steps off the vertex, so the faces around it don't count.
+    let tolerance = Tolerance::from_mesh(mesh).distance;
+    for (v, inside) in mesh.vertex_iter().zip(ret.iter_mut()) {
+        if !*inside {
+            continue;
+        }
+        let p = v.data().unwrap().position();
+        let (direction, limit) = if eye[3] != 0.0 {
+            let eye = scale([eye[0], eye[1], eye[2]], 1.0 / eye[3]);
+            (normalize(sub(eye, p)), distance(eye, p) - 2.0 * tolerance)
+        } else {
+            // an orthographic camera looks along the same direction
+            // everywhere, from the side of smaller depth.
+            let mut direction = normalize([eye[0], eye[1], eye[2]]);
+            let depth = view_projection[2];
+            if dot([depth[0], depth[1], depth[2]], direction) > 0.0 {
+                direction = scale(direction, -1.0);
+            }
+            (direction, f32::INFINITY)
+        };
+        let origin = add(p, scale(direction, tolerance));
+        if limit > 0.0 && bvh.intersect(&triangles, origin, direction, limit).is_some() {
+            *inside = false;
+        }
+    }
+    return ret;
+}
+
+// The camera position, in homogeneous coordinates, of a view-projection
+// matrix: the point that x, y and w all map to zero. w is zero for an
+// orthographic camera, leaving its view direction.
+fn camera(view_projection: &Mat4) -> [f32; 4] {
+    let rows = [view_projection[0], view_projection[1], view_projection[3]];
+    let minor = |skip: usize| {
+        let columns: Vec<usize> = (0..4).filter(|c| *c != skip).collect();
+        let column = |r: usize| [rows[r][columns[0]], rows[r][columns[1]], rows[r][columns[2]]];
+        return dot(column(0), cross(column(1), column(2)));
+    };
+    return [minor(0), -minor(1), minor(2), -minor(3)];
+}
+
+impl VertexSelection {
+    pub fn from_region<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, view_projection: &Mat4,
+                                             region: &ScreenRegion, visible_only: bool) -> Self {
+        let inside = vertices_in_region(mesh, view_projection, region, visible_only);
+        let mut ret = SelectionSet::new();
+        ret.elements.extend(mesh.vertex_iter().map(|v| v.index()).filter(|v| inside[v.index()]));
+        return ret;
+    }
+}
+
+impl EdgeSelection {
+    // Edges with both endpoints in the region.
+    pub fn from_region<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, view_projection: &Mat4,
+                                             region: &ScreenRegion, visible_only: bool) -> Self {
+        let inside = vertices_in_region(mesh, view_projection, region, visible_only);
+        let mut ret = SelectionSet::new();
+        ret.elements.extend(mesh.edge_iter()
+            .filter(|e| e.vertices().iter().all(|v| inside[v.index()]))
+            .map(|e| e.index()));
+        return ret;
+    }
+}
+
+impl FaceSelection {
+    // Faces with all their vertices in the region.
+    pub fn from_region<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, view_projection: &Mat4,
+                                             region: &ScreenRegion, visible_only: bool) -> Self {
+        let inside = vertices_in_region(mesh, view_projection, region, visible_only);
+        let mut ret = SelectionSet::new();
+        ret.elements.extend(mesh.face_iter()
+            .filter(|f| f.vertex_iter().all(|v| inside[v.index().index()]))
+            .map(|f| f.index()));
+        return ret;
+    }
+}
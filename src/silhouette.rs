@@ -0,0 +1,63 @@
+use super::geometry::*;
+use super::index::*;
+use super::mesh::{HalfEdge, Mesh};
+
+// The edges a line renderer draws for a view, by kind. An edge can be both
+// a silhouette and a crease.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct FeatureEdges {
+    // Edges between a face turned towards the eye and one turned away,
+    // running the way the front face winds, so the silhouette of a closed
+    // mesh chains into loops, ready to extrude into shadow volumes.
+    pub silhouette: Vec<HalfEdge>,
+    // Edges whose two faces' normals differ by more than the crease angle.
+    pub crease: Vec<EdgeIndex>,
+    // Edges with one face.
+    pub border: Vec<EdgeIndex>,
+}
+
+// Classifies the edges of `mesh` as seen from `eye`. A face is turned
+// towards the eye if the eye is strictly in front of its plane, through its
+// centroid; `crease_angle` is in radians. Wire edges are none of these.
+pub fn feature_edges<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, eye: Vec3, crease_angle: f32) -> FeatureEdges {
+    let positions: Vec<Vec3> = mesh.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+    let mut normals = Vec::with_capacity(mesh.face_iter().len());
+    let mut front = Vec::with_capacity(mesh.face_iter().len());
+    for f in mesh.face_iter() {
+        let points: Vec<Vec3> = f.vertex_iter().map(|v| positions[v.index().index()]).collect();
+        let normal = normalize(newell_normal(&points));
+        normals.push(normal);
+        front.push(dot(normal, sub(eye, centroid(&points).unwrap())) > 0.0);
+    }
+    let cos_crease = crease_angle.cos();
+
+    let mut ret = FeatureEdges::default();
+    for e in mesh.edge_iter() {
+        let faces = e.faces();
+        if faces.len() == 1 {
+            ret.border.push(e.index());
+        }
+        if faces.len() != 2 {
+            continue;
+        }
+        let (f0, f1) = (faces[0].index(), faces[1].index());
+        if dot(normals[f0], normals[f1]) < cos_crease {
+            ret.crease.push(e.index());
+        }
+        if front[f0] != front[f1] {
+            let facing = if front[f0] { faces[0] } else { faces[1] };
+            let vertices = e.vertices();
+            let (a, b) = (vertices[0], vertices[1]);
+            // the front face runs a -> b if b follows a round it.
+            let corners: Vec<VertexIndex> = mesh.face(facing).vertex_iter().map(|v| v.index()).collect();
+            let i = corners.iter().position(|v| *v == a).unwrap();
+            let forward = corners[(i + 1) % corners.len()] == b;
+            ret.silhouette.push(if forward {
+                HalfEdge { edge: e.index(), from: a, to: b }
+            } else {
+                HalfEdge { edge: e.index(), from: b, to: a }
+            });
+        }
+    }
+    return ret;
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+
+// A polyline in a layer's plane. Closed contours of a solid run counter-
+// clockwise (seen from +z) around material and clockwise around holes;
+// open ones come from meshes with boundaries.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Contour {
+    pub points: Vec<[f32; 2]>,
+    pub closed: bool,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Layer {
+    pub z: f32,
+    pub contours: Vec<Contour>,
+}
+
+// The cross-section of the mesh's faces with the plane at height `z`, as
+// contours. Vertices exactly at `z` count as above it, so each crossing is
+// found once and contours chain through them.
+pub fn slice_at<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, z: f32) -> Vec<Contour> {
+    let positions: Vec<Vec3> = mesh.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+    let crossing = |e: EdgeIndex| {
+        let vertices = mesh.edge(e).vertices();
+        let (a, b) = (positions[vertices[0].index()], positions[vertices[1].index()]);
+        if (a[2] >= z) == (b[2] >= z) {
+            return None;
+        }
+        let t = (z - a[2]) / (b[2] - a[2]);
+        return Some([a[0] + t * (b[0] - a[0]), a[1] + t * (b[1] - a[1])]);
+    };
+
+    // One segment per pair of crossings along each face, keyed by the edges
+    // the ends lie on, and oriented so the face's outward side is on its right.
+    let mut segments: Vec<(EdgeIndex, EdgeIndex, [f32; 2], [f32; 2])> = Vec::new();
+    for f in mesh.face_iter() {
+        let points: Vec<Vec3> = f.vertex_iter().map(|v| positions[v.index().index()]).collect();
+        let normal = newell_normal(&points);
+        let right = [normal[0], normal[1]];
+        let along = [-normal[1], normal[0]];
+        let mut crossings: Vec<(EdgeIndex, [f32; 2])> = f.edge_iter()
+            .filter_map(|e| crossing(e.index()).map(|p| (e.index(), p)))
+            .collect();
+        crossings.sort_by(|a, b| dot2(a.1, along).total_cmp(&dot2(b.1, along)));
+        for pair in crossings.chunks_exact(2) {
+            let (mut a, mut b) = (pair[0], pair[1]);
+            if dot2(sub2(b.1, a.1), [-right[1], right[0]]) < 0.0 {
+                std::mem::swap(&mut a, &mut b);
+            }
+            segments.push((a.0, b.0, a.1, b.1));
+        }
+    }
+
+    let mut at_edge: HashMap<EdgeIndex, Vec<usize>> = HashMap::new();
+    for (i, s) in segments.iter().enumerate() {
+        at_edge.entry(s.0).or_default().push(i);
+        at_edge.entry(s.1).or_default().push(i);
+    }
+    let mut used = vec![false; segments.len()];
+    let mut ret = Vec::new();
+    // open contours have to be walked from one of their ends.
+    let is_end = |e: EdgeIndex| at_edge[&e].len() == 1;
+    let mut starts: Vec<usize> = (0..segments.len()).filter(|i| is_end(segments[*i].0) || is_end(segments[*i].1)).collect();
+    starts.extend(0..segments.len());
+    for start in starts {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let s = segments[start];
+        let (first_edge, mut edge, mut points, mut agreement) = if is_end(s.1) && !is_end(s.0) {
+            (s.1, s.0, vec![s.3, s.2], -1)
+        } else {
+            (s.0, s.1, vec![s.2, s.3], 1)
+        };
+        let mut closed = false;
+        while let Some(next) = at_edge[&edge].iter().copied().find(|i| !used[*i]) {
+            used[next] = true;
+            let n = segments[next];
+            let (to_edge, to_point) = if n.0 == edge { (n.1, n.3) } else { (n.0, n.2) };
+            agreement += if n.0 == edge { 1 } else { -1 };
+            if to_edge == first_edge {
+                closed = true;
+                break;
+            }
+            points.push(to_point);
+            edge = to_edge;
+        }
+        // most segments agree with their face's orientation.
+        if agreement < 0 {
+            points.reverse();
+        }
+        ret.push(Contour { points: points, closed: closed });
+    }
+    return ret;
+}
+
+// Slices the mesh every `layer_height` along z, mid-layer, from the bottom
+// of its bounding box up.
+pub fn slice<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, layer_height: f32) -> Vec<Layer> {
+    assert!(layer_height > 0.0, "layer height must be positive");
+    let (min, max) = match bounds(mesh.vertex_iter().map(|v| v.data().unwrap().position())) {
+        Some(b) => b,
+        None => return Vec::new(),
+    };
+    let count = ((max[2] - min[2]) / layer_height).ceil().max(1.0) as usize;
+    return (0..count).map(|i| {
+        let z = min[2] + (i as f32 + 0.5) * layer_height;
+        return Layer { z: z, contours: slice_at(mesh, z) };
+    }).collect();
+}
+
+// Generates the fill paths inside a layer's closed contours, for slicers to
+// plug in their own patterns (lines, grids, gyroids and so on).
+pub trait Infill {
+    // `index` is the layer's position in the stack, for patterns that
+    // change from layer to layer.
+    fn fill(&mut self, layer: &Layer, index: usize) -> Vec<Vec<[f32; 2]>>;
+}
+
+// Fills every layer of `layers` with `pattern`.
+pub fn infill<I: Infill>(layers: &[Layer], pattern: &mut I) -> Vec<Vec<Vec<[f32; 2]>>> {
+    return layers.iter().enumerate().map(|(i, layer)| pattern.fill(layer, i)).collect();
+}
+
+// Parallel lines `spacing` apart at `angle` radians from the x axis, turned
+// a quarter turn on odd layers when `alternate` is set.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct LineInfill {
+    pub spacing: f32,
+    pub angle: f32,
+    pub alternate: bool,
+}
+
+impl Infill for LineInfill {
+    fn fill(&mut self, layer: &Layer, index: usize) -> Vec<Vec<[f32; 2]>> {
+        assert!(self.spacing > 0.0, "infill spacing must be positive");
+        let mut angle = self.angle;
+        if self.alternate && index % 2 == 1 {
+            angle += std::f32::consts::FRAC_PI_2;
+        }
+        // Work in a frame where the lines run along x.
+        let (sin, cos) = angle.sin_cos();
+        let to_frame = |p: [f32; 2]| [p[0] * cos + p[1] * sin, p[1] * cos - p[0] * sin];
+        let from_frame = |p: [f32; 2]| [p[0] * cos - p[1] * sin, p[0] * sin + p[1] * cos];
+        let mut edges: Vec<([f32; 2], [f32; 2])> = Vec::new();
+        for contour in layer.contours.iter().filter(|c| c.closed) {
+            for (i, p) in contour.points.iter().enumerate() {
+                let q = contour.points[(i + 1) % contour.points.len()];
+                edges.push((to_frame(*p), to_frame(q)));
+            }
+        }
+        let mut ret = Vec::new();
+        if edges.is_empty() {
+            return ret;
+        }
+        let (lo, hi) = edges.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), (a, _)| (lo.min(a[1]), hi.max(a[1])));
+        // Scanlines on a fixed grid, so lines line up across layers, inside
+        // wherever an odd number of contour edges lie to the left.
+        let mut y = (lo / self.spacing).floor() * self.spacing;
+        while y <= hi {
+            let mut xs: Vec<f32> = edges.iter().filter(|(a, b)| (a[1] > y) != (b[1] > y))
+                .map(|(a, b)| a[0] + (y - a[1]) / (b[1] - a[1]) * (b[0] - a[0]))
+                .collect();
+            xs.sort_by(|a, b| a.total_cmp(b));
+            for pair in xs.chunks_exact(2) {
+                ret.push(vec![from_frame([pair[0], y]), from_frame([pair[1], y])]);
+            }
+            y += self.spacing;
+        }
+        return ret;
+    }
+}
+
+fn dot2(a: [f32; 2], b: [f32; 2]) -> f32 {
+    return a[0] * b[0] + a[1] * b[1];
+}
+
+fn sub2(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+    return [a[0] - b[0], a[1] - b[1]];
+}
@@ -0,0 +1,97 @@
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+use super::spatial::SpatialHash;
+
+// What a position was snapped to.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SnapTarget {
+    Vertex(VertexIndex),
+    Edge(EdgeIndex),
+    Grid,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Snap {
+    pub position: Vec3,
+    pub target: SnapTarget,
+}
+
+// Snaps positions to the vertices and edges of a mesh, which may or may not
+// be the mesh being edited. The mesh is borrowed for the snapper's lifetime,
+// so its index can't go stale; build a new one after editing.
+pub struct Snapper<'a, V, E, F> {
+    mesh: &'a Mesh<V, E, F>,
+    vertices: SpatialHash,
+    tolerance: f32,
+    max_edge_length: f32,
+}
+
+impl<'a, V: HasPosition, E, F> Snapper<'a, V, E, F> {
+    pub fn new(mesh: &'a Mesh<V, E, F>, tolerance: f32) -> Self {
+        assert!(tolerance > 0.0, "snap tolerance must be positive");
+        let max_edge_length = mesh.edge_iter().map(|e| {
+            let vertices = e.vertices();
+            return distance(mesh.vertices()[vertices[0]].position(), mesh.vertices()[vertices[1]].position());
+        }).fold(0.0, f32::max);
+        // Large enough that an edge query only touches a few cells.
+        let cell_size = tolerance.max(max_edge_length / 2.0);
+        return Snapper {
+            mesh: mesh,
+            vertices: SpatialHash::from_mesh(mesh, cell_size),
+            tolerance: tolerance,
+            max_edge_length: max_edge_length,
+        };
+    }
+
+    pub fn tolerance(&self) -> f32 {
+        return self.tolerance;
+    }
+
+    // The nearest vertex within tolerance, if any, and otherwise the nearest
+    // point on an edge. `moving` is the vertex being dragged, which never
+    // snaps to itself or to its own edges.
+    pub fn snap(&self, position: Vec3, moving: Option<VertexIndex>) -> Option<Snap> {
+        return self.snap_to_vertex(position, moving).or_else(|| self.snap_to_edge(position, moving));
+    }
+
+    pub fn snap_to_vertex(&self, position: Vec3, moving: Option<VertexIndex>) -> Option<Snap> {
+        let v = self.vertices.nearest_filtered(position, self.tolerance, |v| Some(v) != moving)?;
+        return Some(Snap { position: self.mesh.vertices()[v].position(), target: SnapTarget::Vertex(v) });
+    }
+
+    pub fn snap_to_edge(&self, position: Vec3, moving: Option<VertexIndex>) -> Option<Snap> {
+        // The closest point of an edge within tolerance puts one of its
+        // endpoints within tolerance + half the edge's length.
+        let radius = self.tolerance + self.max_edge_length / 2.0;
+        let mut best: Option<(Snap, f32)> = None;
+        for v in self.vertices.query_radius(position, radius) {
+            for e in self.mesh.vertex(v).edge_iter() {
+                let vertices = e.vertices();
+                if moving.is_some_and(|m| vertices.contains(&m)) {
+                    continue;
+                }
+                let a = self.mesh.vertices()[vertices[0]].position();
+                let b = self.mesh.vertices()[vertices[1]].position();
+                let (p, _) = closest_point_on_segment(position, a, b);
+                let d = distance(p, position);
+                // edges are reached from both endpoints; the lower index breaks ties.
+                let better = match best {
+                    Some((Snap { target: SnapTarget::Edge(be), .. }, bd)) => d < bd || (d == bd && e.index() < be),
+                    _ => true,
+                };
+                if d <= self.tolerance && better {
+                    best = Some((Snap { position: p, target: SnapTarget::Edge(e.index()) }, d));
+                }
+            }
+        }
+        return best.map(|(snap, _)| snap);
+    }
+}
+
+// Rounds each coordinate to the nearest multiple of `spacing`.
+pub fn snap_to_grid(position: Vec3, spacing: f32) -> Snap {
+    assert!(spacing > 0.0, "grid spacing must be positive");
+    let round = |x: f32| (x / spacing).round() * spacing;
+    return Snap { position: [round(position[0]), round(position[1]), round(position[2])], target: SnapTarget::Grid };
+}
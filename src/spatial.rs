@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+
+type Cell = (i32, i32, i32);
+
+// A uniform grid hash over vertex positions.
+//
+// The mesh has no mutation hooks, so the owner is expected to mirror vertex
+// insertions and moves with insert()/update() as they happen. Each update only
+// touches the two affected cells, so the index never needs a full rebuild.
+#[derive(Clone)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<Cell, Vec<VertexIndex>>,
+    positions: Vec<Option<Vec3>>,
+    len: usize,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "spatial hash cell size must be positive");
+        SpatialHash {
+            cell_size: cell_size,
+            cells: HashMap::new(),
+            positions: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn from_mesh<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, cell_size: f32) -> Self {
+        let mut hash = SpatialHash::new(cell_size);
+        for v in mesh.vertex_iter() {
+            hash.insert(v.index(), v.data().unwrap().position());
+        }
+        return hash;
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        return self.cell_size;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.len;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len == 0;
+    }
+
+    pub fn contains(&self, index: VertexIndex) -> bool {
+        return self.position(index).is_some();
+    }
+
+    pub fn position(&self, index: VertexIndex) -> Option<Vec3> {
+        return self.positions.get(index.index()).cloned().flatten();
+    }
+
+    // Inserting an index that is already present moves it.
+    pub fn insert(&mut self, index: VertexIndex, position: Vec3) {
+        if self.contains(index) {
+            self.update(index, position);
+            return;
+        }
+        if self.positions.len() <= index.index() {
+            self.positions.resize(index.index() + 1, None);
+        }
+        self.positions[index.index()] = Some(position);
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push(index);
+        self.len += 1;
+    }
+
+    // Returns false if the index was never inserted.
+    pub fn update(&mut self, index: VertexIndex, position: Vec3) -> bool {
+        let old_position = match self.position(index) {
+            Some(p) => p,
+            None => return false,
+        };
+        let old_cell = self.cell(old_position);
+        let new_cell = self.cell(position);
+        if old_cell != new_cell {
+            self.remove_from_cell(old_cell, index);
+            self.cells.entry(new_cell).or_default().push(index);
+        }
+        self.positions[index.index()] = Some(position);
+        return true;
+    }
+
+    // Returns false if the index was never inserted.
+    pub fn remove(&mut self, index: VertexIndex) -> bool {
+        let position = match self.position(index) {
+            Some(p) => p,
+            None => return false,
+        };
+        let cell = self.cell(position);
+        self.remove_from_cell(cell, index);
+        self.positions[index.index()] = None;
+        self.len -= 1;
+        return true;
+    }
+
+    // Follows Mesh::remove_vertex(index), which returned `moved`: drops
+    // `index` and files the moved vertex under it.
+    pub fn swap_remove(&mut self, index: VertexIndex, moved: Option<VertexIndex>) {
+        self.remove(index);
+        if let Some(moved) = moved {
+            if let Some(p) = self.position(moved) {
+                self.remove(moved);
+                self.insert(index, p);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.positions.clear();
+        self.len = 0;
+    }
+
+    // All indices within `radius` of `center`, in no particular order.
+    pub fn query_radius(&self, center: Vec3, radius: f32) -> Vec<VertexIndex> {
+        let mut ret = Vec::new();
+        let lo = self.cell(sub(center, [radius, radius, radius]));
+        let hi = self.cell(add(center, [radius, radius, radius]));
+        let radius_squared = radius * radius;
+        for x in lo.0..=hi.0 {
+            for y in lo.1..=hi.1 {
+                for z in lo.2..=hi.2 {
+                    if let Some(indices) = self.cells.get(&(x, y, z)) {
+                        for index in indices {
+                            let p = self.positions[index.index()].unwrap();
+                            if distance_squared(p, center) <= radius_squared {
+                                ret.push(*index);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return ret;
+    }
+
+    // The closest index to `point` that is no further than `max_radius`.
+    pub fn nearest(&self, point: Vec3, max_radius: f32) -> Option<VertexIndex> {
+        return self.nearest_filtered(point, max_radius, |_| true);
+    }
+
+    // Like nearest(), but only considers indices accepted by `filter`.
+    pub fn nearest_filtered<P>(&self, point: Vec3, max_radius: f32, filter: P) -> Option<VertexIndex>
+        where P: Fn(VertexIndex) -> bool {
+        let center = self.cell(point);
+        let max_ring = (max_radius / self.cell_size).ceil() as i32 + 1;
+        let mut best: Option<(VertexIndex, f32)> = None;
+        for ring in 0..=max_ring {
+            // Anything in this ring or beyond is at least (ring - 1) cells away.
+            if let Some((_, best_distance)) = best {
+                if best_distance <= (ring - 1) as f32 * self.cell_size {
+                    break;
+                }
+            }
+            for x in -ring..=ring {
+                for y in -ring..=ring {
+                    for z in -ring..=ring {
+                        if x.abs() != ring && y.abs() != ring && z.abs() != ring {
+                            continue;
+                        }
+                        let cell = (center.0 + x, center.1 + y, center.2 + z);
+                        if let Some(indices) = self.cells.get(&cell) {
+                            for index in indices {
+                                if !filter(*index) {
+                                    continue;
+                                }
+                                let p = self.positions[index.index()].unwrap();
+                                let d = distance(p, point);
+                                if d <= max_radius && best.is_none_or(|(_, bd)| d < bd) {
+                                    best = Some((*index, d));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return best.map(|(index, _)| index);
+    }
+
+    fn cell(&self, p: Vec3) -> Cell {
+        return (
+            (p[0] / self.cell_size).floor() as i32,
+            (p[1] / self.cell_size).floor() as i32,
+            (p[2] / self.cell_size).floor() as i32,
+        );
+    }
+
+    fn remove_from_cell(&mut self, cell: Cell, index: VertexIndex) {
+        let mut now_empty = false;
+        if let Some(indices) = self.cells.get_mut(&cell) {
+            if let Some(i) = indices.iter().position(|x| *x == index) {
+                indices.swap_remove(i);
+            }
+            now_empty = indices.is_empty();
+        }
+        if now_empty {
+            self.cells.remove(&cell);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::fmt;
+
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+
+// A one-line overview of a mesh, for logging what was imported or produced.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MeshSummary {
+    pub vertices: usize,
+    pub edges: usize,
+    pub faces: usize,
+    // Edges with no faces at all.
+    pub wire_edges: usize,
+    // Closed chains of edges with exactly one face.
+    pub boundary_loops: usize,
+    // Connected components, counting isolated vertices.
+    pub components: usize,
+    pub bounds: Option<(Vec3, Vec3)>,
+    pub memory: usize,
+}
+
+impl<V, E, F> Mesh<V, E, F> {
+    pub fn summary(&self) -> MeshSummary {
+        let mut components = DisjointSet::new(self.vertex_iter().len());
+        let mut boundary = DisjointSet::new(self.vertex_iter().len());
+        let mut boundary_vertices = vec![false; self.vertex_iter().len()];
+        let mut wire_edges = 0;
+        for e in self.edge_iter() {
+            let vertices = e.vertices();
+            let (a, b) = (vertices[0].index(), vertices[1].index());
+            components.union(a, b);
+            match e.faces().len() {
+                0 => wire_edges += 1,
+                1 => {
+                    boundary.union(a, b);
+                    boundary_vertices[a] = true;
+                    boundary_vertices[b] = true;
+                },
+                _ => {},
+            }
+        }
+        let boundary_loops = (0..boundary_vertices.len())
+            .filter(|i| boundary_vertices[*i] && boundary.find(*i) == *i)
+            .count();
+
+        return MeshSummary {
+            vertices: self.vertex_iter().len(),
+            edges: self.edge_iter().len(),
+            faces: self.face_iter().len(),
+            wire_edges: wire_edges,
+            boundary_loops: boundary_loops,
+            components: components.roots(),
+            bounds: None,
+            memory: self.memory_usage(),
+        };
+    }
+
+    // summary() plus the bounding box of the vertex positions.
+    pub fn summary_with_bounds(&self) -> MeshSummary where V: HasPosition {
+        let mut ret = self.summary();
+        ret.bounds = bounds(self.vertex_iter().map(|v| v.data().unwrap().position()));
+        return ret;
+    }
+}
+
+impl MeshSummary {
+    // Emits the summary as an info event on the current span.
+    #[cfg(feature = "tracing")]
+    pub fn trace(&self) {
+        tracing::info!(
+            vertices = self.vertices,
+            edges = self.edges,
+            faces = self.faces,
+            wire_edges = self.wire_edges,
+            boundary_loops = self.boundary_loops,
+            components = self.components,
+            bounds = ?self.bounds,
+            memory = self.memory,
+            "mesh summary"
+        );
+    }
+}
+
+impl fmt::Display for MeshSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} vertices, {} edges, {} faces", self.vertices, self.edges, self.faces)?;
+        if self.wire_edges > 0 {
+            write!(f, " ({} wire)", self.wire_edges)?;
+        }
+        write!(f, ", {} components, {} boundary loops", self.components, self.boundary_loops)?;
+        if let Some((min, max)) = self.bounds {
+            write!(f, ", bounds [{}, {}, {}]..[{}, {}, {}]",
+                   min[0], min[1], min[2], max[0], max[1], max[2])?;
+        }
+        return write!(f, ", {}", format_bytes(self.memory));
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        return format!("{} B", bytes);
+    }
+    return format!("{:.1} {}", size, UNITS[unit]);
+}
+
+// Union-find over 0..n with path halving.
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self {
+        DisjointSet { parent: (0..n).collect() }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        return i;
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[ra.max(rb)] = ra.min(rb);
+        }
+    }
+
+    fn roots(&mut self) -> usize {
+        return (0..self.parent.len()).filter(|i| self.find(*i) == *i).count();
+    }
+}
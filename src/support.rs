@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+
+use super::bake::{Bvh, Triangles};
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+use super::tolerance::Tolerance;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SupportOptions {
+    // The steepest overhang that prints without support, in radians from
+    // vertical: 0 supports everything facing down, pi / 2 nothing.
+    pub max_overhang: f32,
+    // Pillars stand on a square grid this far apart, aligned to the origin
+    // so neighbouring overhangs share it.
+    pub spacing: f32,
+    pub radius: f32,
+    // Sides of each pillar's prism.
+    pub sides: usize,
+}
+
+impl Default for SupportOptions {
+    fn default() -> Self {
+        SupportOptions {
+            max_overhang: std::f32::consts::FRAC_PI_4,
+            spacing: 2.0,
+            radius: 0.4,
+            sides: 6,
+        }
+    }
+}
+
+// The faces that point down more steeply than `max_overhang` (see
+// SupportOptions), leaving out those resting on the build plate at the
+// bottom of the mesh.
+pub fn overhangs<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, max_overhang: f32) -> Vec<FaceIndex> {
+    let positions: Vec<Vec3> = mesh.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+    let plate = match bounds(positions.iter().copied()) {
+        Some((min, _)) => min[2],
+        None => return Vec::new(),
+    };
+    let tolerance = Tolerance::from_mesh(mesh).distance;
+    let mut ret = Vec::new();
+    for f in mesh.face_iter() {
+        let points: Vec<Vec3> = f.vertex_iter().map(|v| positions[v.index().index()]).collect();
+        let normal = newell_normal(&points);
+        let area = length(normal);
+        if area == 0.0 || !overhanging(normal[2] / area, max_overhang) {
+            continue;
+        }
+        if points.iter().all(|p| p[2] - plate <= tolerance) {
+            continue;
+        }
+        ret.push(f.index());
+    }
+    return ret;
+}
+
+// Whether a face whose unit normal has `up` as its upward component hangs
+// over more than `max_overhang`. A face tilted by a from vertical has a
+// normal pointing sin(a) down.
+fn overhanging(up: f32, max_overhang: f32) -> bool {
+    return -up > max_overhang.sin();
+}
+
+// Pillars from the build plate, at the bottom of the mesh, up to its
+// overhangs. Grid points under an overhang get a pillar up to the lowest
+// overhang above them, unless some other part of the mesh is in the way;
+// supports resting on the model itself are left to the caller. The pillars
+// are closed prisms, returned together as one mesh.
+//
+// Faces with more than three vertices are fanned from their first vertex.
+pub fn supports<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, options: &SupportOptions) -> Mesh<Vec3, (), ()> {
+    assert!(options.spacing > 0.0, "support spacing must be positive");
+    assert!(options.sides >= 3, "support pillars need at least 3 sides");
+    let mut ret = Mesh::new();
+    let positions: Vec<Vec3> = mesh.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+    let plate = match bounds(positions.iter().copied()) {
+        Some((min, _)) => min[2],
+        None => return ret,
+    };
+    let tolerance = Tolerance::from_mesh(mesh).distance;
+
+    // The lowest overhang over each grid cell, sorted so the pillars come
+    // out in a repeatable order.
+    let mut tops: BTreeMap<(i64, i64), f32> = BTreeMap::new();
+    for f in overhangs(mesh, options.max_overhang) {
+        let points: Vec<Vec3> = mesh.face(f).vertex_iter().map(|v| positions[v.index().index()]).collect();
+        for j in 1..points.len() - 1 {
+            let (a, b, c) = (points[0], points[j], points[j + 1]);
+            // seen from above.
+            let (a2, b2, c2) = ([a[0], a[1]], [b[0], b[1]], [c[0], c[1]]);
+            let area = edge_function(a2, b2, c2);
+            if area == 0.0 {
+                continue;
+            }
+            let (lo, hi) = bounds([a, b, c]).unwrap();
+            // the grid points from lo to hi, inclusive.
+            let first = |x: f32| (x / options.spacing - 0.5).ceil() as i64;
+            let last = |x: f32| (x / options.spacing - 0.5).floor() as i64;
+            for i in first(lo[0])..=last(hi[0]) {
+                for k in first(lo[1])..=last(hi[1]) {
+                    let p = [(i as f32 + 0.5) * options.spacing, (k as f32 + 0.5) * options.spacing];
+                    let w = [edge_function(b2, c2, p) / area, edge_function(c2, a2, p) / area,
+                             edge_function(a2, b2, p) / area];
+                    if w.iter().any(|w| *w < 0.0) {
+                        continue;
+                    }
+                    let z = w[0] * a[2] + w[1] * b[2] + w[2] * c[2];
+                    let top = tops.entry((i, k)).or_insert(z);
+                    *top = top.min(z);
+                }
+            }
+        }
+    }
+
+    let triangles = Triangles::new(mesh);
+    let bvh = Bvh::new(&triangles);
+    for ((i, k), top) in tops {
+        let (x, y) = ((i as f32 + 0.5) * options.spacing, (k as f32 + 0.5) * options.spacing);
+        // Just under the overhang down to just over the plate, so neither
+        // counts as being in the way.
+        let height = top - plate - 2.0 * tolerance;
+        if height > 0.0 && bvh.intersect(&triangles, [x, y, top - tolerance], [0.0, 0.0, -1.0], height).is_some() {
+            continue;
+        }
+        add_pillar(&mut ret, [x, y], plate, top, options);
+    }
+    return ret;
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum OrientCost {
+    // The total area of the overhanging faces.
+    OverhangArea,
+    // The volume between the overhanging faces and the build plate, which
+    // is roughly what supports() fills.
+    SupportVolume,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct OrientOptions {
+    pub max_overhang: f32,
+    pub cost: OrientCost,
+    // Up directions tried, spread evenly over the sphere. The axes and the
+    // outward normals of the largest faces, to lay them on the plate, are
+    // tried as well.
+    pub samples: usize,
+}
+
+impl Default for OrientOptions {
+    fn default() -> Self {
+        OrientOptions {
+            max_overhang: std::f32::consts::FRAC_PI_4,
+            cost: OrientCost::SupportVolume,
+            samples: 256,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Orientation {
+    // Rotation about the origin turning the mesh to print with the chosen
+    // direction up along z.
+    pub transform: Mat4,
+    pub overhang_area: f32,
+    pub support_volume: f32,
+}
+
+// Searches for the way up that needs the least support, scoring each
+// candidate direction from the faces' normals, areas and heights alone,
+// without building any supports. The current orientation wins ties.
+pub fn optimize_orientation<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, options: &OrientOptions) -> Orientation {
+    let positions: Vec<Vec3> = mesh.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+    let tolerance = Tolerance::from_mesh(mesh).distance;
+    // unit normal, area, centroid and vertices of each face.
+    let mut faces: Vec<(Vec3, f32, Vec3, Vec<usize>)> = Vec::new();
+    for f in mesh.face_iter() {
+        let vertices: Vec<usize> = f.vertex_iter().map(|v| v.index().index()).collect();
+        let points: Vec<Vec3> = vertices.iter().map(|v| positions[*v]).collect();
+        let normal = newell_normal(&points);
+        let area = length(normal);
+        if area > 0.0 {
+            faces.push((scale(normal, 1.0 / area), area * 0.5, centroid(&points).unwrap(), vertices));
+        }
+    }
+
+    let mut candidates: Vec<Vec3> = vec![[0.0, 0.0, 1.0], [0.0, 0.0, -1.0], [1.0, 0.0, 0.0], [-1.0, 0.0, 0.0],
+                                         [0.0, 1.0, 0.0], [0.0, -1.0, 0.0]];
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    for i in 0..options.samples {
+        let z = 1.0 - 2.0 * (i as f32 + 0.5) / options.samples as f32;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let phi = i as f32 * golden_angle;
+        candidates.push([r * phi.cos(), r * phi.sin(), z]);
+    }
+    let mut largest: Vec<usize> = (0..faces.len()).collect();
+    largest.sort_by(|a, b| faces[*b].1.total_cmp(&faces[*a].1));
+    candidates.extend(largest.iter().take(options.samples).map(|f| scale(faces[*f].0, -1.0)));
+
+    let mut best = (f32::INFINITY, 0.0, 0.0, [0.0, 0.0, 1.0]);
+    for up in candidates {
+        let heights: Vec<f32> = positions.iter().map(|p| dot(*p, up)).collect();
+        let plate = heights.iter().copied().fold(f32::INFINITY, f32::min);
+        let (mut area, mut volume) = (0.0, 0.0);
+        for (normal, face_area, center, vertices) in faces.iter() {
+            let facing = dot(*normal, up);
+            if !overhanging(facing, options.max_overhang) || vertices.iter().all(|v| heights[*v] - plate <= tolerance) {
+                continue;
+            }
+            area += face_area;
+            volume += face_area * -facing * (dot(*center, up) - plate);
+        }
+        let cost = match options.cost {
+            OrientCost::OverhangArea => area,
+            OrientCost::SupportVolume => volume,
+        };
+        if cost < best.0 {
+            best = (cost, area, volume, up);
+        }
+    }
+    return Orientation { transform: rotation_to_z(best.3), overhang_area: best.1, support_volume: best.2 };
+}
+
+// The rotation turning the unit vector `up` onto +z by the shortest arc.
+fn rotation_to_z(up: Vec3) -> Mat4 {
+    let axis = cross(up, [0.0, 0.0, 1.0]);
+    let (sin, cos) = (length(axis), up[2]);
+    if sin <= 1e-6 {
+        // straight up needs nothing; straight down a half turn about x.
+        return if cos > 0.0 { IDENTITY } else { [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, -1.0, 0.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ] };
+    }
+    // Rodrigues' formula.
+    let k = scale(axis, 1.0 / sin);
+    let t = 1.0 - cos;
+    return [
+        [cos + k[0] * k[0] * t, k[0] * k[1] * t - k[2] * sin, k[0] * k[2] * t + k[1] * sin, 0.0],
+        [k[1] * k[0] * t + k[2] * sin, cos + k[1] * k[1] * t, k[1] * k[2] * t - k[0] * sin, 0.0],
+        [k[2] * k[0] * t - k[1] * sin, k[2] * k[1] * t + k[0] * sin, cos + k[2] * k[2] * t, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+}
+
+fn add_pillar(mesh: &mut Mesh<Vec3, (), ()>, center: [f32; 2], bottom: f32, top: f32, options: &SupportOptions) {
+    let n = options.sides;
+    let ring = |z: f32| -> Vec<Vec3> {
+        return (0..n).map(|i| {
+            let angle = i as f32 * 2.0 * std::f32::consts::PI / n as f32;
+            return [center[0] + options.radius * angle.cos(), center[1] + options.radius * angle.sin(), z];
+        }).collect();
+    };
+    let lower: Vec<VertexIndex> = ring(bottom).into_iter().map(|p| mesh.add_vertex(p)).collect();
+    let upper: Vec<VertexIndex> = ring(top).into_iter().map(|p| mesh.add_vertex(p)).collect();
+    for i in 0..n {
+        let j = (i + 1) % n;
+        mesh.add_default_face(&[lower[i], lower[j], upper[j], upper[i]]);
+    }
+    mesh.add_default_face(&upper);
+    mesh.add_default_face(&lower.into_iter().rev().collect::<Vec<_>>());
+}
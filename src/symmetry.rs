@@ -0,0 +1,122 @@
+use super::edge_map::EdgeKey;
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+use super::spatial::SpatialHash;
+use super::tolerance::Tolerance;
+
+// A reflective symmetry: the plane through `point` with unit `normal`, and
+// for every vertex the vertex it mirrors onto (itself if on the plane).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Symmetry {
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub mirror: Vec<VertexIndex>,
+}
+
+impl Symmetry {
+    pub fn reflect(&self, p: Vec3) -> Vec3 {
+        return sub(p, scale(self.normal, 2.0 * dot(sub(p, self.point), self.normal)));
+    }
+}
+
+// Finds the planes the mesh is mirror symmetric across: every vertex
+// reflects onto a vertex within `tolerance`, and every edge onto an edge.
+//
+// A symmetry plane passes through the centroid, and maps any vertex off the
+// plane onto another vertex just as far from the centroid. So candidates
+// are the bisecting planes between a few anchor vertices, chosen so that no
+// plane can contain them all, and the vertices they could mirror onto.
+// The plane of a flat mesh, which mirrors every vertex onto itself, is not
+// reported.
+pub fn find_symmetries<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>, tolerance: &Tolerance) -> Vec<Symmetry> {
+    let positions: Vec<Vec3> = mesh.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+    let center = match centroid(&positions) {
+        Some(c) => c,
+        None => return Vec::new(),
+    };
+    let cell_size = if tolerance.distance > 0.0 { tolerance.distance } else { 1.0 };
+    let mut index = SpatialHash::new(cell_size);
+    for (i, p) in positions.iter().enumerate() {
+        index.insert(VertexIndex::new(i), *p);
+    }
+    let edges = mesh.edge_map();
+
+    let mut ret: Vec<Symmetry> = Vec::new();
+    for anchor in anchors(&positions, center, tolerance) {
+        let radius = distance(positions[anchor], center);
+        for (i, p) in positions.iter().enumerate() {
+            if i == anchor || !tolerance.is_zero(distance(*p, center) - radius) {
+                continue;
+            }
+            let normal = canonical(normalize(sub(positions[anchor], *p)));
+            if ret.iter().any(|s| tolerance.is_zero(length(sub(s.normal, normal)))) {
+                continue;
+            }
+            let mut candidate = Symmetry { point: center, normal: normal, mirror: Vec::new() };
+            if let Some(mirror) = match_vertices(&candidate, &positions, &index, tolerance) {
+                let maps_edges = mesh.edge_iter().all(|e| {
+                    let vertices = e.vertices();
+                    let key = EdgeKey::new(mirror[vertices[0].index()], mirror[vertices[1].index()]);
+                    return edges.contains_key(&key);
+                });
+                if maps_edges {
+                    candidate.mirror = mirror;
+                    ret.push(candidate);
+                }
+            }
+        }
+    }
+    return ret;
+}
+
+// Up to three vertices that, with the centroid, no single plane contains
+// (fewer if the mesh is flat or collinear). Farthest from the centroid
+// first, since those pin down the plane best.
+fn anchors(positions: &[Vec3], center: Vec3, tolerance: &Tolerance) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..positions.len()).collect();
+    order.sort_by(|a, b| {
+        return distance(positions[*b], center).total_cmp(&distance(positions[*a], center)).then(a.cmp(b));
+    });
+    let mut ret: Vec<usize> = Vec::new();
+    for i in order {
+        let d = sub(positions[i], center);
+        let independent = match ret.len() {
+            0 => !tolerance.is_zero(length(d)),
+            1 => !tolerance.is_zero(length(cross(sub(positions[ret[0]], center), normalize(d)))),
+            2 => {
+                let normal = cross(sub(positions[ret[0]], center), sub(positions[ret[1]], center));
+                !tolerance.is_zero(dot(normalize(normal), d))
+            }
+            _ => break,
+        };
+        if independent {
+            ret.push(i);
+        }
+    }
+    return ret;
+}
+
+// The mirror image of every vertex, or None if some vertex has none.
+fn match_vertices(symmetry: &Symmetry, positions: &[Vec3], index: &SpatialHash,
+                  tolerance: &Tolerance) -> Option<Vec<VertexIndex>> {
+    let mut ret = Vec::with_capacity(positions.len());
+    for p in positions {
+        ret.push(index.nearest(symmetry.reflect(*p), tolerance.distance)?);
+    }
+    // a reflection is its own inverse, so the matching must be too.
+    if (0..ret.len()).any(|i| ret[ret[i].index()].index() != i) {
+        return None;
+    }
+    return Some(ret);
+}
+
+// n or -n, whichever has its first non-zero component positive.
+fn canonical(n: Vec3) -> Vec3 {
+    for x in n.iter() {
+        if *x != 0.0 {
+            return if *x > 0.0 { n } else { scale(n, -1.0) };
+        }
+    }
+    return n;
+}
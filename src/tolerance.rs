@@ -0,0 +1,79 @@
+use super::geometry::*;
+use super::mesh::Mesh;
+
+// How close two things must be to count as the same, for welding, planarity
+// and degeneracy checks. A fixed epsilon is wrong for either a millimetre or
+// a kilometre sized mesh, so from_mesh() scales it by the mesh's extent.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Tolerance {
+    pub distance: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance { distance: Tolerance::DEFAULT_DISTANCE }
+    }
+}
+
+impl Tolerance {
+    pub const DEFAULT_DISTANCE: f32 = 1e-5;
+    // Fraction of the bounding box diagonal used by for_bounds(). f32 only
+    // carries about seven significant digits, so going much lower is noise.
+    pub const RELATIVE_DISTANCE: f32 = 1e-5;
+
+    pub fn new(distance: f32) -> Self {
+        assert!(distance >= 0.0, "tolerance distance must not be negative");
+        Tolerance { distance: distance }
+    }
+
+    pub fn for_bounds(min: Vec3, max: Vec3) -> Self {
+        let diagonal = distance(min, max);
+        if diagonal > 0.0 {
+            return Tolerance::new(diagonal * Tolerance::RELATIVE_DISTANCE);
+        }
+        return Tolerance::default();
+    }
+
+    // Scaled to the mesh's bounding box, or the default for an empty mesh.
+    pub fn from_mesh<V: HasPosition, E, F>(mesh: &Mesh<V, E, F>) -> Self {
+        let positions = mesh.vertex_iter().map(|v| v.data().unwrap().position());
+        match bounds(positions) {
+            Some((min, max)) => Tolerance::for_bounds(min, max),
+            None => Tolerance::default(),
+        }
+    }
+
+    pub fn is_zero(&self, d: f32) -> bool {
+        return d.abs() <= self.distance;
+    }
+
+    pub fn coincident(&self, a: Vec3, b: Vec3) -> bool {
+        return distance_squared(a, b) <= self.distance * self.distance;
+    }
+
+    // True if the triangle's height over its longest edge is within
+    // tolerance, i.e. it has collapsed to a segment or a point.
+    pub fn is_degenerate_triangle(&self, a: Vec3, b: Vec3, c: Vec3) -> bool {
+        let longest = distance(a, b).max(distance(b, c)).max(distance(c, a));
+        if self.is_zero(longest) {
+            return true;
+        }
+        let double_area = length(cross(sub(b, a), sub(c, a)));
+        return self.is_zero(double_area / longest);
+    }
+
+    // True if every point is within tolerance of a common plane. Collinear
+    // and smaller point sets are trivially planar.
+    pub fn is_planar(&self, points: &[Vec3]) -> bool {
+        if points.len() < 4 {
+            return true;
+        }
+        let normal = newell_normal(points);
+        if length(normal) == 0.0 {
+            return true;
+        }
+        let normal = normalize(normal);
+        let centroid = centroid(points).unwrap();
+        return points.iter().all(|p| self.is_zero(dot(sub(*p, centroid), normal)));
+    }
+}
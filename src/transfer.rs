@@ -0,0 +1,121 @@
+use super::dynamic::{Attributes, Value};
+use super::geometry::*;
+use super::index::*;
+use super::mesh::Mesh;
+use super::spatial::SpatialHash;
+
+// For each vertex of `dst`, finds the closest point on `src` (on a face, an
+// edge, or an isolated vertex) and calls `mapping` with the source vertices
+// around that point and their interpolation weights, which sum to one, to
+// fill in the destination vertex. Used to carry colors, UVs or weights from
+// a scan over to its retopologized mesh; see interpolate_attributes() for
+// attribute meshes.
+//
+// Faces with more than three vertices are fanned from their first vertex.
+pub fn transfer_attributes<V, E, F, V2, E2, F2, M>(src: &Mesh<V, E, F>, dst: &mut Mesh<V2, E2, F2>, mut mapping: M)
+    where V: HasPosition, V2: HasPosition, M: FnMut(&[(&V, f32)], &mut V2) {
+    let positions: Vec<Vec3> = src.vertex_iter().map(|v| v.data().unwrap().position()).collect();
+    let center = match centroid(&positions) {
+        Some(c) => c,
+        None => return,
+    };
+    let radius = positions.iter().map(|p| distance(*p, center)).fold(0.0, f32::max);
+    // Every point of a face or edge is within `reach` of one of its vertices.
+    let mut reach: f32 = 0.0;
+    for e in src.edge_iter() {
+        let vertices = e.vertices();
+        reach = reach.max(distance(positions[vertices[0].index()], positions[vertices[1].index()]));
+    }
+    for f in src.face_iter() {
+        let vertices: Vec<VertexIndex> = f.vertex_iter().map(|v| v.index()).collect();
+        for v in vertices.iter() {
+            reach = reach.max(distance(positions[vertices[0].index()], positions[v.index()]));
+        }
+    }
+    let cell_size = if reach > 0.0 { reach } else if radius > 0.0 { radius } else { 1.0 };
+    let mut index = SpatialHash::new(cell_size);
+    for (i, p) in positions.iter().enumerate() {
+        index.insert(VertexIndex::new(i), *p);
+    }
+
+    let data = src.vertices();
+    for i in 0..dst.vertex_iter().len() {
+        let target = VertexIndex::new(i);
+        let p = dst.vertices()[target].position();
+        let nearest = index.nearest(p, distance(p, center) + radius + cell_size).unwrap();
+        let mut best = Closest { distance: distance(p, positions[nearest.index()]), weights: vec![(nearest, 1.0)] };
+        // the closest point's element has a vertex within this.
+        for v in index.query_radius(p, best.distance + reach) {
+            for e in src.vertex(v).edge_iter() {
+                let vertices = e.vertices();
+                let (a, b) = (vertices[0], vertices[1]);
+                let (q, t) = closest_point_on_segment(p, positions[a.index()], positions[b.index()]);
+                best.offer(distance(p, q), &[(a, 1.0 - t), (b, t)]);
+            }
+            for f in src.vertex(v).face_iter() {
+                let vertices: Vec<VertexIndex> = f.vertex_iter().map(|v| v.index()).collect();
+                for j in 1..vertices.len() - 1 {
+                    let (a, b, c) = (vertices[0], vertices[j], vertices[j + 1]);
+                    let (q, w) = closest_point_on_triangle(p, positions[a.index()], positions[b.index()],
+                                                           positions[c.index()]);
+                    best.offer(distance(p, q), &[(a, w[0]), (b, w[1]), (c, w[2])]);
+                }
+            }
+        }
+        let sources: Vec<(&V, f32)> = best.weights.iter().map(|(v, w)| (&data[*v], *w)).collect();
+        mapping(&sources, dst.vertex_data_mut(target).unwrap());
+    }
+}
+
+struct Closest {
+    distance: f32,
+    weights: Vec<(VertexIndex, f32)>,
+}
+
+impl Closest {
+    // Keeps the first of equally close points.
+    fn offer(&mut self, distance: f32, weights: &[(VertexIndex, f32)]) {
+        if distance < self.distance {
+            self.distance = distance;
+            self.weights = weights.iter().filter(|(_, w)| *w > 0.0).cloned().collect();
+        }
+    }
+}
+
+// A mapping for transfer_attributes() between attribute meshes that blends
+// the named attributes: floats and vectors (colors, UVs, weights) are
+// interpolated, and other values are copied from the most heavily weighted
+// source vertex. Source vertices missing an attribute don't contribute to
+// it, and destination attributes none of them have are left alone.
+pub fn interpolate_attributes(names: &[&str]) -> impl FnMut(&[(&Attributes, f32)], &mut Attributes) {
+    let names: Vec<String> = names.iter().map(|s| s.to_string()).collect();
+    return move |sources: &[(&Attributes, f32)], dst: &mut Attributes| {
+        for name in names.iter() {
+            if let Some(value) = interpolate(sources, name) {
+                dst.set(name, value);
+            }
+        }
+    };
+}
+
+fn interpolate(sources: &[(&Attributes, f32)], name: &str) -> Option<Value> {
+    let heaviest = sources.iter()
+        .filter(|(a, _)| a.get(name).is_some())
+        .fold(None, |best: Option<&(&Attributes, f32)>, s| match best {
+            Some(b) if b.1 >= s.1 => Some(b),
+            _ => Some(s),
+        })?;
+    match heaviest.0.get(name).unwrap() {
+        Value::Float(_) => {
+            let (sum, total) = sources.iter().filter_map(|(a, w)| a.get_float(name).map(|x| (x, *w)))
+                .fold((0.0, 0.0), |(sum, total), (x, w)| (sum + x * w as f64, total + w as f64));
+            return Some(Value::Float(sum / total));
+        },
+        Value::Vector(_) => {
+            let (sum, total) = sources.iter().filter_map(|(a, w)| a.get_vector(name).map(|x| (x, *w)))
+                .fold(([0.0; 3], 0.0), |(sum, total), (x, w)| (add(sum, scale(x, w)), total + w));
+            return Some(Value::Vector(scale(sum, 1.0 / total)));
+        },
+        value => return Some(value.clone()),
+    }
+}
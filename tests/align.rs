@@ -0,0 +1,80 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::align::{icp, IcpOptions};
+    use wedge::geometry::*;
+    use wedge::mesh::Mesh;
+
+    // An irregular cloud, so no other alignment fits as well.
+    fn cloud() -> Mesh<Vec3, (), ()> {
+        let mut mesh = Mesh::new();
+        let mut seed: u32 = 12345;
+        let mut random = || {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) as f32 / 65536.0
+        };
+        for _ in 0..60 {
+            mesh.add_vertex([random() * 4.0, random() * 2.0, random()]);
+        }
+        mesh
+    }
+
+    fn rotation_z(angle: f32, t: Vec3) -> Mat4 {
+        let (s, c) = angle.sin_cos();
+        [[c, -s, 0.0, t[0]], [s, c, 0.0, t[1]], [0.0, 0.0, 1.0, t[2]], [0.0, 0.0, 0.0, 1.0]]
+    }
+
+    fn transformed(mesh: &Mesh<Vec3, (), ()>, m: &Mat4) -> Mesh<Vec3, (), ()> {
+        mesh.map_data(|p| transform_point(m, *p), |_| (), |_| ())
+    }
+
+    #[test]
+    fn test_icp_recovers_transform() {
+        let a = cloud();
+        let truth = rotation_z(0.1, [0.2, -0.1, 0.05]);
+        let b = transformed(&a, &truth);
+        let alignment = icp(&a, &b, &IcpOptions::default());
+        assert!(alignment.error < 1e-4, "error {}", alignment.error);
+        assert_eq!(alignment.pairs, 60);
+        assert!(alignment.iterations > 0);
+        for v in a.vertex_iter() {
+            let p = *v.data().unwrap();
+            assert!(distance(transform_point(&alignment.transform, p), transform_point(&truth, p)) < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_icp_partial_overlap() {
+        // a scan covering part of b, which also has some clutter far away.
+        let full = cloud();
+        let truth = rotation_z(-0.05, [0.1, 0.0, 0.0]);
+        let mut a: Mesh<Vec3, (), ()> = Mesh::new();
+        for v in full.vertex_iter().take(40) {
+            a.add_vertex(*v.data().unwrap());
+        }
+        let mut b = transformed(&full, &truth);
+        b.add_vertex([20.0, 20.0, 20.0]);
+        let options = IcpOptions { max_distance: 0.5, ..IcpOptions::default() };
+        let alignment = icp(&a, &b, &options);
+        assert_eq!(alignment.pairs, 40);
+        let p = a.vertices()[wedge::index::VertexIndex(0)];
+        assert!(distance(transform_point(&alignment.transform, p), transform_point(&truth, p)) < 1e-3);
+    }
+
+    #[test]
+    fn test_icp_identical_and_empty() {
+        let a = cloud();
+        let alignment = icp(&a, &a, &IcpOptions::default());
+        assert_eq!(alignment.error, 0.0);
+        for (row, expected) in alignment.transform.iter().zip(IDENTITY.iter()) {
+            for (x, y) in row.iter().zip(expected.iter()) {
+                assert!((x - y).abs() < 1e-6);
+            }
+        }
+
+        let empty: Mesh<Vec3, (), ()> = Mesh::new();
+        let alignment = icp(&a, &empty, &IcpOptions::default());
+        assert_eq!((alignment.transform, alignment.pairs, alignment.iterations), (IDENTITY, 0, 0));
+    }
+}
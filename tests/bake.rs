@@ -0,0 +1,166 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::bake::{bake, bake_vertices, BakeOptions, HasUv, VertexBakeOptions};
+    use wedge::dynamic::{Attributes, DynMesh};
+    use wedge::geometry::*;
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+
+    #[derive(Copy, Clone, Default)]
+    struct Vertex {
+        position: Vec3,
+        uv: [f32; 2],
+    }
+
+    impl HasPosition for Vertex {
+        fn position(&self) -> Vec3 {
+            self.position
+        }
+
+        fn set_position(&mut self, position: Vec3) {
+            self.position = position;
+        }
+    }
+
+    impl HasUv for Vertex {
+        fn uv(&self) -> [f32; 2] {
+            self.uv
+        }
+    }
+
+    // The unit square in z = 0, with UVs matching x and y.
+    fn low() -> Mesh<Vertex, (), ()> {
+        let mut mesh = Mesh::new();
+        let v: Vec<VertexIndex> = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]].iter()
+            .map(|p: &[f32; 2]| mesh.add_vertex(Vertex { position: [p[0], p[1], 0.0], uv: *p })).collect();
+        mesh.add_default_face(&v);
+        mesh
+    }
+
+    // A quad over [-1, 2] x [-1, 2] at height z(x, y).
+    fn plane<Z: Fn(f32, f32) -> f32>(z: Z) -> Mesh<Vec3, (), ()> {
+        let mut mesh = Mesh::new();
+        let v: Vec<VertexIndex> = [[-1.0, -1.0], [2.0, -1.0], [2.0, 2.0], [-1.0, 2.0]].iter()
+            .map(|p: &[f32; 2]| mesh.add_vertex([p[0], p[1], z(p[0], p[1])])).collect();
+        mesh.add_default_face(&v);
+        mesh
+    }
+
+    fn options() -> BakeOptions {
+        BakeOptions { width: 8, height: 8, cage_offset: 0.5, max_distance: 0.5, ..BakeOptions::default() }
+    }
+
+    #[test]
+    fn test_bake_flat() {
+        let maps = bake(&plane(|_, _| 0.0), &low(), &options());
+        assert!(maps.coverage.pixels.iter().all(|c| *c));
+        for n in maps.normal.pixels.iter() {
+            assert!(distance(*n, [0.5, 0.5, 1.0]) < 1e-5);
+        }
+        assert!(maps.height.pixels.iter().all(|h| h.abs() < 1e-5));
+        assert!(maps.occlusion.pixels.iter().all(|o| *o == 1.0));
+    }
+
+    #[test]
+    fn test_bake_slope() {
+        // rises along u, so its normal leans towards -u.
+        let maps = bake(&plane(|x, _| 0.1 * x + 0.05), &low(), &options());
+        let n = normalize([-0.1, 0.0, 1.0]);
+        let expected = [n[0] * 0.5 + 0.5, 0.5, n[2] * 0.5 + 0.5];
+        for y in 0..8 {
+            for x in 0..8 {
+                assert!(*maps.coverage.get(x, y));
+                assert!(distance(*maps.normal.get(x, y), expected) < 1e-4);
+                let u = (x as f32 + 0.5) / 8.0;
+                assert!((maps.height.get(x, y) - (0.1 * u + 0.05)).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bake_coverage_and_occlusion() {
+        // a low triangle covering half the UV square, under a lid.
+        let mut low: Mesh<Vertex, (), ()> = Mesh::new();
+        let v: Vec<VertexIndex> = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]].iter()
+            .map(|p: &[f32; 2]| low.add_vertex(Vertex { position: [p[0], p[1], 0.0], uv: *p })).collect();
+        low.add_default_face(&v);
+        let mut high = plane(|_, _| 0.0);
+        let lid: Vec<VertexIndex> = [[-1.0, -1.0], [2.0, -1.0], [2.0, 2.0], [-1.0, 2.0]].iter()
+            .map(|p: &[f32; 2]| high.add_vertex([p[0], p[1], 0.3])).collect();
+        high.add_default_face(&lid);
+
+        let options = BakeOptions { cage_offset: 0.2, occlusion_samples: 16, ..options() };
+        let maps = bake(&high, &low, &options);
+        assert!(*maps.coverage.get(0, 0));
+        assert!(!maps.coverage.get(7, 7));
+        assert_eq!(*maps.normal.get(7, 7), [0.5, 0.5, 1.0]);
+        assert!(*maps.occlusion.get(0, 0) < 0.5);
+        assert_eq!(*maps.occlusion.get(7, 7), 1.0);
+
+        // nothing within reach.
+        let far = plane(|_, _| 5.0);
+        let maps = bake(&far, &low, &options);
+        assert!(maps.coverage.pixels.iter().all(|c| !c));
+    }
+
+    // An axis-aligned box from the origin to `size`, facing out.
+    fn cuboid(size: Vec3) -> Mesh<Vec3, (), ()> {
+        let mut mesh = Mesh::new();
+        let v: Vec<VertexIndex> = (0..8)
+            .map(|i| mesh.add_vertex([(i & 1) as f32 * size[0], (i >> 1 & 1) as f32 * size[1], (i >> 2) as f32 * size[2]]))
+            .collect();
+        for face in [[0, 2, 3, 1], [4, 5, 7, 6], [0, 1, 5, 4], [2, 6, 7, 3], [0, 4, 6, 2], [1, 3, 7, 5]].iter() {
+            mesh.add_default_face(&[v[face[0]], v[face[1]], v[face[2]], v[face[3]]]);
+        }
+        mesh
+    }
+
+    #[test]
+    fn test_bake_vertices() {
+        let options = VertexBakeOptions { samples: 32, distance: 10.0, threads: 1 };
+        // nothing outside a convex box, and every ray inwards hits it.
+        let cube = bake_vertices(&cuboid([1.0, 1.0, 1.0]), &options);
+        assert!(cube.occlusion.iter().all(|o| *o == 1.0));
+        assert!(cube.thickness.iter().all(|t| *t > 0.0 && *t < 2.0));
+        let slab = bake_vertices(&cuboid([1.0, 1.0, 0.1]), &options);
+        for (thin, thick) in slab.thickness.iter().zip(cube.thickness.iter()) {
+            assert!(thin < thick);
+        }
+
+        // turned inside out, every ray outwards hits a wall and none inwards do.
+        let room = cuboid([1.0, 1.0, 1.0]);
+        let mut inverted: Mesh<Vec3, (), ()> = Mesh::new();
+        for v in room.vertex_iter() {
+            inverted.add_vertex(*v.data().unwrap());
+        }
+        for f in room.face_iter() {
+            let mut vertices: Vec<VertexIndex> = f.vertex_iter().map(|v| v.index()).collect();
+            vertices.reverse();
+            inverted.add_default_face(&vertices);
+        }
+        let lone = inverted.add_vertex([5.0, 5.0, 5.0]);
+        let baked = bake_vertices(&inverted, &options);
+        assert!(baked.occlusion[..8].iter().all(|o| *o == 0.0));
+        assert!(baked.thickness[..8].iter().all(|t| *t == 10.0));
+        assert_eq!((baked.occlusion[lone.0 as usize], baked.thickness[lone.0 as usize]), (1.0, 10.0));
+    }
+
+    #[test]
+    fn test_bake_vertices_threads() {
+        let mut mesh = DynMesh::from_positions(&cuboid([1.0, 2.0, 0.5]));
+        let options = VertexBakeOptions { samples: 16, distance: 3.0, threads: 1 };
+        let single = bake_vertices(&mesh, &options);
+        for threads in [0, 3, 8, 20].iter() {
+            assert_eq!(bake_vertices(&mesh, &VertexBakeOptions { threads: *threads, ..options }), single);
+        }
+
+        single.store(&mut mesh);
+        for v in mesh.vertex_iter() {
+            let attributes = v.data().unwrap();
+            assert_eq!(attributes.get_float(Attributes::OCCLUSION), Some(single.occlusion[v.index().0 as usize] as f64));
+            assert_eq!(attributes.get_float(Attributes::THICKNESS), Some(single.thickness[v.index().0 as usize] as f64));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::buffer::DoubleBuffer;
+    use wedge::geometry::*;
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+
+    #[test]
+    fn test_double_buffered_smoothing() {
+        // a path 0 - 1 - 2 with the middle vertex raised.
+        let mut mesh: Mesh<[f32; 3], (), ()> = Mesh::new();
+        let a = mesh.add_vertex([0.0, 0.0, 0.0]);
+        let b = mesh.add_vertex([1.0, 4.0, 0.0]);
+        let c = mesh.add_vertex([2.0, 0.0, 0.0]);
+        mesh.add_edge((), a, b);
+        mesh.add_edge((), b, c);
+
+        let mut positions = DoubleBuffer::from_positions(&mesh);
+        for _ in 0..2 {
+            let (old, new) = positions.split();
+            for v in mesh.vertex_iter() {
+                let neighbors: Vec<Vec3> = v.vertex_iter().map(|n| old[n.index().0 as usize]).collect();
+                let mut average = [0.0; 3];
+                for n in neighbors.iter() {
+                    average = add(average, scale(*n, 1.0 / neighbors.len() as f32));
+                }
+                new[v.index().0 as usize] = scale(add(old[v.index().0 as usize], average), 0.5);
+            }
+            positions.swap();
+        }
+        // every vertex reads its neighbors' previous positions, even ones
+        // already written this step.
+        assert_eq!(positions.front()[1], [1.0, 2.0, 0.0]);
+        assert_eq!(positions.front()[0], [0.75, 2.0, 0.0]);
+        assert_eq!(mesh.vertices()[VertexIndex(1)], [1.0, 4.0, 0.0]);
+
+        positions.write_positions(&mut mesh);
+        assert_eq!(mesh.vertices()[VertexIndex(1)], [1.0, 2.0, 0.0]);
+    }
+}
@@ -0,0 +1,44 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::coloring::color;
+    use wedge::index::{EdgeIndex, VertexIndex};
+    use wedge::mesh::TopologyMesh;
+
+    // A star: vertex 0 joined to 1..=4, plus a path 1 - 2.
+    fn star() -> TopologyMesh {
+        let mut mesh = TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..5).map(|_| mesh.add_default_vertex()).collect();
+        for i in 1..5 {
+            mesh.add_default_edge(v[0], v[i]);
+        }
+        mesh.add_default_edge(v[1], v[2]);
+        mesh
+    }
+
+    #[test]
+    fn test_color_vertices() {
+        let mesh = star();
+        let batches: Vec<Vec<VertexIndex>> = color(&mesh, 1);
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0], vec![VertexIndex(0)]);
+        for batch in batches.iter() {
+            for v in batch.iter() {
+                assert!(mesh.vertex(*v).vertex_iter().all(|n| !batch.contains(&n.index())));
+            }
+        }
+        assert_eq!(batches.iter().map(|b| b.len()).sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn test_color_distance_two() {
+        let mesh = star();
+        // every vertex is within two steps of every other one.
+        assert_eq!(color::<VertexIndex, _, _, _>(&mesh, 2).len(), 5);
+        // all edges share vertex 0 except 1 - 2, which shares a vertex with 0 - 1 and 0 - 2.
+        let batches: Vec<Vec<EdgeIndex>> = color(&mesh, 1);
+        assert_eq!(batches.len(), 4);
+        assert!(batches.iter().any(|b| b == &vec![EdgeIndex(2), EdgeIndex(4)]));
+    }
+}
@@ -0,0 +1,51 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::debug::DebugLines;
+    use wedge::geometry::distance;
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+
+    #[test]
+    fn test_wireframe() {
+        let mut mesh: Mesh<[f32; 3], (), ()> = Mesh::new();
+        let a = mesh.add_vertex([0.0, 0.0, 0.0]);
+        let b = mesh.add_vertex([1.0, 0.0, 0.0]);
+        let c = mesh.add_vertex([0.0, 1.0, 0.0]);
+        mesh.add_edge((), a, b);
+        mesh.add_edge((), b, c);
+
+        let lines = DebugLines::new(&mesh, 1.0);
+        assert_eq!(lines.wireframe.len(), 2);
+        assert_eq!(lines.wireframe[1], [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+        assert_eq!(lines.boundary.len(), 2);
+        assert_eq!(DebugLines::flatten(&lines.wireframe).len(), 12);
+        assert!(lines.vertex_normals.is_empty() && lines.face_normals.is_empty());
+    }
+
+    #[test]
+    fn test_normals() {
+        // a unit square facing up, and a triangle standing on its far edge
+        // facing back over the square.
+        let mut mesh: Mesh<[f32; 3], (), ()> = Mesh::new();
+        let v: Vec<VertexIndex> = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0],
+                                   [0.5, 1.0, 1.0]]
+            .iter().map(|p| mesh.add_vertex(*p)).collect();
+        mesh.add_default_face(&v[..4]);
+        mesh.add_default_face(&[v[3], v[2], v[4]]);
+        mesh.add_vertex([5.0, 5.0, 5.0]);
+
+        let lines = DebugLines::new(&mesh, 0.5);
+        assert_eq!(lines.face_normals, vec![[[0.5, 0.5, 0.0], [0.5, 0.5, 0.5]],
+                                            [[0.5, 1.0, 1.0 / 3.0], [0.5, 0.5, 1.0 / 3.0]]]);
+        // the lone vertex has no normal; the shared corners lean between the
+        // two faces, weighted by area.
+        assert_eq!(lines.vertex_normals.len(), 5);
+        assert_eq!(lines.vertex_normals[0], [[0.0, 0.0, 0.0], [0.0, 0.0, 0.5]]);
+        assert_eq!(lines.vertex_normals[4], [[0.5, 1.0, 1.0], [0.5, 0.5, 1.0]]);
+        let [from, to] = lines.vertex_normals[2];
+        assert!((distance(from, to) - 0.5).abs() < 1e-6);
+        assert!(to[1] < from[1] && to[2] - from[2] > from[1] - to[1]);
+    }
+}
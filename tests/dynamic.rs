@@ -0,0 +1,41 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::dynamic::{Attributes, DynMesh, Value};
+    use wedge::geometry::HasPosition;
+    use wedge::index::VertexIndex;
+
+    #[test]
+    fn test_attributes() {
+        let mut a = Attributes::new();
+        assert_eq!(a.set("weight", 0.5), None);
+        a.set("count", 3i64);
+        a.set("name", "rim");
+        assert_eq!(a.set("weight", 0.75), Some(Value::Float(0.5)));
+        assert_eq!(a.get_float("weight"), Some(0.75));
+        assert_eq!(a.get_float("count"), Some(3.0));
+        assert_eq!(a.get_int("weight"), None);
+        assert_eq!(a.get_text("name"), Some("rim"));
+        let names: Vec<&str> = a.iter().map(|(k, _)| k).collect();
+        assert_eq!(names, vec!["count", "name", "weight"]);
+        assert_eq!(a.position(), [0.0; 3]);
+    }
+
+    #[test]
+    fn test_dyn_mesh_with_ops() {
+        let mut typed: wedge::mesh::Mesh<[f32; 3], (), ()> = wedge::mesh::Mesh::new();
+        typed.add_vertex([0.0, 0.0, 0.0]);
+        typed.add_vertex([0.001, 0.0, 0.0]);
+        typed.add_vertex([1.0, 0.0, 0.0]);
+        typed.add_edge((), VertexIndex(1), VertexIndex(2));
+
+        let mut mesh = DynMesh::from_positions(&typed);
+        mesh.vertex_data_mut(VertexIndex(2)).unwrap().set("selected", true);
+        assert_eq!(mesh.vertices()[VertexIndex(2)].get_vector("position"), Some([1.0, 0.0, 0.0]));
+
+        let (welded, _) = wedge::ops::weld_vertices(&mesh, &wedge::tolerance::Tolerance::new(0.01));
+        assert_eq!(welded.vertex_iter().len(), 2);
+        assert_eq!(welded.vertices()[VertexIndex(1)].get_bool("selected"), Some(true));
+    }
+}
@@ -0,0 +1,38 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::edge_map::{EdgeKey, EdgeMap};
+    use wedge::index::VertexIndex;
+
+    #[test]
+    fn test_edge_key_is_unordered() {
+        let (a, b) = (VertexIndex(7), VertexIndex(3));
+        assert_eq!(EdgeKey::new(a, b), EdgeKey::new(b, a));
+        assert_eq!(EdgeKey::new(a, b).vertices(), (b, a));
+        assert_eq!(EdgeKey::new(a, b).opposite(a), b);
+        assert!(!EdgeKey::new(a, b).contains(VertexIndex(0)));
+
+        let mut map: EdgeMap<u32> = EdgeMap::default();
+        map.insert(EdgeKey::new(a, b), 1);
+        *map.entry(EdgeKey::new(b, a)).or_insert(0) += 1;
+        assert_eq!(map.len(), 1);
+        assert_eq!(map[&EdgeKey::new(a, b)], 2);
+    }
+
+    #[test]
+    fn test_mesh_edge_map() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v1 = mesh.add_default_vertex();
+        let v2 = mesh.add_default_vertex();
+        let v3 = mesh.add_default_vertex();
+        let e1 = mesh.add_default_edge(v1, v2);
+        let e2 = mesh.add_default_edge(v3, v2);
+        let edges = mesh.edge_map();
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges.get(&EdgeKey::new(v2, v1)), Some(&e1));
+        assert_eq!(edges.get(&EdgeKey::new(v2, v3)), Some(&e2));
+        assert!(!edges.contains_key(&EdgeKey::new(v1, v3)));
+        assert_eq!(EdgeKey::from(mesh.edge(e2)), EdgeKey::new(v2, v3));
+    }
+}
@@ -0,0 +1,48 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::geometry::*;
+
+    #[test]
+    fn test_f64_precision() {
+        // 1 + 1e-10 is indistinguishable from 1 in f32.
+        let a: DVec3 = [1.0 + 1e-10, 0.0, 0.0];
+        let b: DVec3 = [1.0, 0.0, 0.0];
+        assert!(distance(a, b) > 0.0);
+        let a32: Vec3 = convert(a);
+        let b32: Vec3 = convert(b);
+        assert_eq!(distance(a32, b32), 0.0);
+    }
+
+    #[test]
+    fn test_generic_transforms() {
+        let m: DMat4 = mat4_mul(&translation([1.0, 2.0, 3.0]), &translation([0.5, 0.0, 0.0]));
+        assert_eq!(transform_point(&m, [0.0, 0.0, 0.0]), [1.5, 2.0, 3.0]);
+        assert_eq!(identity::<f32>(), IDENTITY);
+        assert_eq!(normalize([0.0f64, 0.0, 2.0]), [0.0, 0.0, 1.0]);
+        assert_eq!(f64::from_f64(0.25), 0.25);
+    }
+
+    #[test]
+    fn test_closest_points() {
+        let (a, b, c): (Vec3, Vec3, Vec3) = ([0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]);
+        assert_eq!(closest_point_on_segment([1.5, 1.0, 0.0], a, b), ([1.5, 0.0, 0.0], 0.75));
+        assert_eq!(closest_point_on_segment([-1.0, 0.0, 0.0], a, b), (a, 0.0));
+        // inside, past a vertex, past an edge.
+        assert_eq!(closest_point_on_triangle([0.5, 0.5, 3.0], a, b, c), ([0.5, 0.5, 0.0], [0.5, 0.25, 0.25]));
+        assert_eq!(closest_point_on_triangle([3.0, -1.0, 0.0], a, b, c), (b, [0.0, 1.0, 0.0]));
+        assert_eq!(closest_point_on_triangle([2.0, 2.0, 0.0], a, b, c), ([1.0, 1.0, 0.0], [0.0, 0.5, 0.5]));
+        // a degenerate triangle acts like its longest edge.
+        let (p, w) = closest_point_on_triangle([1.0, 1.0, 0.0], a, b, [1.0, 0.0, 0.0]);
+        assert_eq!(p, [1.0, 0.0, 0.0]);
+        assert_eq!(w[0] * 0.0 + w[1] * 2.0 + w[2] * 1.0, 1.0);
+    }
+
+    #[test]
+    fn test_edge_function() {
+        assert_eq!(edge_function([0.0, 0.0], [2.0, 0.0], [0.0, 1.0]), 2.0);
+        assert_eq!(edge_function([0.0, 0.0], [0.0, 1.0], [2.0, 0.0]), -2.0);
+        assert_eq!(edge_function([0.0f64, 0.0], [1.0, 1.0], [2.0, 2.0]), 0.0);
+    }
+}
@@ -0,0 +1,644 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+
+    fn vertices(indices: &[u32]) -> Vec<VertexIndex> {
+        indices.iter().map(|i| VertexIndex(*i)).collect()
+    }
+
+    // A path of `n` vertices along x, each pushed up by `bump` in y.
+    fn bumpy_path(n: usize, bump: f32) -> Mesh<[f32; 3], (), ()> {
+        let mut mesh = Mesh::new();
+        for i in 0..n {
+            mesh.add_vertex([i as f32, bump, 0.0]);
+        }
+        for i in 1..n {
+            mesh.add_edge((), VertexIndex((i - 1) as u32), VertexIndex(i as u32));
+        }
+        mesh.vertex_data_mut(VertexIndex(0)).unwrap()[1] = 0.0;
+        mesh.vertex_data_mut(VertexIndex((n - 1) as u32)).unwrap()[1] = 0.0;
+        mesh
+    }
+
+    #[test]
+    fn test_fair_membrane() {
+        let mut mesh = bumpy_path(5, 3.0);
+        wedge::ops::fair(&mut mesh, &vertices(&[1, 2, 3]), 0);
+        for v in mesh.vertex_iter() {
+            let p = v.data().unwrap();
+            assert!(p[1].abs() < 1e-5, "vertex {} not faired: {:?}", v.index(), p);
+            assert!((p[0] - v.index().0 as f32).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_fair_thin_plate() {
+        // With two fixed vertices on each end, a thin plate solution along a
+        // line is still the straight line through them.
+        let mut mesh = bumpy_path(8, 0.0);
+        for i in 2..6 {
+            mesh.vertex_data_mut(VertexIndex(i)).unwrap()[1] = 5.0;
+        }
+        wedge::ops::fair(&mut mesh, &vertices(&[2, 3, 4, 5]), 1);
+        for v in mesh.vertex_iter() {
+            assert!(v.data().unwrap()[1].abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_fair_leaves_fixed_vertices() {
+        let mut mesh = bumpy_path(4, 1.0);
+        wedge::ops::fair(&mut mesh, &[], 0);
+        assert_eq!(*mesh.vertex(VertexIndex(1)).data().unwrap(), [1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_cluster_vertices() {
+        let mut mesh: Mesh<[f32; 3], u32, ()> = Mesh::new();
+        let a = mesh.add_vertex([0.1, 0.1, 0.0]);
+        let b = mesh.add_vertex([0.3, 0.1, 0.0]);
+        let c = mesh.add_vertex([1.5, 0.5, 0.0]);
+        let d = mesh.add_vertex([1.7, 0.5, 0.0]);
+        mesh.add_edge(0, a, b);
+        mesh.add_edge(1, b, c);
+        mesh.add_edge(2, a, d);
+        mesh.add_edge(3, c, d);
+
+        let (simple, remap) = wedge::ops::cluster_vertices(&mesh, 1.0);
+        assert_eq!(remap, vertices(&[0, 0, 1, 1]));
+        assert_eq!(simple.vertex_iter().count(), 2);
+        let p = *simple.vertex(VertexIndex(0)).data().unwrap();
+        assert!((p[0] - 0.2).abs() < 1e-6 && (p[1] - 0.1).abs() < 1e-6);
+        let edges: Vec<u32> = simple.edge_iter().map(|e| *e.data().unwrap()).collect();
+        assert_eq!(edges, vec![1]);
+    }
+
+    #[test]
+    fn test_cluster_vertices_faces() {
+        let mut mesh: Mesh<[f32; 3], (), char> = Mesh::new();
+        let a = mesh.add_vertex([0.1, 0.1, 0.0]);
+        let b = mesh.add_vertex([0.3, 0.1, 0.0]);
+        let c = mesh.add_vertex([1.5, 0.5, 0.0]);
+        let d = mesh.add_vertex([0.5, 1.5, 0.0]);
+        let e = mesh.add_vertex([1.5, 1.5, 0.0]);
+        let f = mesh.add_vertex([1.5, -0.5, 0.0]);
+        // x collapses to an edge, w takes the side y has once a and b merge.
+        mesh.add_face_with('x', &[a, b, d], |_, _| ());
+        mesh.add_face_with('y', &[b, c, d], |_, _| ());
+        mesh.add_face_with('z', &[c, e, d], |_, _| ());
+        mesh.add_face_with('w', &[a, c, f], |_, _| ());
+
+        let (simple, remap) = wedge::ops::cluster_vertices(&mesh, 1.0);
+        assert_eq!(remap, vertices(&[0, 0, 1, 2, 3, 4]));
+        let faces: Vec<char> = simple.face_data_iter().map(|(_, f)| *f).collect();
+        assert_eq!(faces, vec!['y', 'z']);
+        assert_eq!(simple.edge_iter().len(), 7);
+        let shared = simple.find_edge(VertexIndex(1), VertexIndex(2)).unwrap();
+        assert_eq!(simple.edge(shared).faces().len(), 2);
+    }
+
+    #[test]
+    fn test_weld_vertices() {
+        use wedge::tolerance::Tolerance;
+        let mut mesh: Mesh<[f32; 3], u32, ()> = Mesh::new();
+        mesh.add_vertex([0.0, 0.0, 0.0]);
+        mesh.add_vertex([1.0, 0.0, 0.0]);
+        mesh.add_vertex([1.0, 0.0005, 0.0]);
+        mesh.add_vertex([2.0, 0.0, 0.0]);
+        mesh.add_edge(1, VertexIndex(0), VertexIndex(1));
+        mesh.add_edge(2, VertexIndex(0), VertexIndex(2));
+        mesh.add_edge(3, VertexIndex(2), VertexIndex(3));
+        mesh.add_edge(4, VertexIndex(1), VertexIndex(2));
+
+        let (welded, remap) = wedge::ops::weld_vertices(&mesh, &Tolerance::new(0.001));
+        assert_eq!(remap, vertices(&[0, 1, 1, 2]));
+        assert_eq!(welded.vertices()[VertexIndex(1)], [1.0, 0.0, 0.0]);
+        let edges: Vec<u32> = welded.edge_data_iter().map(|(_, e)| *e).collect();
+        assert_eq!(edges, vec![1, 3]);
+
+        let (unchanged, _) = wedge::ops::weld_vertices(&mesh, &Tolerance::new(0.0));
+        assert_eq!(unchanged.vertex_iter().len(), 4);
+    }
+
+    #[test]
+    fn test_weld_vertices_faces() {
+        use wedge::tolerance::Tolerance;
+        // two triangles of a square, split along the diagonal, and a sliver
+        // that welds down to an edge.
+        let mut mesh: Mesh<[f32; 3], (), u32> = Mesh::new();
+        for p in [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0],
+                  [0.0, 1.0, 0.0], [2.0, 0.0, 0.0], [2.0, 0.0005, 0.0], [3.0, 0.0, 0.0]].iter() {
+            mesh.add_vertex(*p);
+        }
+        mesh.add_face_with(1, &vertices(&[0, 1, 2]), |_, _| ());
+        mesh.add_face_with(2, &vertices(&[3, 4, 5]), |_, _| ());
+        mesh.add_face_with(3, &vertices(&[6, 7, 8]), |_, _| ());
+
+        let (welded, remap) = wedge::ops::weld_vertices(&mesh, &Tolerance::new(0.001));
+        assert_eq!(remap, vertices(&[0, 1, 2, 1, 3, 2, 4, 4, 5]));
+        let faces: Vec<u32> = welded.face_data_iter().map(|(_, f)| *f).collect();
+        assert_eq!(faces, vec![1, 2]);
+        let diagonal = welded.find_edge(VertexIndex(1), VertexIndex(2)).unwrap();
+        assert_eq!(welded.edge(diagonal).faces().len(), 2);
+        assert_eq!(welded.summary().boundary_loops, 1);
+    }
+
+    #[test]
+    fn test_weld_vertices_by() {
+        use wedge::geometry::HasPosition;
+        use wedge::tolerance::Tolerance;
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Vertex {
+            position: [f32; 3],
+            uv: [f32; 2],
+        }
+
+        impl HasPosition for Vertex {
+            fn position(&self) -> [f32; 3] {
+                self.position
+            }
+
+            fn set_position(&mut self, position: [f32; 3]) {
+                self.position = position;
+            }
+        }
+
+        // two quads' worth of corners split along a UV seam at x = 1.
+        let mut mesh: Mesh<Vertex, (), ()> = Mesh::new();
+        for (x, u) in [(0.0, 0.0), (1.0, 1.0), (1.0, 0.0), (1.0, 1.0), (2.0, 1.0)].iter() {
+            mesh.add_vertex(Vertex { position: [*x, 0.0, 0.0], uv: [*u, 0.0] });
+        }
+        let (welded, remap) = wedge::ops::weld_vertices_by(&mesh, &Tolerance::new(0.001),
+                                                           |a, b| a.uv == b.uv);
+        assert_eq!(remap, vertices(&[0, 1, 2, 1, 3]));
+        assert_eq!(welded.vertices()[VertexIndex(2)].uv, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_op_context_progress() {
+        use wedge::ops::OpContext;
+        let mut mesh = bumpy_path(5, 3.0);
+        let mut reports: Vec<f32> = Vec::new();
+        {
+            let mut ctx = OpContext::new().with_progress(|f| reports.push(f));
+            wedge::ops::fair_with_context(&mut mesh, &vertices(&[1, 2, 3]), 0, &mut ctx).unwrap();
+        }
+        assert!(!reports.is_empty());
+        assert!(reports.windows(2).all(|w| w[0] <= w[1]), "{:?}", reports);
+        assert_eq!(*reports.last().unwrap(), 1.0);
+        assert!(mesh.vertices()[VertexIndex(2)][1].abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_op_context_cancel() {
+        use wedge::ops::{Cancelled, CancelToken, OpContext};
+        let token = CancelToken::new();
+        token.cancel();
+        let mut ctx = OpContext::new().with_cancel(token.clone());
+
+        let mut mesh = bumpy_path(5, 3.0);
+        let result = wedge::ops::fair_with_context(&mut mesh, &vertices(&[1, 2, 3]), 0, &mut ctx);
+        assert_eq!(result, Err(Cancelled));
+        assert_eq!(mesh.vertices()[VertexIndex(2)][1], 3.0);
+        assert!(wedge::ops::cluster_vertices_with_context(&mesh, 1.0, &mut ctx).is_err());
+    }
+
+    #[test]
+    fn test_op_graph_rebuild() {
+        use wedge::ops::{Fair, OpGraph, Source, WeldVertices};
+        use wedge::tolerance::Tolerance;
+
+        // Vertex 3 duplicates vertex 2 until the weld tolerance is raised.
+        let mut mesh = bumpy_path(5, 3.0);
+        mesh.vertex_data_mut(VertexIndex(3)).unwrap()[0] = 2.05;
+        let mut graph: OpGraph<Mesh<[f32; 3], (), ()>> = OpGraph::new();
+        let source = graph.add(Source(mesh), &[]);
+        let weld = graph.add(WeldVertices { tolerance: Tolerance::new(0.01) }, &[source]);
+        let faired = graph.add(Fair { selection: vertices(&[1]), continuity_order: 0 }, &[weld]);
+
+        assert_eq!(graph.evaluate(faired).vertex_iter().len(), 5);
+        assert!(graph.is_evaluated(source) && graph.is_evaluated(weld));
+        assert!(graph.name(weld).ends_with("WeldVertices"));
+
+        assert!(graph.update(weld, |w: &mut WeldVertices| w.tolerance = Tolerance::new(0.1)));
+        assert!(graph.is_evaluated(source));
+        assert!(!graph.is_evaluated(weld) && !graph.is_evaluated(faired));
+        assert_eq!(graph.evaluate(faired).vertex_iter().len(), 4);
+        assert_eq!(graph.operation::<WeldVertices>(weld).unwrap().tolerance.distance, 0.1);
+
+        // the wrong operation type changes nothing.
+        assert!(!graph.update(weld, |f: &mut Fair| f.continuity_order = 1));
+        assert!(graph.is_evaluated(faired));
+    }
+
+    #[test]
+    fn test_modifier_stack() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+        use wedge::ops::{Fair, Modifier, ModifierStack, WeldVertices};
+        use wedge::tolerance::Tolerance;
+
+        type TestMesh = Mesh<[f32; 3], (), ()>;
+
+        // Lifts every vertex, counting how often it runs.
+        struct Lift(f32, Rc<Cell<usize>>);
+
+        impl Modifier<TestMesh> for Lift {
+            fn apply(&self, input: &TestMesh) -> TestMesh {
+                self.1.set(self.1.get() + 1);
+                input.map_data(|v| [v[0], v[1], v[2] + self.0], |_| (), |_| ())
+            }
+        }
+
+        let mut mesh = bumpy_path(5, 3.0);
+        mesh.vertex_data_mut(VertexIndex(3)).unwrap()[0] = 2.05;
+        let runs = Rc::new(Cell::new(0));
+        let mut stack = ModifierStack::new(mesh);
+        let weld = stack.push(WeldVertices { tolerance: Tolerance::new(0.1) });
+        let lift = stack.push(Lift(1.0, runs.clone()));
+        assert_eq!(stack.evaluate().vertex_iter().len(), 4);
+        assert_eq!(stack.evaluate().vertices()[VertexIndex(0)][2], 1.0);
+        assert_eq!(runs.get(), 1);
+        assert!(stack.name(weld).ends_with("WeldVertices"));
+
+        // editing the last stage keeps the weld cached.
+        assert!(stack.update(lift, |l: &mut Lift| l.0 = 2.0));
+        assert!(stack.is_evaluated(weld) && !stack.is_evaluated(lift));
+        assert_eq!(stack.evaluate().vertices()[VertexIndex(0)][2], 2.0);
+        assert_eq!(runs.get(), 2);
+        assert!(!stack.update(lift, |f: &mut Fair| f.continuity_order = 1));
+
+        // a disabled stage passes its input through.
+        stack.set_enabled(weld, false);
+        assert!(!stack.is_evaluated(lift));
+        assert_eq!(stack.evaluate().vertex_iter().len(), 5);
+        stack.set_enabled(lift, false);
+        assert_eq!(stack.evaluate().vertices()[VertexIndex(0)][2], 0.0);
+        assert_eq!(runs.get(), 3);
+        assert_eq!(stack.modifier::<Lift>(lift).unwrap().0, 2.0);
+    }
+
+    #[test]
+    fn test_solve_coplanar_and_collinear() {
+        use wedge::ops::{solve_constraints, Constraint};
+        use wedge::tolerance::Tolerance;
+
+        let mut mesh: Mesh<[f32; 3], (), ()> = Mesh::new();
+        for p in [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.5], [0.0, 1.0, 0.0],
+                  [0.0, 0.0, 0.0], [1.0, 0.3, 0.0], [2.0, 0.0, 0.0]].iter() {
+            mesh.add_vertex(*p);
+        }
+        let constraints = vec![
+            Constraint::Coplanar(vertices(&[0, 1, 2, 3])),
+            Constraint::Collinear(vertices(&[4, 5, 6])),
+        ];
+        let tolerance = Tolerance::new(1e-4);
+        assert!(solve_constraints(&mut mesh, &constraints, &vertices(&[0, 1, 3, 4, 6]), &tolerance, 10));
+        assert!(mesh.vertices()[VertexIndex(2)][2].abs() < 1e-4);
+        assert_eq!(mesh.vertices()[VertexIndex(2)][0], 1.0);
+        assert!(mesh.vertices()[VertexIndex(5)][1].abs() < 1e-4);
+        assert_eq!(mesh.vertices()[VertexIndex(0)], [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_solve_lengths() {
+        use wedge::geometry::distance;
+        use wedge::ops::{solve_constraints, Constraint};
+        use wedge::tolerance::Tolerance;
+
+        // a unit-spaced chain whose end is dragged away and pinned.
+        let mut mesh = bumpy_path(3, 0.0);
+        let constraints: Vec<Constraint> = mesh.edge_iter()
+            .map(|e| Constraint::keep_length(&mesh, e.index()))
+            .collect();
+        mesh.vertex_data_mut(VertexIndex(2)).unwrap()[0] = 3.0;
+        let tolerance = Tolerance::new(1e-4);
+        assert!(solve_constraints(&mut mesh, &constraints, &vertices(&[2]), &tolerance, 100));
+        let p: Vec<[f32; 3]> = mesh.vertex_data_iter().map(|(_, p)| *p).collect();
+        assert_eq!(p[2], [3.0, 0.0, 0.0]);
+        assert!((distance(p[0], p[1]) - 1.0).abs() < 1e-3);
+        assert!((distance(p[1], p[2]) - 1.0).abs() < 1e-3);
+
+        // both ends pinned too far apart can't be satisfied.
+        mesh.vertex_data_mut(VertexIndex(0)).unwrap()[0] = -1.0;
+        assert!(!solve_constraints(&mut mesh, &constraints, &vertices(&[0, 2]), &tolerance, 100));
+    }
+
+    #[test]
+    fn test_greedy() {
+        use wedge::ops::{greedy, LocalOperator};
+
+        // Retires the smallest value and zeroes the next one unless it is
+        // retired, which makes that the cheapest candidate even though it was
+        // queued with a high cost.
+        struct Retire(Vec<VertexIndex>);
+
+        impl LocalOperator<Vec<u32>> for Retire {
+            type Element = VertexIndex;
+
+            fn cost(&mut self, values: &Vec<u32>, v: VertexIndex) -> Option<f64> {
+                values.get(v.0 as usize).filter(|x| **x < 100).map(|x| *x as f64)
+            }
+
+            fn apply(&mut self, values: &mut Vec<u32>, v: VertexIndex) -> Vec<VertexIndex> {
+                self.0.push(v);
+                values[v.0 as usize] += 100;
+                let next = v.0 as usize + 1;
+                if next < values.len() && values[next] < 100 {
+                    values[next] = 0;
+                }
+                vec![VertexIndex(next as u32)]
+            }
+        }
+
+        let all = vertices(&[0, 1, 2, 3]);
+        let mut values = vec![5, 7, 3, 9];
+        let mut retire = Retire(Vec::new());
+        assert_eq!(greedy(&mut values, &mut retire, &all, 4.0, 10), 2);
+        assert_eq!(retire.0, vertices(&[2, 3]));
+
+        let mut values = vec![5, 7, 3, 9];
+        let mut retire = Retire(Vec::new());
+        assert_eq!(greedy(&mut values, &mut retire, &all, f64::INFINITY, 10), 4);
+        assert_eq!(retire.0, vertices(&[2, 3, 0, 1]));
+        assert_eq!(values, vec![105, 100, 103, 100]);
+    }
+
+    #[test]
+    fn test_greedy_rescores() {
+        use wedge::ops::{greedy, LocalOperator};
+
+        // Retires a value and raises the last one without reporting it, so
+        // its queued cost goes stale.
+        struct Raise;
+
+        impl LocalOperator<Vec<u32>> for Raise {
+            type Element = VertexIndex;
+
+            fn cost(&mut self, values: &Vec<u32>, v: VertexIndex) -> Option<f64> {
+                values.get(v.0 as usize).filter(|x| **x < 100).map(|x| *x as f64)
+            }
+
+            fn apply(&mut self, values: &mut Vec<u32>, v: VertexIndex) -> Vec<VertexIndex> {
+                values[v.0 as usize] += 100;
+                *values.last_mut().unwrap() += 10;
+                vec![]
+            }
+        }
+
+        let mut values = vec![1, 2, 3];
+        assert_eq!(greedy(&mut values, &mut Raise, &vertices(&[0, 1, 2]), 10.0, 10), 2);
+        assert_eq!(values, vec![101, 102, 23]);
+        // an element past the end is skipped.
+        assert_eq!(greedy(&mut values, &mut Raise, &vertices(&[2, 5]), 100.0, 10), 1);
+    }
+
+    #[test]
+    fn test_greedy_collapse() {
+        use wedge::geometry::*;
+        use wedge::index::EdgeIndex;
+        use wedge::ops::{greedy, LocalOperator};
+
+        // Collapses the shortest edges to their midpoints. Each collapse
+        // moves other edges into the indices it frees without reporting
+        // them, which greedy() has to catch when they surface.
+        struct Shortest;
+
+        impl LocalOperator<Mesh<Vec3, (), ()>> for Shortest {
+            type Element = EdgeIndex;
+
+            fn cost(&mut self, mesh: &Mesh<Vec3, (), ()>, e: EdgeIndex) -> Option<f64> {
+                if !mesh.is_valid_edge_index(e) || !mesh.is_collapsible(e) {
+                    return None;
+                }
+                let ends = mesh.edge(e).vertices();
+                Some(distance(mesh[ends[0]], mesh[ends[1]]) as f64)
+            }
+
+            fn apply(&mut self, mesh: &mut Mesh<Vec3, (), ()>, e: EdgeIndex) -> Vec<EdgeIndex> {
+                let ends = mesh.edge(e).vertices();
+                let midpoint = scale(add(mesh[ends[0]], mesh[ends[1]]), 0.5);
+                let merged = mesh.collapse_edge(e);
+                mesh[merged] = midpoint;
+                mesh.vertex(merged).edge_iter().map(|x| x.index()).collect()
+            }
+        }
+
+        // a 5 x 5 grid of quads split into triangles, squeezed along x
+        // towards the middle so the edge lengths differ.
+        let mut mesh: Mesh<Vec3, (), ()> = Mesh::new();
+        for j in 0..6 {
+            for i in 0..6 {
+                let x = i as f32 - 2.5;
+                mesh.add_vertex([x * x.abs() * 0.2, j as f32, 0.0]);
+            }
+        }
+        for j in 0..5u32 {
+            for i in 0..5u32 {
+                let v = |di: u32, dj: u32| VertexIndex((j + dj) * 6 + i + di);
+                mesh.add_default_face(&[v(0, 0), v(1, 0), v(1, 1)]);
+                mesh.add_default_face(&[v(0, 0), v(1, 1), v(0, 1)]);
+            }
+        }
+        let all: Vec<EdgeIndex> = mesh.edge_iter().map(|e| e.index()).collect();
+        let steps = greedy(&mut mesh, &mut Shortest, &all, f64::INFINITY, 12);
+        assert_eq!(steps, 12);
+        assert_eq!(mesh.vertex_iter().len(), 36 - 12);
+        // still a disk.
+        let summary = mesh.summary();
+        assert_eq!(summary.boundary_loops, 1);
+        assert_eq!(mesh.vertex_iter().len() + mesh.face_iter().len(), mesh.edge_iter().len() + 1);
+        for f in mesh.face_iter() {
+            assert_eq!(f.vertex_iter().count(), 3);
+        }
+    }
+
+    #[test]
+    fn test_simplify_path() {
+        use wedge::geometry::*;
+        use wedge::ops::simplify_path;
+
+        // a strip of quads whose lower side wobbles in y.
+        let wobble = [0.0, 0.02, -0.02, 0.01, 0.0, 0.0];
+        let strip = || {
+            let mut mesh: Mesh<Vec3, (), ()> = Mesh::new();
+            for (i, y) in wobble.iter().enumerate() {
+                mesh.add_vertex([i as f32, *y, 0.0]);
+            }
+            for i in 0..6 {
+                mesh.add_vertex([i as f32, 1.0, 0.0]);
+            }
+            for i in 0..5u32 {
+                mesh.add_default_face(&vertices(&[i, i + 1, i + 7, i + 6]));
+            }
+            mesh
+        };
+        let lower = vertices(&[0, 1, 2, 3, 4, 5]);
+
+        let mut mesh = strip();
+        let path = simplify_path(&mut mesh, &lower, 0.05);
+        assert_eq!(path.len(), 2);
+        assert_eq!((mesh[path[0]], mesh[path[1]]), ([0.0, 0.0, 0.0], [5.0, 0.0, 0.0]));
+        assert!(mesh.find_edge(path[0], path[1]).is_some());
+        assert_eq!((mesh.vertex_iter().len(), mesh.face_iter().len()), (8, 5));
+        assert_eq!(mesh.summary().boundary_loops, 1);
+
+        // within 0.015 only the last wobble goes.
+        let mut mesh = strip();
+        let path = simplify_path(&mut mesh, &lower, 0.015);
+        let ys: Vec<f32> = path.iter().map(|v| mesh[*v][1]).collect();
+        assert_eq!(ys, [0.0, 0.02, -0.02, 0.01, 0.0]);
+        assert_eq!(mesh[path[4]][0], 5.0);
+        assert_eq!(mesh.vertex_iter().len(), 11);
+    }
+
+    #[test]
+    fn test_simplify_path_loop() {
+        use wedge::geometry::*;
+        use wedge::ops::simplify_path;
+
+        // a fan over a square rim with three more vertices along each side.
+        let mut mesh: Mesh<Vec3, (), ()> = Mesh::new();
+        let center = mesh.add_vertex([0.0, 0.0, 0.0]);
+        let corners = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]];
+        let mut rim = Vec::new();
+        for k in 0..4 {
+            let (a, b) = (corners[k], corners[(k + 1) % 4]);
+            for t in 0..4 {
+                let t = t as f32 / 4.0;
+                rim.push(mesh.add_vertex([a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, 0.0]));
+            }
+        }
+        for i in 0..16 {
+            mesh.add_default_face(&[center, rim[i], rim[(i + 1) % 16]]);
+        }
+
+        let e = mesh.find_edge(rim[0], rim[1]).unwrap();
+        let mut path: Vec<VertexIndex> = mesh.boundary_loop(e).map(|h| h.from).collect();
+        let start = path.iter().position(|v| *v == rim[0]).unwrap();
+        path.rotate_left(start);
+        path.push(rim[0]);
+        let simplified = simplify_path(&mut mesh, &path, 1e-3);
+        assert_eq!(simplified.len(), 5);
+        assert_eq!(simplified[0], simplified[4]);
+        let mut kept: Vec<[f32; 2]> = simplified[..4].iter().map(|v| [mesh[*v][0], mesh[*v][1]]).collect();
+        kept.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(kept, [[-1.0, -1.0], [-1.0, 1.0], [1.0, -1.0], [1.0, 1.0]]);
+        assert_eq!((mesh.vertex_iter().len(), mesh.face_iter().len()), (5, 4));
+        assert_eq!(mesh.boundary_loops().len(), 1);
+        assert_eq!(mesh.boundary_loops()[0].len(), 4);
+    }
+
+    // A grid of (w + 1) x (h + 1) points at corner + i * u + j * v, each cell
+    // split into two triangles lettered from `first`.
+    fn triangulated_grid(mesh: &mut Mesh<[f32; 3], u32, char>, corner: [f32; 3], u: [f32; 3], v: [f32; 3],
+                         w: usize, h: usize, first: char) -> Vec<VertexIndex> {
+        use wedge::geometry::*;
+        let points: Vec<VertexIndex> = (0..(w + 1) * (h + 1)).map(|k| {
+            let (i, j) = ((k % (w + 1)) as f32, (k / (w + 1)) as f32);
+            let p = add(corner, add(scale(u, i), scale(v, j)));
+            match mesh.vertex_iter().find(|x| distance(*x.data().unwrap(), p) < 1e-6) {
+                Some(x) => x.index(),
+                None => mesh.add_vertex(p),
+            }
+        }).collect();
+        let mut letter = first as u8;
+        for j in 0..h {
+            for i in 0..w {
+                let at = |di: usize, dj: usize| points[(j + dj) * (w + 1) + i + di];
+                for triangle in [[at(0, 0), at(1, 0), at(1, 1)], [at(0, 0), at(1, 1), at(0, 1)]].iter() {
+                    let mut id = mesh.edge_iter().len() as u32;
+                    mesh.add_face_with(letter as char, triangle, |_, _| { id += 1; id });
+                    letter += 1;
+                }
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_merge_coplanar_faces() {
+        use wedge::index::IndexType;
+        use wedge::ops::merge_coplanar_faces;
+
+        // a flat 2 x 2 grid becomes one square.
+        let mut mesh: Mesh<[f32; 3], u32, char> = Mesh::new();
+        let grid = triangulated_grid(&mut mesh, [0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], 2, 2, 'a');
+        let (merged, remap) = merge_coplanar_faces(&mesh, 0.01);
+        assert_eq!((merged.vertex_iter().len(), merged.edge_iter().len(), merged.face_iter().len()), (4, 4, 1));
+        assert_eq!(*merged.face(wedge::index::FaceIndex(0)).data(), 'a');
+        assert!(!remap[grid[4].index()].is_valid());
+        for k in [0, 2, 6, 8].iter() {
+            assert_eq!(merged[remap[grid[*k].index()]], mesh[grid[*k]]);
+        }
+        let corners: Vec<VertexIndex> = merged.face(wedge::index::FaceIndex(0)).vertex_iter().map(|v| v.index()).collect();
+        let expect: Vec<VertexIndex> = [0, 2, 8, 6].iter().map(|k| remap[grid[*k].index()]).collect();
+        let start = corners.iter().position(|v| *v == expect[0]).unwrap();
+        assert_eq!(corners[start..].iter().chain(corners[..start].iter()).cloned().collect::<Vec<VertexIndex>>(), expect);
+        // each side keeps the data of one of the edges along it.
+        for e in merged.edge_iter() {
+            assert!(mesh.edge_iter().any(|x| x.data() == e.data()));
+        }
+    }
+
+    #[test]
+    fn test_merge_coplanar_faces_fold() {
+        use wedge::index::IndexType;
+        use wedge::ops::merge_coplanar_faces;
+
+        // a floor and a wall, each 2 x 1 cells, meeting along y = 0.
+        let mut mesh: Mesh<[f32; 3], u32, char> = Mesh::new();
+        triangulated_grid(&mut mesh, [0.0; 3], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0], 2, 1, 'a');
+        triangulated_grid(&mut mesh, [0.0; 3], [0.0, -1.0, 0.0], [1.0, 0.0, 0.0], 1, 2, 'e');
+        assert_eq!((mesh.vertex_iter().len(), mesh.face_iter().len()), (9, 8));
+        let (merged, _) = merge_coplanar_faces(&mesh, 0.1);
+        // the middle of the fold only joins its ends.
+        assert_eq!((merged.vertex_iter().len(), merged.edge_iter().len(), merged.face_iter().len()), (6, 7, 2));
+        let data: Vec<char> = merged.face_iter().map(|f| *f.data()).collect();
+        assert_eq!(data, ['a', 'e']);
+        assert_eq!(merged.summary().boundary_loops, 1);
+
+        // past a right angle the two merge into one bent polygon, and the
+        // middle of the fold goes with the rest of the inside. The outline's
+        // corners bend by a right angle too, so all but three of them go.
+        let (merged, remap) = merge_coplanar_faces(&mesh, 1.6);
+        assert_eq!((merged.vertex_iter().len(), merged.edge_iter().len(), merged.face_iter().len()), (3, 3, 1));
+        assert_eq!(remap.iter().filter(|v| !v.is_valid()).count(), 6);
+    }
+
+    #[test]
+    fn test_merge_coplanar_faces_kept() {
+        use wedge::ops::merge_coplanar_faces;
+
+        // a ring of quads around a square hole isn't a disk.
+        let mut mesh: Mesh<[f32; 3], u32, char> = Mesh::new();
+        let outer: Vec<VertexIndex> = [[0.0, 0.0], [3.0, 0.0], [3.0, 3.0], [0.0, 3.0]].iter()
+            .map(|p: &[f32; 2]| mesh.add_vertex([p[0], p[1], 0.0])).collect();
+        let inner: Vec<VertexIndex> = [[1.0, 1.0], [2.0, 1.0], [2.0, 2.0], [1.0, 2.0]].iter()
+            .map(|p: &[f32; 2]| mesh.add_vertex([p[0], p[1], 0.0])).collect();
+        for k in 0..4 {
+            let l = (k + 1) % 4;
+            mesh.add_face_with('q', &[outer[k], outer[l], inner[l], inner[k]], |_, _| 0);
+        }
+        let (merged, _) = merge_coplanar_faces(&mesh, 0.01);
+        assert_eq!((merged.vertex_iter().len(), merged.face_iter().len()), (8, 4));
+
+        // nor do faces bent further than the tolerance merge.
+        let mut fan: Mesh<[f32; 3], u32, char> = Mesh::new();
+        let center = fan.add_vertex([0.0, 0.0, 1.0]);
+        let rim: Vec<VertexIndex> = (0..6).map(|i| {
+            let a = i as f32 * std::f32::consts::PI / 3.0;
+            fan.add_vertex([a.cos(), a.sin(), 0.0])
+        }).collect();
+        for i in 0..6 {
+            fan.add_face_with('t', &[center, rim[i], rim[(i + 1) % 6]], |_, _| 0);
+        }
+        let (merged, remap) = merge_coplanar_faces(&fan, 0.1);
+        assert_eq!((merged.vertex_iter().len(), merged.face_iter().len()), (7, 6));
+        assert!(remap.iter().enumerate().all(|(i, v)| merged[*v] == fan[VertexIndex(i as u32)]));
+    }
+}
@@ -0,0 +1,54 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::index::VertexIndex;
+    use wedge::mesh::TopologyMesh;
+    use wedge::partition::{partition, Partition};
+
+    // A `w` by `h` grid of vertices joined to their horizontal and vertical
+    // neighbours.
+    fn grid(w: u32, h: u32) -> TopologyMesh {
+        let mut mesh = TopologyMesh::new();
+        for _ in 0..w * h {
+            mesh.add_default_vertex();
+        }
+        for y in 0..h {
+            for x in 0..w {
+                if x + 1 < w {
+                    mesh.add_default_edge(VertexIndex(y * w + x), VertexIndex(y * w + x + 1));
+                }
+                if y + 1 < h {
+                    mesh.add_default_edge(VertexIndex(y * w + x), VertexIndex((y + 1) * w + x));
+                }
+            }
+        }
+        mesh
+    }
+
+    #[test]
+    fn test_partition_grid() {
+        let mesh = grid(8, 2);
+        let parts: Partition<VertexIndex> = partition(&mesh, 2);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts.elements(0).len(), 8);
+        assert_eq!(parts.elements(1).len(), 8);
+        // a clean cut across the short side.
+        assert_eq!(parts.cut_size(), 2);
+        assert_eq!(parts.halo(0).len(), 2);
+        for v in parts.halo(0) {
+            assert_eq!(parts.part(*v), 1);
+        }
+    }
+
+    #[test]
+    fn test_partition_uneven() {
+        let mesh = grid(7, 1);
+        let parts: Partition<VertexIndex> = partition(&mesh, 3);
+        let mut sizes: Vec<usize> = (0..3).map(|p| parts.elements(p).len()).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![2, 2, 3]);
+        assert_eq!(parts.cut_size(), 2);
+        assert_eq!(partition::<VertexIndex, _, _, _>(&mesh, 1).cut_size(), 0);
+    }
+}
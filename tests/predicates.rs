@@ -0,0 +1,71 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::predicates::*;
+
+    const ULP: f64 = 1.0 / 9007199254740992.0; // 2^-53, the spacing just above 0.5
+
+    fn sign(x: f64) -> i32 {
+        if x > 0.0 { 1 } else if x < 0.0 { -1 } else { 0 }
+    }
+
+    // Exact orient2d of 2^53 * p, which is integral for these inputs.
+    fn orient2d_i128(a: Point2, b: Point2, c: Point2) -> i32 {
+        let s = 9007199254740992.0;
+        let v = |x: f64| (x * s) as i128;
+        let det = (v(a[0]) - v(c[0])) * (v(b[1]) - v(c[1])) - (v(a[1]) - v(c[1])) * (v(b[0]) - v(c[0]));
+        det.signum() as i32
+    }
+
+    #[test]
+    fn test_simple_signs() {
+        assert!(orient2d([0.0, 0.0], [1.0, 0.0], [0.0, 1.0]) > 0.0);
+        assert!(orient2d([0.0, 0.0], [0.0, 1.0], [1.0, 0.0]) < 0.0);
+        assert_eq!(orient2d([0.0, 0.0], [1.0, 1.0], [2.0, 2.0]), 0.0);
+
+        let (a, b, c) = ([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+        assert!(orient3d(a, b, c, [0.0, 0.0, -1.0]) > 0.0);
+        assert!(orient3d(a, b, c, [0.0, 0.0, 1.0]) < 0.0);
+        assert_eq!(orient3d(a, b, c, [0.3, 0.7, 0.0]), 0.0);
+        assert!(orient3d(a, b, c, to_point3([0.0, 0.0, 2.0])) < 0.0);
+
+        let (a, b, c) = ([1.0, 0.0], [0.0, 1.0], [-1.0, 0.0]);
+        assert!(incircle(a, b, c, [0.0, 0.0]) > 0.0);
+        assert!(incircle(a, b, c, [2.0, 0.0]) < 0.0);
+        assert_eq!(incircle(a, b, c, [0.0, -1.0]), 0.0);
+    }
+
+    #[test]
+    fn test_orient2d_near_collinear() {
+        let (b, c) = ([12.0, 12.0], [24.0, 24.0]);
+        let mut naive_wrong = 0;
+        for i in 0..64 {
+            for j in 0..64 {
+                let a = [0.5 + i as f64 * ULP, 0.5 + j as f64 * ULP];
+                let expect = orient2d_i128(a, b, c);
+                assert_eq!(sign(orient2d(a, b, c)), expect, "orient2d at ({}, {})", i, j);
+                assert_eq!(sign(orient3d([a[0], a[1], 0.0], [b[0], b[1], 0.0], [c[0], c[1], 0.0],
+                                         [0.0, 0.0, 1.0])), -expect);
+
+                let naive = (a[0] - c[0]) * (b[1] - c[1]) - (a[1] - c[1]) * (b[0] - c[0]);
+                if sign(naive) != expect {
+                    naive_wrong += 1;
+                }
+            }
+        }
+        // Otherwise this test would not reach the exact path.
+        assert!(naive_wrong > 0);
+    }
+
+    #[test]
+    fn test_incircle_near_cocircular() {
+        // A 3-4-5 circle, scaled so that one unit is tiny relative to the radius.
+        let s = 1048576.0;
+        let (a, b, c) = ([3.0 * s, 4.0 * s], [-4.0 * s, 3.0 * s], [-3.0 * s, -4.0 * s]);
+        assert_eq!(incircle(a, b, c, [5.0 * s, 0.0]), 0.0);
+        assert!(incircle(a, b, c, [5.0 * s - 1.0, 0.0]) > 0.0);
+        assert!(incircle(a, b, c, [5.0 * s + 1.0, 0.0]) < 0.0);
+        assert!(incircle(a, b, c, [4.0 * s, -3.0 * s - 0.5]) < 0.0);
+    }
+}
@@ -0,0 +1,43 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::index::FaceIndex;
+    use wedge::provenance::FaceIds;
+
+    #[test]
+    fn test_split_and_merge() {
+        let mut ids = FaceIds::new();
+        let a = ids.assign(FaceIndex(0));
+        let b = ids.assign(FaceIndex(1));
+        ids.add_tag(a, "extrude #3");
+
+        // face 0 is split in place into 0 and 2.
+        let pieces = ids.split(FaceIndex(0), &[FaceIndex(0), FaceIndex(2)]);
+        assert_eq!(ids.face(a), None);
+        assert_eq!(ids.resolve(a), vec![FaceIndex(0), FaceIndex(2)]);
+        assert_eq!(ids.parents(pieces[1]), &[a]);
+        assert_eq!(ids.resolve_tag("extrude #3"), vec![FaceIndex(0), FaceIndex(2)]);
+
+        // then face 2 and face 1 are merged into face 1.
+        let merged = ids.merge(&[FaceIndex(2), FaceIndex(1)], FaceIndex(1));
+        assert_eq!(ids.parents(merged), &[pieces[1], b]);
+        assert_eq!(ids.resolve(a), vec![FaceIndex(0), FaceIndex(1)]);
+        assert_eq!(ids.resolve(b), vec![FaceIndex(1)]);
+        assert_eq!(ids.id(FaceIndex(2)), None);
+
+        ids.remove(FaceIndex(0));
+        assert_eq!(ids.resolve_tag("extrude #3"), vec![FaceIndex(1)]);
+        assert_eq!(ids.tags(a), vec!["extrude #3"]);
+    }
+
+    #[test]
+    fn test_remap() {
+        let mut ids = FaceIds::new();
+        let a = ids.assign(FaceIndex(0));
+        let b = ids.assign(FaceIndex(1));
+        ids.remap(&[FaceIndex(1), FaceIndex(0)]);
+        assert_eq!(ids.face(a), Some(FaceIndex(1)));
+        assert_eq!(ids.id(FaceIndex(0)), Some(b));
+    }
+}
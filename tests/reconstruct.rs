@@ -0,0 +1,50 @@
+#![cfg(feature = "reconstruct")]
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::geometry::*;
+    use wedge::reconstruct::ball_pivot;
+
+    #[test]
+    fn test_ball_pivot_plane() {
+        // a 5 x 5 grid facing +z.
+        let points: Vec<Vec3> = (0..25).map(|i| [(i % 5) as f32, (i / 5) as f32, 0.0]).collect();
+        let normals = vec![[0.0, 0.0, 1.0]; 25];
+        let mesh = ball_pivot(&points, &normals, &[1.0]);
+        assert_eq!(mesh.face_iter().len(), 32);
+        assert_eq!(mesh.boundary_loops().len(), 1);
+        assert_eq!(mesh.boundary_loops()[0].len(), 16);
+        for f in mesh.face_iter() {
+            let corners: Vec<Vec3> = f.vertex_iter().map(|v| *v.data().unwrap()).collect();
+            assert!(newell_normal(&corners)[2] > 0.0);
+        }
+
+        // too small a ball can't bridge the spacing.
+        let mesh = ball_pivot(&points, &normals, &[0.6]);
+        assert_eq!(mesh.face_iter().len(), 0);
+        assert_eq!(mesh.vertex_iter().len(), 25);
+    }
+
+    #[test]
+    fn test_ball_pivot_sphere() {
+        // a Fibonacci sphere, which the ball closes over.
+        let n = 200;
+        let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+        let points: Vec<Vec3> = (0..n).map(|i| {
+            let z = 1.0 - (i as f32 + 0.5) * 2.0 / n as f32;
+            let r = (1.0 - z * z).sqrt();
+            let phi = i as f32 * golden_angle;
+            [r * phi.cos(), r * phi.sin(), z]
+        }).collect();
+        let mesh = ball_pivot(&points, &points, &[0.2, 0.3]);
+        let summary = mesh.summary();
+        assert_eq!(summary.boundary_loops, 0);
+        assert_eq!(summary.components, 1);
+        assert_eq!(mesh.vertex_iter().len() + mesh.face_iter().len(), mesh.edge_iter().len() + 2);
+        for f in mesh.face_iter() {
+            let corners: Vec<Vec3> = f.vertex_iter().map(|v| *v.data().unwrap()).collect();
+            assert!(dot(newell_normal(&corners), centroid(&corners).unwrap()) > 0.0);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::geometry::Vec3;
+    use wedge::index::{FaceIndex, VertexIndex};
+    use wedge::mesh::Mesh;
+    use wedge::reposition::{MoveChecks, MoveError};
+
+    // Triangles from `center` to each side of the closed `rim`.
+    fn fan(center: Vec3, rim: &[Vec3]) -> (Mesh<Vec3, (), ()>, VertexIndex) {
+        let mut mesh = Mesh::new();
+        let c = mesh.add_vertex(center);
+        let r: Vec<VertexIndex> = rim.iter().map(|p| mesh.add_vertex(*p)).collect();
+        for i in 0..r.len() {
+            mesh.add_default_face(&[c, r[i], r[(i + 1) % r.len()]]);
+        }
+        (mesh, c)
+    }
+
+    #[test]
+    fn test_try_move_vertex_inversion() {
+        let hexagon: Vec<Vec3> = (0..6).map(|i| {
+            let a = i as f32 * std::f32::consts::PI / 3.0;
+            [a.cos(), a.sin(), 0.0]
+        }).collect();
+        let (mut mesh, c) = fan([0.0, 0.0, 0.0], &hexagon);
+        assert_eq!(mesh.try_move_vertex(c, [0.3, -0.2, 0.5], MoveChecks::ALL), Ok(()));
+        assert_eq!(*mesh.vertex(c).data().unwrap(), [0.3, -0.2, 0.5]);
+
+        // past the rim, the triangles on the far side turn over.
+        let moved = mesh.try_move_vertex(c, [2.0, 0.0, 0.0], MoveChecks::ALL);
+        assert!(matches!(moved, Err(MoveError::Inverted(_))));
+        assert_eq!(*mesh.vertex(c).data().unwrap(), [0.3, -0.2, 0.5]);
+        // so do all of them, below a raised rim.
+        let raised: Vec<Vec3> = hexagon.iter().map(|p| [p[0], p[1], 1.0]).collect();
+        let (mut cone, apex) = fan([0.0, 0.0, 0.0], &raised);
+        assert!(matches!(cone.try_move_vertex(apex, [0.0, 0.0, 2.0], MoveChecks::ALL), Err(MoveError::Inverted(_))));
+
+        assert_eq!(mesh.try_move_vertex(c, [2.0, 0.0, 0.0], MoveChecks::NONE), Ok(()));
+        assert_eq!(*mesh.vertex(c).data().unwrap(), [2.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_try_move_vertex_intersection() {
+        // with the center at the origin, the first triangle lies in y = 0 and
+        // the third one's far side, from (1, -1, 0) to (1, 1, 0), pierces it.
+        let rim = [[2.0, 0.0, -1.0], [2.0, 0.0, 1.0], [1.0, -1.0, 0.0], [1.0, 1.0, 0.0]];
+        let (mut mesh, c) = fan([-2.0, -2.0, 3.0], &rim);
+        let inversion = MoveChecks { inversion: true, self_intersection: false };
+        assert_eq!(mesh.try_move_vertex(c, [-2.0, -2.0, 3.0], MoveChecks::ALL), Ok(()));
+        assert_eq!(mesh.try_move_vertex(c, [0.0, 0.0, 0.0], MoveChecks::ALL),
+                   Err(MoveError::Intersecting(FaceIndex(0), FaceIndex(2))));
+        assert_eq!(*mesh.vertex(c).data().unwrap(), [-2.0, -2.0, 3.0]);
+        assert_eq!(MoveError::Intersecting(FaceIndex(0), FaceIndex(2)).to_string(), "faces 0 and 2 would intersect");
+
+        // no face turns over, so checking for that alone lets it through.
+        assert_eq!(mesh.try_move_vertex(c, [0.0, 0.0, 0.0], inversion), Ok(()));
+        assert_eq!(*mesh.vertex(c).data().unwrap(), [0.0, 0.0, 0.0]);
+    }
+}
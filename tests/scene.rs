@@ -0,0 +1,87 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::geometry::*;
+    use wedge::index::FaceIndex;
+    use wedge::scene::Scene;
+
+    #[test]
+    fn test_hierarchy() {
+        let mut scene = Scene::new();
+        let car = scene.add_node("car", None);
+        let wheel = scene.add_node("wheel", Some(car));
+        scene.node_mut(wheel).faces = vec![FaceIndex(3), FaceIndex(4), FaceIndex(5)];
+        assert_eq!(scene.find("wheel"), Some(wheel));
+        assert_eq!(scene.find("boat"), None);
+        assert_eq!(scene.roots(), vec![car]);
+        assert_eq!(scene.node(car).children(), &[wheel]);
+        assert_eq!(scene.node_of_face(FaceIndex(4)), Some(wheel));
+        assert_eq!(scene.node_of_face(FaceIndex(6)), None);
+    }
+
+    #[test]
+    fn test_world_transform() {
+        let mut scene = Scene::new();
+        let a = scene.add_node("a", None);
+        let b = scene.add_node("b", Some(a));
+        scene.node_mut(a).transform = translation([1.0, 0.0, 0.0]);
+        scene.node_mut(b).transform = translation([0.0, 2.0, 0.0]);
+        let m = scene.world_transform(b);
+        assert_eq!(transform_point(&m, [0.0, 0.0, 1.0]), [1.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_remove_face() {
+        use wedge::index::VertexIndex;
+        use wedge::mesh::Mesh;
+        use wedge::provenance::FaceIds;
+
+        // a strip of three triangles, the first two in one node and the
+        // last in another.
+        let mut mesh: Mesh<(), (), char> = Mesh::new();
+        let v: Vec<VertexIndex> = (0..5).map(|_| mesh.add_vertex(())).collect();
+        let a = mesh.add_face_with('a', &[v[0], v[1], v[2]], |_, _| ());
+        let b = mesh.add_face_with('b', &[v[2], v[1], v[3]], |_, _| ());
+        let c = mesh.add_face_with('c', &[v[2], v[3], v[4]], |_, _| ());
+        let mut scene = Scene::new();
+        let front = scene.add_node("front", None);
+        let back = scene.add_node("back", None);
+        scene.node_mut(front).faces = vec![a, b];
+        scene.node_mut(back).faces = vec![c];
+        let mut ids = FaceIds::new();
+        let id_b = ids.assign(b);
+        let id_c = ids.assign(c);
+
+        // c moves into a's index.
+        let moved = mesh.remove_face(a, false);
+        assert_eq!(moved, Some(c));
+        scene.swap_remove_face(a, moved);
+        ids.swap_remove(a, moved);
+        assert_eq!(scene.node(front).faces, vec![b]);
+        assert_eq!(scene.node(back).faces, vec![a]);
+        assert_eq!(mesh[scene.node(back).faces[0]], 'c');
+        assert_eq!(ids.face(id_c), Some(a));
+        assert_eq!(ids.id(a), Some(id_c));
+        assert_eq!(ids.id(c), None);
+
+        // b is last now, so nothing moves.
+        let moved = mesh.remove_face(b, false);
+        assert_eq!(moved, None);
+        scene.swap_remove_face(b, moved);
+        ids.swap_remove(b, moved);
+        assert!(scene.node(front).faces.is_empty());
+        assert_eq!(scene.node_of_face(a), Some(back));
+        assert_eq!(ids.resolve(id_b), vec![]);
+        assert_eq!(ids.resolve(id_c), vec![a]);
+    }
+
+    #[test]
+    fn test_remap_faces() {
+        let mut scene = Scene::new();
+        let a = scene.add_node("a", None);
+        scene.node_mut(a).faces = vec![FaceIndex(0), FaceIndex(2)];
+        scene.remap_faces(&[FaceIndex(1), FaceIndex(2), FaceIndex(0)]);
+        assert_eq!(scene.node(a).faces, vec![FaceIndex(1), FaceIndex(0)]);
+    }
+}
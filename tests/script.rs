@@ -0,0 +1,48 @@
+#![cfg(feature = "rhai")]
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::dynamic::Value;
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+    use wedge::script::VertexScript;
+
+    fn path() -> Mesh<[f32; 3], (), ()> {
+        let mut mesh = Mesh::new();
+        let a = mesh.add_vertex([0.0, 0.0, 0.0]);
+        let b = mesh.add_vertex([1.0, 0.0, 0.0]);
+        let c = mesh.add_vertex([2.0, 1.0, 0.0]);
+        mesh.add_edge((), a, b);
+        mesh.add_edge((), b, c);
+        mesh
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let mesh = path();
+        let script = VertexScript::compile("if valence > 1 { \"inner\" } else { x + y }").unwrap();
+        let values = script.evaluate(&mesh).unwrap();
+        assert_eq!(values, vec![Value::Float(0.0), Value::Text("inner".to_string()), Value::Float(3.0)]);
+    }
+
+    #[test]
+    fn test_displace() {
+        let mut mesh = path();
+        let script = VertexScript::compile("[x, y, z + index]").unwrap();
+        script.displace(&mut mesh).unwrap();
+        assert_eq!(mesh.vertices()[VertexIndex(2)], [2.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut mesh = path();
+        assert!(VertexScript::compile("x +").is_err());
+        let script = VertexScript::compile("if index == 1 { 0 } else { position }").unwrap();
+        let error = script.displace(&mut mesh).unwrap_err();
+        assert_eq!(error.vertex, Some(VertexIndex(1)));
+        assert_eq!(mesh.vertices()[VertexIndex(0)], [0.0, 0.0, 0.0]);
+        let script = VertexScript::compile("position[3]").unwrap();
+        assert_eq!(script.evaluate(&mesh).unwrap_err().vertex, Some(VertexIndex(0)));
+    }
+}
@@ -0,0 +1,134 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+    use wedge::selection::VertexSelection;
+
+    // 0 - 1 - 2 - 3 - 4 - 5 - 6
+    fn path(n: u32) -> Mesh<(), (), ()> {
+        let mut mesh = Mesh::new();
+        for _ in 0..n {
+            mesh.add_vertex(());
+        }
+        for i in 1..n {
+            mesh.add_edge((), VertexIndex(i - 1), VertexIndex(i));
+        }
+        mesh
+    }
+
+    fn vertices(indices: &[u32]) -> Vec<VertexIndex> {
+        indices.iter().map(|i| VertexIndex(*i)).collect()
+    }
+
+    #[test]
+    fn test_grow_shrink() {
+        let mesh = path(7);
+        let mut selection = VertexSelection::from_slice(&vertices(&[3]));
+        selection.grow(&mesh, 2);
+        assert_eq!(selection.to_vec(), vertices(&[1, 2, 3, 4, 5]));
+        selection.grow(&mesh, 10);
+        assert_eq!(selection.len(), 7);
+
+        let mut selection = VertexSelection::from_slice(&vertices(&[1, 2, 3, 4, 5]));
+        selection.shrink(&mesh, 1);
+        assert_eq!(selection.to_vec(), vertices(&[2, 3, 4]));
+        selection.shrink(&mesh, 5);
+        assert!(selection.is_empty());
+    }
+
+    #[test]
+    fn test_boundary() {
+        let mesh = path(7);
+        let selection = VertexSelection::from_slice(&vertices(&[0, 1, 2, 3]));
+        assert_eq!(selection.boundary(&mesh).to_vec(), vertices(&[3]));
+    }
+
+    #[test]
+    fn test_edge_grow() {
+        use wedge::index::EdgeIndex;
+        use wedge::selection::EdgeSelection;
+
+        let mesh = path(5);
+        let mut selection = EdgeSelection::from_slice(&[EdgeIndex(0)]);
+        selection.grow(&mesh, 2);
+        assert_eq!(selection.to_vec(), vec![EdgeIndex(0), EdgeIndex(1), EdgeIndex(2)]);
+    }
+
+    #[test]
+    fn test_select_region() {
+        use wedge::index::EdgeIndex;
+        use wedge::selection::{EdgeSelection, ScreenRegion};
+
+        // a camera at the origin looking down -z, so w = -z.
+        let view_projection = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0],
+        ];
+        let mut mesh: Mesh<[f32; 3], (), ()> = Mesh::new();
+        let a = mesh.add_vertex([0.0, 0.0, -2.0]);
+        let b = mesh.add_vertex([1.0, 0.0, -4.0]);
+        let c = mesh.add_vertex([1.0, 0.0, -1.0]);
+        let d = mesh.add_vertex([0.0, 0.0, 2.0]); // behind the camera.
+        mesh.add_edge((), a, b);
+        mesh.add_edge((), b, c);
+        mesh.add_edge((), a, d);
+
+        let rect = ScreenRegion::Rect([0.5, 0.5], [-0.5, -0.5]);
+        assert_eq!(VertexSelection::from_region(&mesh, &view_projection, &rect, false).to_vec(), vec![a, b]);
+        assert_eq!(EdgeSelection::from_region(&mesh, &view_projection, &rect, false).to_vec(), vec![EdgeIndex(0)]);
+
+        let lasso = ScreenRegion::Lasso(vec![[0.1, -0.5], [2.0, -0.5], [2.0, 0.5], [0.1, 0.5]]);
+        assert_eq!(VertexSelection::from_region(&mesh, &view_projection, &lasso, false).to_vec(), vec![b, c]);
+        assert!(!lasso.contains([0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_select_region_occluded() {
+        use wedge::index::FaceIndex;
+        use wedge::selection::{FaceSelection, ScreenRegion};
+
+        // a wall at z = -2 in front of a triangle at z = -4, and a vertex off
+        // to the side of the wall.
+        let mut mesh: Mesh<[f32; 3], (), ()> = Mesh::new();
+        let wall: Vec<VertexIndex> = [[-1.0, -1.0, -2.0], [1.0, -1.0, -2.0], [1.0, 1.0, -2.0], [-1.0, 1.0, -2.0]]
+            .iter().map(|p| mesh.add_vertex(*p)).collect();
+        mesh.add_default_face(&wall);
+        let hidden: Vec<VertexIndex> = [[0.2, 0.1, -4.0], [0.4, 0.1, -4.0], [0.2, 0.3, -4.0]]
+            .iter().map(|p| mesh.add_vertex(*p)).collect();
+        mesh.add_default_face(&hidden);
+        let side = mesh.add_vertex([3.0, 0.0, -4.0]);
+        let rect = ScreenRegion::Rect([-1.0, -1.0], [1.0, 1.0]);
+
+        // a camera at the origin looking down -z, and one looking the same
+        // way without perspective.
+        let perspective = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0],
+        ];
+        let orthographic = [
+            [0.25, 0.0, 0.0, 0.0],
+            [0.0, 0.25, 0.0, 0.0],
+            [0.0, 0.0, -0.1, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        for view_projection in [perspective, orthographic].iter() {
+            let all = VertexSelection::from_region(&mesh, view_projection, &rect, false);
+            assert_eq!(all.len(), 8);
+            let visible = VertexSelection::from_region(&mesh, view_projection, &rect, true);
+            let mut expected = wall.clone();
+            expected.push(side);
+            assert_eq!(visible.to_vec(), expected);
+
+            let faces = FaceSelection::from_region(&mesh, view_projection, &rect, false);
+            assert_eq!(faces.to_vec(), vec![FaceIndex(0), FaceIndex(1)]);
+            let faces = FaceSelection::from_region(&mesh, view_projection, &rect, true);
+            assert_eq!(faces.to_vec(), vec![FaceIndex(0)]);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::geometry::Vec3;
+    use wedge::index::{FaceIndex, VertexIndex};
+    use wedge::mesh::Mesh;
+    use wedge::silhouette::feature_edges;
+
+    // The unit cube facing out; vertex i is at the bits of i as x, y, z.
+    fn cube() -> Mesh<Vec3, (), ()> {
+        let mut mesh = Mesh::new();
+        let v: Vec<VertexIndex> = (0..8)
+            .map(|i| mesh.add_vertex([(i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2) as f32]))
+            .collect();
+        for face in [[0, 2, 3, 1], [4, 5, 7, 6], [0, 1, 5, 4], [2, 6, 7, 3], [0, 4, 6, 2], [1, 3, 7, 5]].iter() {
+            mesh.add_default_face(&[v[face[0]], v[face[1]], v[face[2]], v[face[3]]]);
+        }
+        mesh
+    }
+
+    #[test]
+    fn test_feature_edges_cube() {
+        let mesh = cube();
+        // facing the +x side only, its rim is the silhouette.
+        let edges = feature_edges(&mesh, [3.0, 0.5, 0.5], 0.5);
+        assert_eq!(edges.silhouette.len(), 4);
+        for h in edges.silhouette.iter() {
+            assert_eq!(mesh.vertex(h.from).data().unwrap()[0], 1.0);
+            assert_eq!(mesh.vertex(h.to).data().unwrap()[0], 1.0);
+        }
+        // every edge is a right angle, and nothing is open.
+        assert_eq!(edges.crease.len(), 12);
+        assert!(edges.border.is_empty());
+        assert!(feature_edges(&mesh, [3.0, 0.5, 0.5], 2.0).crease.is_empty());
+
+        // from a corner, three sides face the eye and the silhouette is a
+        // hexagon, wound like the faces that are in front.
+        let edges = feature_edges(&mesh, [3.0, 3.0, 3.0], 0.5);
+        assert_eq!(edges.silhouette.len(), 6);
+        let mut h = edges.silhouette[0];
+        for _ in 0..6 {
+            h = *edges.silhouette.iter().find(|next| next.from == h.to).unwrap();
+        }
+        assert_eq!(h, edges.silhouette[0]);
+        assert!(edges.silhouette.iter().all(|h| h.from != VertexIndex(7) && h.to != VertexIndex(7)));
+
+        // from inside, every face is turned away.
+        assert!(feature_edges(&mesh, [0.5, 0.5, 0.5], 0.5).silhouette.is_empty());
+    }
+
+    #[test]
+    fn test_feature_edges_open() {
+        // without its top, the cube's rim is a border, and a flat quad
+        // beside it creases nothing.
+        let mut mesh = cube();
+        mesh.remove_face(FaceIndex(1), false);
+        let edges = feature_edges(&mesh, [0.5, 0.5, 3.0], 0.5);
+        assert_eq!(edges.border.len(), 4);
+        assert_eq!(edges.crease.len(), 8);
+
+        let mut flat: Mesh<Vec3, (), ()> = Mesh::new();
+        let v: Vec<VertexIndex> = [[0.0, 0.0], [1.0, 0.0], [2.0, 0.0], [2.0, 1.0], [1.0, 1.0], [0.0, 1.0]].iter()
+            .map(|p: &[f32; 2]| flat.add_vertex([p[0], p[1], 0.0])).collect();
+        flat.add_default_face(&[v[0], v[1], v[4], v[5]]);
+        flat.add_default_face(&[v[1], v[2], v[3], v[4]]);
+        flat.add_edge((), v[0], v[2]);
+        let edges = feature_edges(&flat, [0.5, 0.5, 1.0], 0.1);
+        assert!(edges.crease.is_empty() && edges.silhouette.is_empty());
+        assert_eq!(edges.border.len(), 6);
+    }
+}
@@ -0,0 +1,101 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::geometry::Vec3;
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+    use wedge::slice::{infill, slice, slice_at, Infill, Layer, LineInfill};
+
+    // The side walls of a square prism over [lo, hi]^2 x [0, 1], facing
+    // outwards, or inwards for the inside of a hole.
+    fn walls(mesh: &mut Mesh<Vec3, (), ()>, lo: f32, hi: f32, inwards: bool) -> Vec<VertexIndex> {
+        let corners = [[lo, lo], [hi, lo], [hi, hi], [lo, hi]];
+        let v: Vec<VertexIndex> = [0.0, 1.0].iter()
+            .flat_map(|z| corners.iter().map(move |c| [c[0], c[1], *z]))
+            .map(|p| mesh.add_vertex(p))
+            .collect();
+        for i in 0..4 {
+            let j = (i + 1) % 4;
+            let mut face = vec![v[i], v[j], v[j + 4], v[i + 4]];
+            if inwards {
+                face.reverse();
+            }
+            mesh.add_default_face(&face);
+        }
+        v
+    }
+
+    fn cube() -> Mesh<Vec3, (), ()> {
+        let mut mesh = Mesh::new();
+        let v = walls(&mut mesh, 0.0, 1.0, false);
+        mesh.add_default_face(&[v[0], v[3], v[2], v[1]]);
+        mesh.add_default_face(&[v[4], v[5], v[6], v[7]]);
+        mesh
+    }
+
+    fn signed_area(points: &[[f32; 2]]) -> f32 {
+        (0..points.len()).map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            a[0] * b[1] - b[0] * a[1]
+        }).sum::<f32>() / 2.0
+    }
+
+    #[test]
+    fn test_slice_cube() {
+        let contours = slice_at(&cube(), 0.5);
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0].closed);
+        assert_eq!(contours[0].points.len(), 4);
+        assert_eq!(signed_area(&contours[0].points), 1.0);
+        // vertices on the plane count as above it, so the bottom is missed
+        // and the top outlined.
+        assert_eq!(slice_at(&cube(), 0.0).len(), 0);
+        assert_eq!(signed_area(&slice_at(&cube(), 1.0)[0].points), 1.0);
+
+        let layers = slice(&cube(), 0.25);
+        let heights: Vec<f32> = layers.iter().map(|l| l.z).collect();
+        assert_eq!(heights, vec![0.125, 0.375, 0.625, 0.875]);
+        assert!(layers.iter().all(|l| l.contours.len() == 1));
+    }
+
+    #[test]
+    fn test_slice_hole_and_open() {
+        let mut tube = Mesh::new();
+        walls(&mut tube, 0.0, 3.0, false);
+        walls(&mut tube, 1.0, 2.0, true);
+        let contours = slice_at(&tube, 0.5);
+        let mut areas: Vec<f32> = contours.iter().map(|c| signed_area(&c.points)).collect();
+        areas.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(areas, vec![-1.0, 9.0]);
+
+        // a single wall is an open contour, still with the material to its left.
+        let mut wall: Mesh<Vec3, (), ()> = Mesh::new();
+        let v: Vec<VertexIndex> = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 0.0, 1.0], [0.0, 0.0, 1.0]].iter()
+            .map(|p| wall.add_vertex(*p)).collect();
+        wall.add_default_face(&v);
+        let contours = slice_at(&wall, 0.5);
+        assert_eq!(contours.len(), 1);
+        assert!(!contours[0].closed);
+        assert_eq!(contours[0].points, vec![[0.0, 0.0], [1.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_line_infill() {
+        let layers = slice(&cube(), 0.5);
+        let mut pattern = LineInfill { spacing: 0.25, angle: 0.0, alternate: true };
+        let paths = infill(&layers, &mut pattern);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].len(), 4);
+        assert_eq!(paths[0][1], vec![[0.0, 0.25], [1.0, 0.25]]);
+        // a quarter turn on the next layer.
+        assert_eq!(paths[1].len(), 4);
+        for line in paths[1].iter() {
+            assert!((line[0][0] - line[1][0]).abs() < 1e-5);
+            assert!((line[0][1] - line[1][1]).abs() > 0.99);
+        }
+
+        let empty = Layer { z: 0.0, contours: Vec::new() };
+        assert!(pattern.fill(&empty, 0).is_empty());
+    }
+}
@@ -0,0 +1,56 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::index::{EdgeIndex, VertexIndex};
+    use wedge::mesh::Mesh;
+    use wedge::snap::{snap_to_grid, SnapTarget, Snapper};
+
+    // An L of two unit edges, 0 - 1 - 2, and a loose vertex 3.
+    fn corner() -> Mesh<[f32; 3], (), ()> {
+        let mut mesh = Mesh::new();
+        let a = mesh.add_vertex([0.0, 0.0, 0.0]);
+        let b = mesh.add_vertex([1.0, 0.0, 0.0]);
+        let c = mesh.add_vertex([1.0, 1.0, 0.0]);
+        mesh.add_vertex([0.5, 0.5, 0.0]);
+        mesh.add_edge((), a, b);
+        mesh.add_edge((), b, c);
+        mesh
+    }
+
+    #[test]
+    fn test_snap_to_mesh() {
+        let mesh = corner();
+        let snapper = Snapper::new(&mesh, 0.1);
+
+        let snap = snapper.snap([0.95, 0.02, 0.0], None).unwrap();
+        assert_eq!(snap.target, SnapTarget::Vertex(VertexIndex(1)));
+        assert_eq!(snap.position, [1.0, 0.0, 0.0]);
+
+        let snap = snapper.snap([0.4, 0.05, 0.0], None).unwrap();
+        assert_eq!(snap.target, SnapTarget::Edge(EdgeIndex(0)));
+        assert_eq!(snap.position, [0.4, 0.0, 0.0]);
+
+        assert_eq!(snapper.snap([0.25, 0.75, 0.0], None), None);
+    }
+
+    #[test]
+    fn test_snap_moving_vertex() {
+        let mesh = corner();
+        let snapper = Snapper::new(&mesh, 0.1);
+        // vertex 3 dragged next to itself and onto edge 1 snaps to the edge.
+        let snap = snapper.snap([0.55, 0.5, 0.0], Some(VertexIndex(3)));
+        assert_eq!(snap, None);
+        let snap = snapper.snap([0.95, 0.5, 0.0], Some(VertexIndex(3))).unwrap();
+        assert_eq!(snap.target, SnapTarget::Edge(EdgeIndex(1)));
+        // vertex 1 never snaps to its own edges.
+        assert_eq!(snapper.snap([0.95, 0.02, 0.0], Some(VertexIndex(1))), None);
+    }
+
+    #[test]
+    fn test_snap_to_grid() {
+        let snap = snap_to_grid([0.26, -0.74, 1.0], 0.5);
+        assert_eq!(snap.position, [0.5, -0.5, 1.0]);
+        assert_eq!(snap.target, SnapTarget::Grid);
+    }
+}
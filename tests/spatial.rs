@@ -0,0 +1,53 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+    use wedge::spatial::SpatialHash;
+
+    fn grid_mesh() -> Mesh<[f32; 3], (), ()> {
+        let mut mesh = Mesh::new();
+        for x in 0..4 {
+            for y in 0..4 {
+                mesh.add_vertex([x as f32, y as f32, 0.0]);
+            }
+        }
+        mesh
+    }
+
+    #[test]
+    fn test_query_radius() {
+        let mesh = grid_mesh();
+        let hash = SpatialHash::from_mesh(&mesh, 1.5);
+        assert_eq!(hash.len(), 16);
+        let mut found = hash.query_radius([1.0, 1.0, 0.0], 1.0);
+        found.sort();
+        assert_eq!(found, [1, 4, 5, 6, 9].iter().map(|i| VertexIndex(*i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_nearest() {
+        let mesh = grid_mesh();
+        let hash = SpatialHash::from_mesh(&mesh, 0.5);
+        assert_eq!(hash.nearest([2.9, 0.2, 0.1], 10.0), Some(VertexIndex(12)));
+        assert_eq!(hash.nearest([10.0, 10.0, 0.0], 1.0), None);
+        assert_eq!(hash.nearest([10.0, 10.0, 0.0], 100.0), Some(VertexIndex(15)));
+    }
+
+    #[test]
+    fn test_incremental_update() {
+        let mesh = grid_mesh();
+        let mut hash = SpatialHash::from_mesh(&mesh, 1.0);
+        assert!(hash.update(VertexIndex(0), [3.1, 3.1, 0.0]));
+        assert_eq!(hash.nearest([3.2, 3.2, 0.0], 1.0), Some(VertexIndex(0)));
+        assert!(hash.query_radius([0.0, 0.0, 0.0], 0.5).is_empty());
+
+        hash.insert(VertexIndex(16), [-1.0, 0.0, 0.0]);
+        assert_eq!(hash.nearest([-0.9, 0.0, 0.0], 1.0), Some(VertexIndex(16)));
+        assert!(hash.remove(VertexIndex(16)));
+        assert!(!hash.remove(VertexIndex(16)));
+        assert!(!hash.update(VertexIndex(16), [0.0, 0.0, 0.0]));
+        assert_eq!(hash.len(), 16);
+    }
+}
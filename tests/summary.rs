@@ -0,0 +1,34 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+
+    #[test]
+    fn test_summary() {
+        let mut mesh: Mesh<[f32; 3], (), ()> = Mesh::new();
+        let empty = mesh.summary();
+        assert_eq!(empty.components, 0);
+        assert!(empty.bounds.is_none());
+
+        for i in 0..5 {
+            mesh.add_vertex([i as f32, 0.0, -1.0]);
+        }
+        mesh.add_edge((), VertexIndex(0), VertexIndex(1));
+        mesh.add_edge((), VertexIndex(1), VertexIndex(2));
+        mesh.add_edge((), VertexIndex(3), VertexIndex(4));
+
+        let summary = mesh.summary_with_bounds();
+        assert_eq!((summary.vertices, summary.edges, summary.faces), (5, 3, 0));
+        assert_eq!(summary.wire_edges, 3);
+        assert_eq!(summary.components, 2);
+        assert_eq!(summary.boundary_loops, 0);
+        assert_eq!(summary.bounds, Some(([0.0, 0.0, -1.0], [4.0, 0.0, -1.0])));
+        assert!(summary.memory >= mesh.memory_usage());
+
+        let text = summary.to_string();
+        assert!(text.starts_with("5 vertices, 3 edges, 0 faces (3 wire), 2 components"), "{}", text);
+        assert!(text.contains("bounds [0, 0, -1]..[4, 0, -1]"), "{}", text);
+    }
+}
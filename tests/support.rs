@@ -0,0 +1,119 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::geometry::{bounds, distance, transform_point, Vec3, IDENTITY};
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+    use wedge::support::{optimize_orientation, overhangs, supports, OrientCost, OrientOptions, SupportOptions};
+
+    // A horizontal square over [lo, hi]^2 at height z, facing up or down.
+    fn square(mesh: &mut Mesh<Vec3, (), ()>, lo: [f32; 2], hi: [f32; 2], z: f32, up: bool) {
+        let corners = [[lo[0], lo[1]], [hi[0], lo[1]], [hi[0], hi[1]], [lo[0], hi[1]]];
+        let mut v: Vec<VertexIndex> = corners.iter().map(|c| mesh.add_vertex([c[0], c[1], z])).collect();
+        if !up {
+            v.reverse();
+        }
+        mesh.add_default_face(&v);
+    }
+
+    // A shelf facing down at z = 2 over [0, 4]^2, with the build plate set
+    // by a floor tile off to the side.
+    fn shelf() -> Mesh<Vec3, (), ()> {
+        let mut mesh = Mesh::new();
+        square(&mut mesh, [0.0, 0.0], [4.0, 4.0], 2.0, false);
+        square(&mut mesh, [10.0, 0.0], [11.0, 1.0], 0.0, true);
+        mesh
+    }
+
+    #[test]
+    fn test_overhangs() {
+        // the shelf plus a triangle tilted by `angle` from vertical, facing
+        // down.
+        let tilted = |angle: f32| {
+            let mut mesh = shelf();
+            let (s, c) = angle.sin_cos();
+            let v: Vec<VertexIndex> = [[20.0, 0.0, 1.0], [20.0, 1.0, 1.0], [20.0 + s, 0.0, 1.0 + c]]
+                .iter().map(|p| mesh.add_vertex(*p)).collect();
+            mesh.add_default_face(&v);
+            overhangs(&mesh, std::f32::consts::FRAC_PI_4).len()
+        };
+        // only the shelf; the floor rests on the plate.
+        assert_eq!(overhangs(&shelf(), std::f32::consts::FRAC_PI_4).len(), 1);
+        assert_eq!(tilted(0.3), 1);
+        assert_eq!(tilted(1.2), 2);
+        assert_eq!(overhangs(&shelf(), std::f32::consts::FRAC_PI_2).len(), 0);
+        assert!(overhangs(&Mesh::<Vec3, (), ()>::new(), 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_supports() {
+        let options = SupportOptions { spacing: 1.0, sides: 6, ..Default::default() };
+        let pillars = supports(&shelf(), &options);
+        // a 4 x 4 grid of hexagonal prisms from the plate to the shelf.
+        assert_eq!(pillars.face_iter().len(), 16 * 8);
+        assert_eq!(pillars.vertex_iter().len(), 16 * 12);
+        assert_eq!(pillars.summary().boundary_loops, 0);
+        let (min, max) = bounds(pillars.vertex_iter().map(|v| *v.data().unwrap())).unwrap();
+        assert_eq!((min[2], max[2]), (0.0, 2.0));
+        assert!(min[0] >= 0.0 && max[0] <= 4.0 && min[1] >= 0.0 && max[1] <= 4.0);
+    }
+
+    #[test]
+    fn test_supports_blocked() {
+        // half the shelf is over the top of a block, which pillars can't
+        // pass through.
+        let mut mesh = shelf();
+        square(&mut mesh, [0.0, 0.0], [2.0, 4.0], 1.0, true);
+        let pillars = supports(&mesh, &SupportOptions { spacing: 1.0, ..Default::default() });
+        assert_eq!(pillars.face_iter().len(), 8 * 8);
+        let (min, _) = bounds(pillars.vertex_iter().map(|v| *v.data().unwrap())).unwrap();
+        assert!(min[0] >= 2.0);
+
+        assert_eq!(supports(&Mesh::<Vec3, (), ()>::new(), &SupportOptions::default()).vertex_iter().len(), 0);
+    }
+
+    // A closed box over [lo, hi], facing outwards.
+    fn add_box(mesh: &mut Mesh<Vec3, (), ()>, lo: Vec3, hi: Vec3) {
+        let v: Vec<VertexIndex> = (0..8)
+            .map(|i| mesh.add_vertex([if i & 1 == 0 { lo[0] } else { hi[0] }, if i & 2 == 0 { lo[1] } else { hi[1] },
+                                      if i & 4 == 0 { lo[2] } else { hi[2] }]))
+            .collect();
+        for face in [[0, 2, 3, 1], [4, 5, 7, 6], [0, 1, 5, 4], [2, 6, 7, 3], [0, 4, 6, 2], [1, 3, 7, 5]].iter() {
+            mesh.add_default_face(&face.iter().map(|i| v[*i]).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_optimize_orientation() {
+        // a box sitting on one of its faces is fine as it is.
+        let mut cube = Mesh::new();
+        add_box(&mut cube, [0.0; 3], [1.0; 3]);
+        let best = optimize_orientation(&cube, &OrientOptions::default());
+        assert_eq!(best.transform, IDENTITY);
+        assert_eq!((best.overhang_area, best.support_volume), (0.0, 0.0));
+
+        // a mushroom: a broad cap on a thin stalk. Tipped onto a corner, no
+        // face hangs over more than 45 degrees.
+        let mut mushroom = Mesh::new();
+        add_box(&mut mushroom, [-0.2, -0.2, 0.0], [0.2, 0.2, 1.0]);
+        add_box(&mut mushroom, [-2.0, -2.0, 1.0], [2.0, 2.0, 1.2]);
+        let tipped = optimize_orientation(&mushroom, &OrientOptions::default());
+        assert_eq!(tipped.support_volume, 0.0);
+        assert_ne!(tipped.transform, IDENTITY);
+
+        // if nearly every downward face needs support, it prints best upside
+        // down, where the only face left facing down is the stalk's top,
+        // inside the cap.
+        let strict = OrientOptions { max_overhang: 0.1, ..Default::default() };
+        let best = optimize_orientation(&mushroom, &strict);
+        assert!(distance(transform_point(&best.transform, [0.0, 0.0, -1.0]), [0.0, 0.0, 1.0]) < 1e-5);
+        assert!((best.overhang_area - 0.16).abs() < 1e-4);
+        assert!((best.support_volume - 0.16 * 0.2).abs() < 1e-4);
+
+        let by_area = optimize_orientation(&mushroom, &OrientOptions { cost: OrientCost::OverhangArea, ..strict });
+        assert!(by_area.overhang_area <= best.overhang_area);
+
+        assert_eq!(optimize_orientation(&Mesh::<Vec3, (), ()>::new(), &OrientOptions::default()).transform, IDENTITY);
+    }
+}
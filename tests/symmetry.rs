@@ -0,0 +1,43 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::index::VertexIndex;
+    use wedge::mesh::Mesh;
+    use wedge::symmetry::find_symmetries;
+    use wedge::tolerance::Tolerance;
+
+    // A closed loop through `points`.
+    fn polygon(points: &[[f32; 3]]) -> Mesh<[f32; 3], (), ()> {
+        let mut mesh = Mesh::new();
+        let v: Vec<VertexIndex> = points.iter().map(|p| mesh.add_vertex(*p)).collect();
+        for i in 0..v.len() {
+            mesh.add_edge((), v[i], v[(i + 1) % v.len()]);
+        }
+        mesh
+    }
+
+    #[test]
+    fn test_square_symmetries() {
+        let mesh = polygon(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]);
+        let symmetries = find_symmetries(&mesh, &Tolerance::new(1e-4));
+        assert_eq!(symmetries.len(), 4);
+        let across_x = symmetries.iter().find(|s| s.normal == [1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(across_x.point, [0.5, 0.5, 0.0]);
+        assert_eq!(across_x.mirror, vec![VertexIndex(1), VertexIndex(0), VertexIndex(3), VertexIndex(2)]);
+    }
+
+    #[test]
+    fn test_connectivity_breaks_symmetry() {
+        // a rectangle, whose diagonals are not symmetry planes.
+        let mut mesh = polygon(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [2.0, 1.0, 0.0], [0.0, 1.0, 0.0]]);
+        assert_eq!(find_symmetries(&mesh, &Tolerance::new(1e-4)).len(), 2);
+        // one diagonal edge is only preserved by a half turn, not a reflection.
+        mesh.add_edge((), VertexIndex(0), VertexIndex(2));
+        assert_eq!(find_symmetries(&mesh, &Tolerance::new(1e-4)).len(), 0);
+        // a nudged vertex is still symmetric within a loose tolerance.
+        let nudged = polygon(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [2.0, 1.0, 0.0], [0.0, 1.001, 0.0]]);
+        assert_eq!(find_symmetries(&nudged, &Tolerance::new(1e-4)).len(), 0);
+        assert_eq!(find_symmetries(&nudged, &Tolerance::new(0.01)).len(), 2);
+    }
+}
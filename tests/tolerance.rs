@@ -0,0 +1,37 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::mesh::Mesh;
+    use wedge::tolerance::Tolerance;
+
+    #[test]
+    fn test_from_mesh_scales_with_extent() {
+        let mut mesh: Mesh<[f32; 3], (), ()> = Mesh::new();
+        assert_eq!(Tolerance::from_mesh(&mesh), Tolerance::default());
+        mesh.add_vertex([0.0, 0.0, 0.0]);
+        mesh.add_vertex([3000.0, 4000.0, 0.0]);
+        let tolerance = Tolerance::from_mesh(&mesh);
+        assert!((tolerance.distance - 5000.0 * Tolerance::RELATIVE_DISTANCE).abs() < 1e-6);
+        assert!(tolerance.coincident([1.0, 1.0, 1.0], [1.0, 1.04, 1.0]));
+        assert!(!Tolerance::default().coincident([1.0, 1.0, 1.0], [1.0, 1.04, 1.0]));
+    }
+
+    #[test]
+    fn test_degenerate_triangle() {
+        let tolerance = Tolerance::new(0.01);
+        assert!(!tolerance.is_degenerate_triangle([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]));
+        assert!(tolerance.is_degenerate_triangle([0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [5.0, 0.005, 0.0]));
+        assert!(tolerance.is_degenerate_triangle([0.0, 0.0, 0.0], [0.001, 0.0, 0.0], [0.0, 0.001, 0.0]));
+    }
+
+    #[test]
+    fn test_planarity() {
+        let tolerance = Tolerance::new(0.01);
+        let mut quad = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.005], [0.0, 1.0, 0.0]];
+        assert!(tolerance.is_planar(&quad));
+        quad[2][2] = 0.1;
+        assert!(!tolerance.is_planar(&quad));
+        assert!(tolerance.is_planar(&quad[..3]));
+    }
+}
@@ -0,0 +1,87 @@
+extern crate wedge;
+
+#[cfg(test)]
+mod tests {
+    use wedge::dynamic::{Attributes, DynMesh};
+    use wedge::geometry::*;
+    use wedge::index::VertexIndex;
+    use wedge::transfer::{interpolate_attributes, transfer_attributes};
+
+    fn vertex(mesh: &mut DynMesh, position: Vec3, color: Vec3, weight: f64) -> VertexIndex {
+        let mut attributes = Attributes::new();
+        attributes.set_position(position);
+        attributes.set("color", color);
+        attributes.set("weight", weight);
+        mesh.add_vertex(attributes)
+    }
+
+    // Destination vertices at `positions` with no other attributes.
+    fn points(positions: &[Vec3]) -> DynMesh {
+        let mut mesh = DynMesh::new();
+        for p in positions {
+            let mut attributes = Attributes::new();
+            attributes.set_position(*p);
+            mesh.add_vertex(attributes);
+        }
+        mesh
+    }
+
+    #[test]
+    fn test_transfer_from_faces() {
+        // a unit square in z = 0 that goes from black to red along x.
+        let mut src = DynMesh::new();
+        let a = vertex(&mut src, [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], 0.0);
+        let b = vertex(&mut src, [1.0, 0.0, 0.0], [1.0, 0.0, 0.0], 1.0);
+        let c = vertex(&mut src, [1.0, 1.0, 0.0], [1.0, 0.0, 0.0], 1.0);
+        let d = vertex(&mut src, [0.0, 1.0, 0.0], [0.0, 0.0, 0.0], 0.0);
+        src.add_default_face(&[a, b, c, d]);
+
+        let mut dst = points(&[[0.25, 0.5, 0.1], [0.75, 0.2, -0.3], [2.0, 0.5, 0.0]]);
+        transfer_attributes(&src, &mut dst, interpolate_attributes(&["color", "weight"]));
+        let expected = [0.25, 0.75, 1.0];
+        for (v, x) in dst.vertex_iter().zip(expected.iter()) {
+            let attributes = v.data().unwrap();
+            assert!((attributes.get_float("weight").unwrap() - x).abs() < 1e-6);
+            assert!(distance(attributes.get_vector("color").unwrap(), [*x as f32, 0.0, 0.0]) < 1e-6);
+        }
+        // positions are left alone.
+        assert_eq!(dst.vertices()[VertexIndex(0)].position(), [0.25, 0.5, 0.1]);
+    }
+
+    #[test]
+    fn test_transfer_from_edges_and_vertices() {
+        let mut src = DynMesh::new();
+        let a = vertex(&mut src, [0.0, 0.0, 0.0], [0.0; 3], 0.0);
+        let b = vertex(&mut src, [2.0, 0.0, 0.0], [0.0; 3], 1.0);
+        src.add_edge(Attributes::new(), a, b);
+        let loose = vertex(&mut src, [10.0, 0.0, 0.0], [0.0; 3], 5.0);
+        src.vertex_data_mut(loose).unwrap().set("name", "loose");
+
+        let mut dst = points(&[[0.5, 1.0, 0.0], [9.0, 1.0, 0.0]]);
+        transfer_attributes(&src, &mut dst, interpolate_attributes(&["weight", "name"]));
+        assert_eq!(dst.vertices()[VertexIndex(0)].get_float("weight"), Some(0.25));
+        assert_eq!(dst.vertices()[VertexIndex(0)].get("name"), None);
+        assert_eq!(dst.vertices()[VertexIndex(1)].get_float("weight"), Some(5.0));
+        assert_eq!(dst.vertices()[VertexIndex(1)].get_text("name"), Some("loose"));
+    }
+
+    #[test]
+    fn test_transfer_typed() {
+        let mut src: wedge::mesh::Mesh<Vec3, (), ()> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]].iter()
+            .map(|p| src.add_vertex(*p)).collect();
+        src.add_default_face(&v);
+        let mut dst: wedge::mesh::Mesh<Vec3, (), ()> = wedge::mesh::Mesh::new();
+        dst.add_vertex([0.2, 0.3, 1.0]);
+
+        // snaps each vertex onto the source surface.
+        transfer_attributes(&src, &mut dst, |sources, p| {
+            *p = sources.iter().fold([0.0; 3], |sum, (q, w)| add(sum, scale(**q, *w)));
+        });
+        assert!(distance(dst.vertices()[VertexIndex(0)], [0.2, 0.3, 0.0]) < 1e-6);
+
+        // an empty source leaves the destination alone.
+        transfer_attributes(&wedge::mesh::Mesh::<Vec3, (), ()>::new(), &mut dst, |_, p| *p = [0.0; 3]);
+        assert_eq!(dst.vertices()[VertexIndex(0)], [0.2, 0.3, 0.0]);
+    }
+}
@@ -2,6 +2,7 @@ extern crate wedge;
 
 #[cfg(test)]
 mod tests {
+    use wedge::index::{EdgeIndex, VertexIndex};
 
     #[test]
     fn test_new() {
@@ -10,22 +11,22 @@ mod tests {
 
     #[test]
     fn test_add_verts() {
-        let mut mesh: wedge::mesh::Mesh<(u32), (), ()> = wedge::mesh::Mesh::new();
+        let mut mesh: wedge::mesh::Mesh<u32, (), ()> = wedge::mesh::Mesh::new();
         mesh.add_vertex(5);
         mesh.add_vertex(11);
         mesh.add_vertex(15);
-        assert_eq!(*mesh.vertex(0).data().unwrap(), 5);
-        assert_eq!(*mesh.vertex(1).data().unwrap(), 11);
-        assert_eq!(*mesh.vertex(2).data().unwrap(), 15);
+        assert_eq!(*mesh.vertex(VertexIndex(0)).data().unwrap(), 5);
+        assert_eq!(*mesh.vertex(VertexIndex(1)).data().unwrap(), 11);
+        assert_eq!(*mesh.vertex(VertexIndex(2)).data().unwrap(), 15);
     }
 
     #[test]
     fn test_mesh_vert_iterator() {
-        let mut mesh: wedge::mesh::Mesh<(u32), (), ()> = wedge::mesh::Mesh::new();
+        let mut mesh: wedge::mesh::Mesh<u32, (), ()> = wedge::mesh::Mesh::new();
         mesh.add_vertex(5);
         mesh.add_vertex(11);
         mesh.add_vertex(15);
-        let expect = vec![5, 11, 15];
+        let expect = [5, 11, 15];
         let mut i = 0;
         for v in mesh.vertex_iter() {
             assert_eq!(*v.data().unwrap(), expect[i]);
@@ -36,7 +37,7 @@ mod tests {
 
     #[test]
     fn test_add_basic_edge() {
-        let mut mesh: wedge::mesh::Mesh<(u32), (f32), ()> = wedge::mesh::Mesh::new();
+        let mut mesh: wedge::mesh::Mesh<u32, f32, ()> = wedge::mesh::Mesh::new();
         let v1 = mesh.add_vertex(5);
         let v2 = mesh.add_vertex(11);
         let v3 = mesh.add_vertex(15);
@@ -45,7 +46,7 @@ mod tests {
         mesh.add_edge(3.1, v2, v3);
         mesh.add_edge(2.2, v3, v1);
         mesh.add_edge(1.1, v1, v4);
-        let expect = vec![5.5, 3.1, 2.2, 1.1];
+        let expect = [5.5, 3.1, 2.2, 1.1];
         let mut i = 0;
         for e in mesh.edge_iter() {
             assert_eq!(*e.data().unwrap(), expect[i]);
@@ -54,10 +55,966 @@ mod tests {
         assert_eq!(i, 4);
 
         // Check that v1 has the right edges.
-        let expect_2 = vec![0, 2, 3];
+        let expect_2 = [0, 2, 3];
         i = 0;
         for e in mesh.vertex(v1).edge_iter() {
-            assert_eq!(e.index(), expect_2[i]);
+            assert_eq!(e.index(), EdgeIndex(expect_2[i]));
+            i += 1;
+        }
+        assert_eq!(i, 3);
+
+        let neighbors: Vec<VertexIndex> = mesh.vertex(v1).vertex_iter().map(|v| v.index()).collect();
+        assert_eq!(neighbors, vec![v2, v3, v4]);
+        assert_eq!(mesh.vertex(v4).edge_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_edge_flags() {
+        use wedge::mesh::EdgeFlags;
+        let mut mesh: wedge::mesh::Mesh<(), (), ()> = wedge::mesh::Mesh::new();
+        let v1 = mesh.add_vertex(());
+        let v2 = mesh.add_vertex(());
+        let v3 = mesh.add_vertex(());
+        let e1 = mesh.add_edge((), v1, v2);
+        let e2 = mesh.add_edge((), v2, v3);
+        assert_eq!(mesh.edge(e1).flags(), EdgeFlags::NONE);
+        assert!(mesh.edges_with_flags(EdgeFlags::SEAM).is_empty());
+
+        mesh.insert_edge_flags(e1, EdgeFlags::SEAM | EdgeFlags::SHARP);
+        mesh.insert_edge_flags(e2, EdgeFlags::HIDDEN);
+        let e3 = mesh.add_edge((), v3, v1);
+        assert_eq!(mesh.edge(e3).flags(), EdgeFlags::NONE);
+        assert!(mesh.edge(e1).flags().contains(EdgeFlags::SHARP));
+        assert_eq!(mesh.edges_with_flags(EdgeFlags::SEAM | EdgeFlags::HIDDEN), vec![e1, e2]);
+
+        mesh.remove_edge_flags(e1, EdgeFlags::SEAM);
+        assert_eq!(mesh.edge(e1).flags(), EdgeFlags::SHARP);
+        assert!(mesh.edges_with_flags(EdgeFlags::SEAM).is_empty());
+    }
+
+    #[test]
+    fn test_unchecked_access() {
+        let mut mesh: wedge::mesh::Mesh<u32, f32, ()> = wedge::mesh::Mesh::new();
+        let v1 = mesh.add_vertex(5);
+        let v2 = mesh.add_vertex(11);
+        let e = mesh.add_edge(2.5, v1, v2);
+        let mut sum = 0;
+        for v in mesh.vertex_iter() {
+            sum += unsafe { *v.data_unchecked() };
+        }
+        assert_eq!(sum, 16);
+        unsafe {
+            *mesh.vertex_data_unchecked_mut(v2) = 12;
+            assert_eq!(*mesh.vertex_data_unchecked(v2), 12);
+            assert_eq!(*mesh.edge_data_unchecked(e), 2.5);
+            assert_eq!(*mesh.edge(e).data_unchecked(), 2.5);
+        }
+    }
+
+    #[test]
+    fn test_data_iterators() {
+        let mut mesh: wedge::mesh::Mesh<u32, f32, ()> = wedge::mesh::Mesh::new();
+        let v1 = mesh.add_vertex(5);
+        let v2 = mesh.add_vertex(11);
+        mesh.add_edge(2.5, v1, v2);
+        let verts: Vec<(VertexIndex, &u32)> = mesh.vertex_data_iter().collect();
+        assert_eq!(verts, vec![(v1, &5), (v2, &11)]);
+        let edges: Vec<(EdgeIndex, &f32)> = mesh.edge_data_iter().collect();
+        assert_eq!(edges, vec![(EdgeIndex(0), &2.5)]);
+        assert_eq!(mesh.face_data_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_iterator_traits() {
+        let mut mesh: wedge::mesh::Mesh<u32, (), ()> = wedge::mesh::Mesh::new();
+        let v1 = mesh.add_vertex(5);
+        let v2 = mesh.add_vertex(11);
+        let v3 = mesh.add_vertex(15);
+        mesh.add_edge((), v1, v2);
+        mesh.add_edge((), v2, v3);
+
+        let mut iter = mesh.vertex_iter();
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        assert_eq!(iter.len(), 2);
+        let reversed: Vec<u32> = mesh.vertex_iter().rev().map(|v| *v.data().unwrap()).collect();
+        assert_eq!(reversed, vec![15, 11, 5]);
+
+        let mut edges = mesh.edge_iter();
+        assert_eq!(edges.next_back().unwrap().index(), EdgeIndex(1));
+        assert_eq!(edges.next().unwrap().index(), EdgeIndex(0));
+        assert!(edges.next().is_none());
+        assert!(edges.next_back().is_none());
+        assert_eq!(mesh.face_iter().len(), 0);
+
+        let pairs: Vec<(u32, u32)> = mesh.vertex_iter().zip(mesh.edge_iter().rev())
+            .map(|(v, e)| (v.index().0, e.index().0))
+            .collect();
+        assert_eq!(pairs, vec![(0, 1), (1, 0)]);
+        assert_eq!(mesh.vertex_data_iter().next_back(), Some((v3, &15)));
+    }
+
+    #[test]
+    fn test_views() {
+        let mut mesh: wedge::mesh::Mesh<u32, f32, ()> = wedge::mesh::Mesh::new();
+        let v1 = mesh.add_vertex(5);
+        let v2 = mesh.add_vertex(11);
+        let e = mesh.add_edge(1.5, v1, v2);
+
+        let mut sum = 0;
+        for v in &mesh.vertices() {
+            sum += *v.data().unwrap();
+        }
+        assert_eq!(sum, 16);
+        assert_eq!(mesh.vertices()[v2], 11);
+        assert_eq!(mesh.edges()[e], 1.5);
+        assert_eq!(mesh.vertices().len(), 2);
+        assert!(mesh.faces().is_empty());
+        assert!(mesh.vertices().get(VertexIndex(2)).is_none());
+        assert_eq!(mesh.edges().into_iter().count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid vertex index 7")]
+    fn test_view_invalid_index() {
+        let mesh: wedge::mesh::Mesh<u32, f32, ()> = wedge::mesh::Mesh::new();
+        let _ = mesh.vertices()[VertexIndex(7)];
+    }
+
+    #[test]
+    fn test_mesh_index() {
+        let mut mesh: wedge::mesh::Mesh<u32, f32, char> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..3).map(|i| mesh.add_vertex(i)).collect();
+        let f = mesh.add_face_with('a', &v, |_, _| 0.5);
+        let e = mesh.find_edge(v[0], v[1]).unwrap();
+
+        mesh[v[1]] += 10;
+        mesh[e] *= 3.0;
+        mesh[f] = 'b';
+        assert_eq!(mesh[v[1]], 11);
+        assert_eq!(mesh[e], 1.5);
+        assert_eq!(mesh[f], 'b');
+        assert_eq!(*mesh.face(f).data(), 'b');
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid face index 2")]
+    fn test_mesh_index_invalid() {
+        let mut mesh: wedge::mesh::Mesh<u32, f32, char> = wedge::mesh::Mesh::new();
+        mesh[wedge::index::FaceIndex(2)] = 'a';
+    }
+
+    #[test]
+    fn test_topology_mesh() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v1 = mesh.add_default_vertex();
+        let v2 = mesh.add_default_vertex();
+        let e = mesh.add_default_edge(v1, v2);
+        assert_eq!(mesh.edge(e).vertices(), vec![v1, v2]);
+    }
+
+    #[test]
+    fn test_refs_without_clone_payloads() {
+        struct Blob(#[allow(dead_code)] Vec<u8>);
+        let mut mesh: wedge::mesh::Mesh<Blob, (), ()> = wedge::mesh::Mesh::new();
+        let v1 = mesh.add_vertex(Blob(vec![1]));
+        let v2 = mesh.add_vertex(Blob(vec![2]));
+        mesh.add_edge((), v1, v2);
+        let v = mesh.vertex(v1);
+        let copy = v;
+        assert_eq!(v.index(), copy.index());
+        let iter = mesh.vertex_iter();
+        assert_eq!(iter.clone().count(), 2);
+        assert_eq!(v.edge_iter().clone().count(), 1);
+        assert_eq!(mesh.vertex_data_iter().clone().count(), 2);
+    }
+
+    #[test]
+    fn test_map_data() {
+        let mut mesh: wedge::mesh::Mesh<[f32; 3], f32, ()> = wedge::mesh::Mesh::new();
+        let v1 = mesh.add_vertex([1.0, 2.0, 3.0]);
+        let v2 = mesh.add_vertex([4.0, 5.0, 6.0]);
+        let v3 = mesh.add_vertex([7.0, 8.0, 9.0]);
+        mesh.add_edge(0.5, v1, v2);
+        mesh.add_edge(1.5, v2, v3);
+
+        let mapped: wedge::mesh::Mesh<[f64; 3], (), ()> = mesh.map_data(
+            |p| [p[0] as f64, p[1] as f64, p[2] as f64], |_| (), |_| ());
+        assert_eq!(mapped.vertices()[v2], [4.0, 5.0, 6.0]);
+        assert_eq!(mapped.edge_iter().len(), 2);
+        let neighbors: Vec<VertexIndex> = mapped.vertex(v2).vertex_iter().map(|v| v.index()).collect();
+        assert_eq!(neighbors, vec![v1, v3]);
+    }
+
+    #[test]
+    fn test_zip_vertex_data() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v1 = mesh.add_default_vertex();
+        let v2 = mesh.add_default_vertex();
+        mesh.add_default_edge(v1, v2);
+        assert!(mesh.zip_vertex_data(&[1.0f32]).is_none());
+
+        let mut weights = [0.5f32, 2.0];
+        let zipped = mesh.zip_vertex_data(&weights).unwrap();
+        assert_eq!(zipped.len(), 2);
+        assert_eq!(zipped[v2], 2.0);
+        assert_eq!(zipped.get(v1).map(|(v, w)| (v.index(), *w)), Some((v1, 0.5)));
+        let total: f32 = zipped.iter().map(|(v, w)| *w * v.edge_iter().count() as f32).sum();
+        assert_eq!(total, 2.5);
+
+        for (v, w) in mesh.zip_vertex_data_mut(&mut weights).unwrap() {
+            *w += v.index().0 as f32;
+        }
+        assert_eq!(weights, [0.5, 3.0]);
+    }
+
+    #[test]
+    fn test_sort_vertices_by() {
+        let mut mesh: wedge::mesh::Mesh<u32, f32, ()> = wedge::mesh::Mesh::new();
+        let v1 = mesh.add_vertex(30);
+        let v2 = mesh.add_vertex(10);
+        let v3 = mesh.add_vertex(20);
+        let e1 = mesh.add_edge(1.0, v1, v2);
+        mesh.add_edge(2.0, v1, v3);
+
+        let remap = mesh.sort_vertices_by(|a, b| a.data().unwrap().cmp(b.data().unwrap()));
+        assert_eq!(remap, vec![VertexIndex(2), VertexIndex(0), VertexIndex(1)]);
+        let data: Vec<u32> = mesh.vertex_data_iter().map(|(_, d)| *d).collect();
+        assert_eq!(data, vec![10, 20, 30]);
+        assert_eq!(mesh.edge(e1).vertices(), vec![remap[v1.0 as usize], remap[v2.0 as usize]]);
+
+        let v = remap[v1.0 as usize];
+        let neighbors: Vec<u32> = mesh.vertex(v).vertex_iter().map(|n| *n.data().unwrap()).collect();
+        assert_eq!(neighbors, vec![10, 20]);
+        assert_eq!(mesh.sort_faces_by(|_, _| std::cmp::Ordering::Equal), vec![]);
+    }
+
+    #[test]
+    fn test_neighbor_order_independent_of_insertion() {
+        let points = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [-1.0, 0.0, 0.0], [0.0, -1.0, 0.0]];
+        let insertion_orders = [[1, 2, 3, 4], [4, 2, 1, 3], [3, 1, 4, 2]];
+        for order in insertion_orders.iter() {
+            let mut mesh: wedge::mesh::Mesh<[f32; 3], (), ()> = wedge::mesh::Mesh::new();
+            for p in points.iter() {
+                mesh.add_vertex(*p);
+            }
+            for i in order.iter() {
+                mesh.add_edge((), VertexIndex(0), VertexIndex(*i));
+            }
+            let center = mesh.vertex(VertexIndex(0));
+            let sorted: Vec<u32> = center.sorted_vertices().iter().map(|v| v.0).collect();
+            assert_eq!(sorted, vec![1, 2, 3, 4]);
+            let ccw: Vec<u32> = center.vertices_ccw([0.0, 0.0, 1.0]).iter().map(|v| v.0).collect();
+            assert_eq!(ccw, vec![1, 2, 3, 4]);
+            let cw: Vec<u32> = center.vertices_ccw([0.0, 0.0, -1.0]).iter().map(|v| v.0).collect();
+            assert_eq!(cw, vec![1, 4, 3, 2]);
+        }
+    }
+
+    #[test]
+    fn test_debug_json() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        assert_eq!(mesh.to_debug_json(), "{\n  \"vertices\": [],\n  \"edges\": [],\n  \"faces\": []\n}\n");
+
+        let v1 = mesh.add_default_vertex();
+        let v2 = mesh.add_default_vertex();
+        mesh.add_default_vertex();
+        let e = mesh.add_default_edge(v1, v2);
+        mesh.insert_edge_flags(e, wedge::mesh::EdgeFlags::SEAM);
+        let expect = r#"{
+  "vertices": [
+    {"base_edge": 0},
+    {"base_edge": 0},
+    {"base_edge": null}
+  ],
+  "edges": [
+    {"half_edges": [{"vertex": 0, "next_face": null, "next_edge": 0, "prev_edge": 0}, {"vertex": 1, "next_face": null, "next_edge": 0, "prev_edge": 0}], "flags": 1}
+  ],
+  "faces": []
+}
+"#;
+        assert_eq!(mesh.to_debug_json(), expect);
+    }
+
+    #[test]
+    fn test_set_base_edge() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let center = mesh.add_default_vertex();
+        let mut edges = Vec::new();
+        for _ in 0..3 {
+            let v = mesh.add_default_vertex();
+            edges.push(mesh.add_default_edge(center, v));
+        }
+        let first: Vec<EdgeIndex> = mesh.vertex(center).edge_iter().map(|e| e.index()).collect();
+        assert_eq!(first, edges);
+
+        mesh.set_base_edge(center, edges[1]);
+        let reanchored: Vec<EdgeIndex> = mesh.vertex(center).edge_iter().map(|e| e.index()).collect();
+        assert_eq!(reanchored, vec![edges[1], edges[2], edges[0]]);
+
+        // Every edge is a wire edge, so the base edge already qualifies.
+        assert!(mesh.edge(edges[1]).is_boundary());
+        assert!(mesh.anchor_to_boundary(center));
+        assert_eq!(mesh.vertex(center).edge_iter().next().unwrap().index(), edges[1]);
+        let isolated = mesh.add_default_vertex();
+        assert!(!mesh.anchor_to_boundary(isolated));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not incident")]
+    fn test_set_base_edge_not_incident() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v1 = mesh.add_default_vertex();
+        let v2 = mesh.add_default_vertex();
+        let v3 = mesh.add_default_vertex();
+        let e = mesh.add_default_edge(v1, v2);
+        mesh.set_base_edge(v3, e);
+    }
+
+    #[test]
+    fn test_clone_topology() {
+        struct Blob(#[allow(dead_code)] Vec<u8>);
+        let mut mesh: wedge::mesh::Mesh<Blob, (), ()> = wedge::mesh::Mesh::new();
+        let v1 = mesh.add_vertex(Blob(vec![0; 1024]));
+        let v2 = mesh.add_vertex(Blob(vec![0; 1024]));
+        let e = mesh.add_edge((), v1, v2);
+        mesh.insert_edge_flags(e, wedge::mesh::EdgeFlags::SHARP);
+
+        let topology = mesh.clone_topology();
+        assert_eq!(topology.to_debug_json(), mesh.to_debug_json());
+        assert!(topology.memory_usage() < mesh.memory_usage());
+    }
+
+    #[test]
+    fn test_checkpoint_region() {
+        use wedge::mesh::EdgeFlags;
+
+        // a path 0 - 1 - 2 and a loose vertex 3, far from 4 - 5.
+        let mut mesh: wedge::mesh::Mesh<u32, (), ()> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..6).map(|i| mesh.add_vertex(i)).collect();
+        mesh.add_edge((), v[0], v[1]);
+        mesh.add_edge((), v[1], v[2]);
+        mesh.add_edge((), v[4], v[5]);
+        let before = mesh.to_debug_json();
+
+        let checkpoint = mesh.checkpoint_region(&[v[1], v[3]]);
+        assert_eq!(checkpoint.vertex_count(), 4);
+        assert_eq!(checkpoint.edge_count(), 2);
+        let e = mesh.add_edge((), v[1], v[3]);
+        mesh.add_edge((), v[2], v[3]);
+        mesh.set_base_edge(v[1], e);
+        mesh.insert_edge_flags(EdgeIndex(0), EdgeFlags::SEAM);
+        *mesh.vertex_data_mut(v[1]).unwrap() = 10;
+        mesh.restore(checkpoint);
+
+        assert_eq!(mesh.to_debug_json(), before);
+        assert_eq!(mesh.vertices()[v[1]], 1);
+        assert_eq!(mesh.edge_iter().len(), 3);
+    }
+
+    #[test]
+    fn test_checkpoint_region_collapse() {
+        use wedge::mesh::EdgeFlags;
+
+        // a 4 x 4 grid of triangles; the collapsed edge is near the start, so
+        // the elements removal moves into its place lie outside the region.
+        let n = 5;
+        let mut mesh: wedge::mesh::Mesh<u32, u32, u32> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..n * n).map(|i| mesh.add_vertex(i as u32)).collect();
+        let mut count = 0;
+        for i in 0..n - 1 {
+            for j in 0..n - 1 {
+                let (a, b, c, d) = (v[i * n + j], v[i * n + j + 1], v[(i + 1) * n + j + 1], v[(i + 1) * n + j]);
+                for face in [[a, b, c], [a, c, d]] {
+                    mesh.add_face_with(count, &face, |_, _| { count += 1; count });
+                }
+            }
+        }
+        let last = EdgeIndex(mesh.edge_iter().len() as u32 - 1);
+        mesh.insert_edge_flags(last, EdgeFlags::SEAM);
+        let before = mesh.to_debug_json();
+        let payloads = |mesh: &wedge::mesh::Mesh<u32, u32, u32>| {
+            (mesh.vertex_iter().map(|v| *v.data().unwrap()).collect::<Vec<_>>(),
+             mesh.edge_iter().map(|e| *e.data().unwrap()).collect::<Vec<_>>(),
+             mesh.face_iter().map(|f| *f.data()).collect::<Vec<_>>())
+        };
+        let data = payloads(&mesh);
+
+        let (a, b) = (v[n + 1], v[n + 2]);
+        let checkpoint = mesh.checkpoint_region(&[a, b]);
+        let merged = mesh.collapse_edge(mesh.find_edge(a, b).unwrap());
+        mesh[merged] = 100;
+        assert_eq!(mesh.vertex_iter().len(), n * n - 1);
+        mesh.restore(checkpoint);
+
+        assert_consistent(&mesh);
+        assert_eq!(mesh.to_debug_json(), before);
+        assert_eq!(payloads(&mesh), data);
+        assert_eq!(mesh.edge_flags(last), EdgeFlags::SEAM);
+    }
+
+    // `a` starting from some element, the way a circulator may return it.
+    fn rotation_of(a: &[VertexIndex], b: &[VertexIndex]) -> bool {
+        a.len() == b.len() && (0..b.len()).any(|i| a.iter().zip(b.iter().cycle().skip(i)).all(|(x, y)| x == y))
+    }
+
+    #[test]
+    fn test_add_face() {
+        let mut mesh: wedge::mesh::Mesh<(), u32, char> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|_| mesh.add_vertex(())).collect();
+        let existing = mesh.add_edge(7, v[1], v[2]);
+        let f = mesh.add_face_with('a', &[v[0], v[1], v[2]], |_, _| Default::default());
+
+        assert_eq!(mesh.face_iter().len(), 1);
+        assert_eq!(mesh.edge_iter().len(), 3);
+        assert_eq!(*mesh.face(f).data(), 'a');
+        assert_eq!(mesh.face(f).index(), f);
+        assert_eq!(mesh.find_edge(v[2], v[1]), Some(existing));
+        assert_eq!(mesh.edges()[existing], 7);
+        let vertices: Vec<VertexIndex> = mesh.face(f).vertex_iter().map(|v| v.index()).collect();
+        assert_eq!(vertices, vec![v[0], v[1], v[2]]);
+        assert_eq!(mesh.face(f).edge_iter().count(), 3);
+        assert!(mesh.edge_iter().all(|e| e.faces() == vec![f] && e.is_boundary()));
+        assert_eq!(mesh.vertex(v[1]).face_iter().map(|f| f.index()).collect::<Vec<_>>(), vec![f]);
+        assert_eq!(mesh.vertex(v[3]).face_iter().count(), 0);
+
+        // the neighbour lists the shared edge the other way round.
+        let g = mesh.add_face_with('b', &[v[2], v[1], v[3]], |_, _| Default::default());
+        let shared = mesh.edge(existing);
+        assert_eq!(shared.faces().len(), 2);
+        assert!(!shared.is_boundary());
+        assert_eq!(mesh.edge_iter().len(), 5);
+        let vertices: Vec<VertexIndex> = mesh.face(g).vertex_iter().map(|v| v.index()).collect();
+        assert_eq!(vertices, vec![v[2], v[1], v[3]]);
+        assert_eq!(mesh.summary().boundary_loops, 1);
+        // circulators around a boundary vertex start on the boundary.
+        assert!(mesh.vertex(v[1]).edge_iter().next().unwrap().is_boundary());
+    }
+
+    #[test]
+    fn test_add_face_fan_order() {
+        // a closed fan of quads around vertex 0, added out of order over
+        // spokes that were added out of order.
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..9).map(|_| mesh.add_default_vertex()).collect();
+        let rim = [v[1], v[2], v[3], v[4], v[5], v[6], v[7], v[8]];
+        for i in [6, 2, 0, 4] {
+            mesh.add_default_edge(v[0], rim[i]);
+        }
+        for i in [1, 3, 0, 2] {
+            let (a, b, c) = (rim[2 * i], rim[2 * i + 1], rim[(2 * i + 2) % 8]);
+            mesh.add_default_face(&[v[0], a, b, c]);
         }
+
+        // each face's corner at the centre is a consecutive pair of spokes.
+        let spokes: Vec<VertexIndex> = mesh.vertex(v[0]).vertex_iter().map(|v| v.index()).collect();
+        assert!(rotation_of(&spokes, &[rim[0], rim[2], rim[4], rim[6]]));
+        assert_eq!(mesh.vertex(v[0]).face_iter().count(), 4);
+        for f in mesh.face_iter() {
+            assert_eq!(f.vertex_iter().count(), 4);
+            assert_eq!(f.vertex_iter().next().unwrap().index(), v[0]);
+        }
+        assert_eq!(mesh.summary().boundary_loops, 1);
+    }
+
+    #[test]
+    fn test_add_face_closed() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|_| mesh.add_default_vertex()).collect();
+        let faces = [[0, 2, 1], [0, 1, 3], [1, 2, 3], [0, 3, 2]];
+        for face in faces.iter() {
+            mesh.add_default_face(&[v[face[0]], v[face[1]], v[face[2]]]);
+        }
+
+        assert!(mesh.edge_iter().all(|e| !e.is_boundary()));
+        assert!(mesh.vertex_iter().all(|v| v.face_iter().count() == 3));
+        for (f, face) in mesh.face_iter().zip(faces.iter()) {
+            let vertices: Vec<VertexIndex> = f.vertex_iter().map(|v| v.index()).collect();
+            assert_eq!(vertices, face.iter().map(|i| v[*i]).collect::<Vec<_>>());
+        }
+        let summary = mesh.summary();
+        assert_eq!((summary.faces, summary.edges, summary.boundary_loops), (4, 6, 0));
+    }
+
+    #[test]
+    fn test_add_face_with() {
+        // an edge payload with no default.
+        #[derive(Debug, PartialEq)]
+        struct Side(VertexIndex, VertexIndex);
+        let mut mesh: wedge::mesh::Mesh<(), Side, ()> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|_| mesh.add_vertex(())).collect();
+        mesh.add_edge(Side(v[2], v[0]), v[2], v[0]);
+        mesh.add_face_with((), &[v[0], v[1], v[2]], Side);
+        assert_eq!(mesh.edge_iter().len(), 3);
+        for e in mesh.edge_iter() {
+            let ends = e.vertices();
+            let side = e.data().unwrap();
+            assert!(ends.contains(&side.0) && ends.contains(&side.1));
+        }
+        assert_eq!(mesh.edges()[mesh.find_edge(v[0], v[1]).unwrap()], Side(v[0], v[1]));
+
+        // add_face only joins up existing edges.
+        let f = mesh.add_face((), &[v[2], v[1], v[0]]);
+        assert_eq!(mesh.face(f).vertex_iter().count(), 3);
+        assert!(mesh.edge_iter().all(|e| !e.is_boundary()));
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no edge")]
+    fn test_add_face_missing_edge() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..3).map(|_| mesh.add_default_vertex()).collect();
+        mesh.add_default_edge(v[0], v[1]);
+        mesh.add_face((), &v);
+    }
+
+    #[test]
+    fn test_can_add_face() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|_| mesh.add_default_vertex()).collect();
+        assert!(mesh.can_add_face(&v[..3]));
+        mesh.add_default_face(&v[..3]);
+        assert!(!mesh.can_add_face(&v[..3]), "each side already has a face");
+        assert!(!mesh.can_add_face(&[v[0], v[1], v[3]]), "0 -> 1 already has a face");
+        assert!(mesh.can_add_face(&[v[1], v[0], v[3]]));
+        assert!(!mesh.can_add_face(&[v[1], v[0]]), "too few vertices");
+        assert!(!mesh.can_add_face(&[v[1], v[0], v[1]]), "repeated vertex");
+        assert!(!mesh.can_add_face(&[v[1], v[0], VertexIndex(9)]), "invalid vertex");
+    }
+
+    #[test]
+    fn test_add_face_oriented() {
+        use wedge::mesh::{WindingConflict, WindingPolicy};
+
+        // 0 -> 1 -> 2 is there; 1 -> 2 -> 3 runs along 1 -> 2 the same way.
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..5).map(|_| mesh.add_default_vertex()).collect();
+        mesh.add_default_face(&v[..3]);
+        let backwards = [v[1], v[2], v[3]];
+
+        let mut refused = mesh.clone();
+        let result = refused.add_face_oriented((), &backwards, WindingPolicy::Error, |_, _| ());
+        assert_eq!(result, Err(WindingConflict { from: v[1], to: v[2] }));
+        assert_eq!(result.unwrap_err().to_string(), "edge 1 -> 2 already has a face on that side");
+        assert_eq!((refused.edge_iter().len(), refused.face_iter().len()), (3, 1));
+
+        let mut flipped = mesh.clone();
+        let f = flipped.add_face_oriented((), &backwards, WindingPolicy::Flip, |_, _| ()).unwrap();
+        assert_consistent(&flipped);
+        assert_eq!(flipped.face(f).vertex_iter().map(|v| v.index()).collect::<Vec<_>>(), vec![v[3], v[2], v[1]]);
+        assert_eq!(flipped.edge_iter().len(), 5);
+        assert_eq!(flipped.edge(flipped.find_edge(v[1], v[2]).unwrap()).faces().len(), 2);
+        // faces that already agree are added as given.
+        let g = flipped.add_face_oriented((), &[v[3], v[1], v[4]], WindingPolicy::Flip, |_, _| ()).unwrap();
+        assert_eq!(flipped.face(g).vertex_iter().next().unwrap().index(), v[3]);
+        assert_eq!(flipped.summary().boundary_loops, 1);
+
+        let mut accepted = mesh.clone();
+        accepted.add_face_oriented((), &backwards, WindingPolicy::Accept, |_, _| ()).unwrap();
+        assert_consistent(&accepted);
+        assert_eq!(accepted.edge_iter().len(), 6);
+        let seam: Vec<_> = accepted.vertex(v[1]).edge_iter().filter(|e| e.vertices().contains(&v[2])).collect();
+        assert_eq!(seam.len(), 2);
+        assert!(seam.iter().all(|e| e.faces().len() == 1));
+        assert_eq!(accepted.face_iter().len(), 2);
+
+        // a quad that disagrees with 0 -> 1 -> 2 one way round and with
+        // 1 -> 0 -> 3 the other can't be flipped into place.
+        mesh.add_default_face(&[v[1], v[0], v[3]]);
+        let twisted = [v[1], v[2], v[3], v[0]];
+        assert_eq!(mesh.add_face_oriented((), &twisted, WindingPolicy::Flip, |_, _| ()),
+                   Err(WindingConflict { from: v[1], to: v[2] }));
+        assert!(!mesh.can_add_face(&twisted));
+        assert!(!mesh.can_add_face(&twisted.iter().rev().cloned().collect::<Vec<_>>()));
+        assert!(mesh.add_face_oriented((), &twisted, WindingPolicy::Accept, |_, _| ()).is_ok());
+        assert_consistent(&mesh);
+    }
+
+    #[test]
+    fn test_add_default_face() {
+        let mut mesh: wedge::mesh::Mesh<(), u32, u32> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..3).map(|_| mesh.add_default_vertex()).collect();
+        let f = mesh.add_default_face(&v);
+        assert_eq!(*mesh.face(f).data(), 0);
+        assert!(mesh.edge_iter().all(|e| e.data() == Some(&0)));
+        assert_eq!(mesh.face(f).vertex_iter().map(|v| v.index()).collect::<Vec<_>>(), v);
+    }
+
+    #[test]
+    #[should_panic(expected = "already has a face")]
+    fn test_add_face_same_side_twice() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|_| mesh.add_default_vertex()).collect();
+        mesh.add_default_face(&[v[0], v[1], v[2]]);
+        mesh.add_default_face(&[v[0], v[1], v[3]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 3 vertices")]
+    fn test_add_face_too_small() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..2).map(|_| mesh.add_default_vertex()).collect();
+        mesh.add_default_face(&v);
+    }
+
+    // Every edge is in the rings of both its endpoints and nowhere else, and
+    // every face's edges know about it.
+    fn assert_consistent<V, E, F>(mesh: &wedge::mesh::Mesh<V, E, F>) {
+        let valences: usize = mesh.vertex_iter().map(|v| v.edge_iter().count()).sum();
+        assert_eq!(valences, 2 * mesh.edge_iter().len());
+        for e in mesh.edge_iter() {
+            for v in e.vertices() {
+                assert_eq!(mesh.vertex(v).edge_iter().filter(|x| x.index() == e.index()).count(), 1);
+            }
+            assert!(e.faces().iter().all(|f| mesh.is_valid_face_index(*f)));
+        }
+        for f in mesh.face_iter() {
+            assert!(f.vertex_iter().count() >= 3);
+            assert!(f.edge_iter().all(|e| e.faces().contains(&f.index())));
+        }
+    }
+
+    #[test]
+    fn test_remove_vertex() {
+        // a closed fan of triangles around vertex 0, whose last rim vertex
+        // moves into the freed index.
+        let mut mesh: wedge::mesh::Mesh<u32, (), ()> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..5).map(|i| mesh.add_vertex(i)).collect();
+        for i in 0..4 {
+            mesh.add_default_face(&[v[0], v[1 + i], v[1 + (i + 1) % 4]]);
+        }
+        mesh.insert_edge_flags(EdgeIndex(7), wedge::mesh::EdgeFlags::SEAM);
+        assert_eq!(mesh.remove_vertex(v[0]), Some(v[4]));
+
+        assert_consistent(&mesh);
+        assert_eq!(mesh.vertex_iter().len(), 4);
+        assert_eq!(mesh.face_iter().len(), 0);
+        assert_eq!(mesh.edge_iter().len(), 4);
+        assert_eq!(mesh.vertices()[v[0]], 4);
+        assert!(mesh.vertex_iter().all(|v| v.edge_iter().count() == 2));
+        assert_eq!(mesh.edges_with_flags(wedge::mesh::EdgeFlags::SEAM).len(), 1);
+        assert_eq!(mesh.summary().wire_edges, 4);
+    }
+
+    #[test]
+    fn test_remove_vertex_followers() {
+        use wedge::selection::VertexSelection;
+        use wedge::spatial::SpatialHash;
+
+        // a path of 5 vertices along x; removing the middle one moves the
+        // last into its index, and what indexes vertices follows it there.
+        let mut mesh: wedge::mesh::Mesh<[f32; 3], (), ()> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..5).map(|i| mesh.add_vertex([i as f32, 0.0, 0.0])).collect();
+        for i in 1..5 {
+            mesh.add_default_edge(v[i - 1], v[i]);
+        }
+        let mut hash = SpatialHash::from_mesh(&mesh, 1.0);
+        let mut selection = VertexSelection::from_slice(&[v[1], v[2], v[4]]);
+        let mut weights: Vec<f32> = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+
+        let moved = mesh.remove_vertex(v[2]);
+        assert_eq!(moved, Some(v[4]));
+        hash.swap_remove(v[2], moved);
+        selection.swap_remove(v[2], moved);
+        weights.swap_remove(v[2].0 as usize);
+
+        assert_eq!(mesh.vertices()[v[2]], [4.0, 0.0, 0.0]);
+        assert_eq!(hash.len(), 4);
+        assert_eq!(hash.nearest([4.0, 0.0, 0.0], 0.5), Some(v[2]));
+        assert_eq!(hash.position(v[4]), None);
+        assert_eq!(selection.to_vec(), vec![v[1], v[2]]);
+        for (i, p) in mesh.vertex_data_iter() {
+            assert_eq!(p[0], weights[i.0 as usize]);
+        }
+
+        // removing the last vertex moves nothing.
+        let moved = mesh.remove_vertex(v[3]);
+        assert_eq!(moved, None);
+        hash.swap_remove(v[3], moved);
+        selection.swap_remove(v[3], moved);
+        assert_eq!(hash.len(), 3);
+        assert_eq!(selection.to_vec(), vec![v[1], v[2]]);
+    }
+
+    #[test]
+    fn test_remove_vertex_closed() {
+        let mut mesh: wedge::mesh::Mesh<u32, (), char> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|i| mesh.add_vertex(i)).collect();
+        mesh.add_face_with('a', &[v[0], v[2], v[1]], |_, _| Default::default());
+        mesh.add_face_with('b', &[v[0], v[1], v[3]], |_, _| Default::default());
+        mesh.add_face_with('c', &[v[1], v[2], v[3]], |_, _| Default::default());
+        mesh.add_face_with('d', &[v[0], v[3], v[2]], |_, _| Default::default());
+        assert_eq!(mesh.remove_vertex(v[3]), None);
+
+        assert_consistent(&mesh);
+        assert_eq!(mesh.face_iter().len(), 1);
+        assert_eq!(*mesh.face(wedge::index::FaceIndex(0)).data(), 'a');
+        assert_eq!(mesh.edge_iter().len(), 3);
+        assert_eq!(mesh.summary().boundary_loops, 1);
+        for v in mesh.vertex_iter() {
+            assert!(v.edge_iter().next().unwrap().is_boundary());
+        }
+
+        // then the rest, including the last and a lone vertex.
+        assert_eq!(mesh.remove_vertex(v[2]), None);
+        assert_consistent(&mesh);
+        assert_eq!((mesh.edge_iter().len(), mesh.face_iter().len()), (1, 0));
+        assert_eq!(mesh.remove_vertex(v[0]), Some(v[1]));
+        assert_eq!(mesh.remove_vertex(v[0]), None);
+        assert_eq!(mesh.vertex_iter().len(), 0);
+        assert_eq!(mesh.edge_iter().len(), 0);
+    }
+
+    #[test]
+    fn test_remove_face() {
+        // two triangles sharing the edge 1 - 2.
+        let mut mesh: wedge::mesh::Mesh<(), (), char> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|_| mesh.add_vertex(())).collect();
+        let f = mesh.add_face_with('a', &[v[0], v[1], v[2]], |_, _| Default::default());
+        mesh.add_face_with('b', &[v[2], v[1], v[3]], |_, _| Default::default());
+        let shared = mesh.find_edge(v[1], v[2]).unwrap();
+
+        // the last face moves into the hole.
+        let mut holed = mesh.clone();
+        holed.remove_face(f, false);
+        assert_consistent(&holed);
+        assert_eq!(holed.face_iter().len(), 1);
+        assert_eq!(*holed.face(f).data(), 'b');
+        assert_eq!(holed.edge_iter().len(), 5);
+        assert_eq!(holed.edge(shared).faces(), vec![f]);
+        assert_eq!(holed.summary().wire_edges, 2);
+        assert_eq!(holed.vertex(v[0]).face_iter().count(), 0);
+
+        mesh.remove_face(f, true);
+        assert_consistent(&mesh);
+        assert_eq!(mesh.edge_iter().len(), 3);
+        assert_eq!(mesh.summary().wire_edges, 0);
+        assert_eq!(mesh.vertex(v[0]).edge_iter().count(), 0);
+        let vertices: Vec<VertexIndex> = mesh.face(f).vertex_iter().map(|v| v.index()).collect();
+        assert_eq!(vertices, vec![v[2], v[1], v[3]]);
+
+        mesh.remove_face(f, true);
+        assert_eq!((mesh.vertex_iter().len(), mesh.edge_iter().len(), mesh.face_iter().len()), (4, 0, 0));
+    }
+
+    #[test]
+    fn test_collapse_edge() {
+        // an open fan of triangles around vertex 0; collapsing 0 - 1 drops
+        // the two triangles on it and merges 1's rim edges into 0's spokes.
+        let mut mesh: wedge::mesh::Mesh<u32, (), char> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..5).map(|i| mesh.add_vertex(i)).collect();
+        for i in 0..4 {
+            mesh.add_face_with((b'a' + i as u8) as char, &[v[0], v[1 + i], v[1 + (i + 1) % 4]], |_, _| Default::default());
+        }
+        let spoke = mesh.find_edge(v[0], v[1]).unwrap();
+        let rim = mesh.find_edge(v[1], v[2]).unwrap();
+        mesh.insert_edge_flags(rim, wedge::mesh::EdgeFlags::SEAM);
+        let merged = mesh.collapse_edge(spoke);
+
+        assert_consistent(&mesh);
+        assert_eq!(merged, v[0]);
+        assert_eq!(mesh.vertices()[merged], 0);
+        // the last vertex moved into the removed one's index.
+        assert_eq!(mesh.vertices()[v[1]], 4);
+        assert_eq!((mesh.vertex_iter().len(), mesh.edge_iter().len(), mesh.face_iter().len()), (4, 5, 2));
+        let mut remaining: Vec<char> = mesh.face_iter().map(|f| *f.data()).collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!['b', 'c']);
+        assert_eq!(mesh.vertex(merged).face_iter().count(), 2);
+        let seams = mesh.edges_with_flags(wedge::mesh::EdgeFlags::SEAM);
+        assert_eq!(seams.len(), 1);
+        let mut ends = mesh.edge(seams[0]).vertices().to_vec();
+        ends.sort();
+        assert_eq!(ends, vec![v[0], v[2]]);
+        assert_eq!(mesh.summary().boundary_loops, 1);
+    }
+
+    #[test]
+    fn test_collapse_edge_closed() {
+        // a tetrahedron flattens into two triangles back to back.
+        let mut mesh: wedge::mesh::Mesh<u32, (), ()> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|i| mesh.add_vertex(i)).collect();
+        for face in [[0, 2, 1], [0, 1, 3], [1, 2, 3], [0, 3, 2]].iter() {
+            mesh.add_default_face(&[v[face[0]], v[face[1]], v[face[2]]]);
+        }
+        let e = mesh.find_edge(v[0], v[1]).unwrap();
+        let kept = mesh.vertices()[mesh.edge(e).vertices()[0]];
+        let merged = mesh.collapse_edge(e);
+
+        assert_consistent(&mesh);
+        assert_eq!(mesh.vertices()[merged], kept);
+        assert_eq!((mesh.vertex_iter().len(), mesh.edge_iter().len(), mesh.face_iter().len()), (3, 3, 2));
+        assert!(mesh.edge_iter().all(|e| !e.is_boundary()));
+    }
+
+    #[test]
+    fn test_collapse_edge_quad() {
+        // a quad loses a side instead of disappearing.
+        let mut mesh: wedge::mesh::Mesh<u32, (), ()> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|i| mesh.add_vertex(i)).collect();
+        let f = mesh.add_default_face(&v);
+        let e = mesh.find_edge(v[2], v[3]).unwrap();
+        let merged = mesh.collapse_edge(e);
+
+        assert_consistent(&mesh);
+        assert_eq!(merged, v[2]);
+        assert_eq!((mesh.vertex_iter().len(), mesh.edge_iter().len(), mesh.face_iter().len()), (3, 3, 1));
+        let vertices: Vec<VertexIndex> = mesh.face(f).vertex_iter().map(|v| v.index()).collect();
+        assert_eq!(vertices.len(), 3);
+        assert!(vertices.contains(&v[0]) && vertices.contains(&v[1]) && vertices.contains(&v[2]));
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't collapsible")]
+    fn test_collapse_edge_quad_diagonal() {
+        // 0 and 2 are opposite corners of the quad, which collapsing the
+        // triangle's edge between them would pinch into two faces.
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|_| mesh.add_default_vertex()).collect();
+        mesh.add_default_face(&[v[2], v[3], v[0], v[1]]);
+        mesh.add_default_face(&[v[2], v[1], v[0]]);
+        let e = mesh.find_edge(v[0], v[2]).unwrap();
+        assert!(!mesh.is_collapsible(e));
+        mesh.collapse_edge(e);
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't collapsible")]
+    fn test_collapse_edge_not_collapsible() {
+        // 0 and 1 both have a triangle on the same side of their edge to 2.
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..5).map(|_| mesh.add_default_vertex()).collect();
+        mesh.add_default_face(&[v[1], v[2], v[3]]);
+        mesh.add_default_face(&[v[0], v[2], v[4]]);
+        let e = mesh.add_edge((), v[0], v[1]);
+        assert!(!mesh.is_collapsible(e));
+        mesh.collapse_edge(e);
+    }
+
+    #[test]
+    fn test_flip_edge() {
+        // a closed fan of triangles around vertex 0 with the rim
+        // counterclockwise; flipping the spoke to rim[0] joins its neighbours.
+        let mut mesh: wedge::mesh::Mesh<(), u32, char> = wedge::mesh::Mesh::new();
+        let v: Vec<VertexIndex> = (0..7).map(|_| mesh.add_vertex(())).collect();
+        let rim = &v[1..];
+        let e = mesh.add_edge(7, v[0], rim[0]);
+        for i in 0..6 {
+            mesh.add_face_with((b'a' + i as u8) as char, &[v[0], rim[i], rim[(i + 1) % 6]], |_, _| Default::default());
+        }
+        assert!(mesh.is_flippable(e));
+        mesh.flip_edge(e);
+
+        assert_consistent(&mesh);
+        assert_eq!(mesh.find_edge(rim[5], rim[1]), Some(e));
+        assert_eq!(mesh.find_edge(v[0], rim[0]), None);
+        assert_eq!(mesh.edge(e).data(), Some(&7));
+        assert_eq!((mesh.edge_iter().len(), mesh.face_iter().len()), (12, 6));
+        let spokes: Vec<VertexIndex> = mesh.vertex(v[0]).vertex_iter().map(|v| v.index()).collect();
+        assert!(rotation_of(&spokes, &rim[1..]));
+        let around: Vec<VertexIndex> = mesh.vertex(rim[1]).vertex_iter().map(|v| v.index()).collect();
+        assert!(rotation_of(&around, &[rim[2], v[0], rim[5], rim[0]]));
+        assert_eq!(mesh.vertex(v[0]).face_iter().count(), 5);
+        assert_eq!(mesh.vertex(rim[0]).face_iter().count(), 1);
+        for f in mesh.face_iter() {
+            assert_eq!(f.vertex_iter().count(), 3);
+        }
+        let faces = mesh.edge(e).faces();
+        let corners: Vec<Vec<VertexIndex>> = faces.iter().map(|f| mesh.face(*f).vertex_iter().map(|v| v.index()).collect()).collect();
+        assert!(corners.iter().any(|c| rotation_of(c, &[rim[0], rim[1], rim[5]])));
+        assert!(corners.iter().any(|c| rotation_of(c, &[v[0], rim[5], rim[1]])));
+
+        // flipping back restores the fan.
+        assert!(mesh.is_flippable(e));
+        mesh.flip_edge(e);
+        assert_consistent(&mesh);
+        assert_eq!(mesh.vertex(v[0]).face_iter().count(), 6);
+        let spokes: Vec<VertexIndex> = mesh.vertex(v[0]).vertex_iter().map(|v| v.index()).collect();
+        assert!(rotation_of(&spokes, rim));
+    }
+
+    #[test]
+    fn test_flip_edge_not_flippable() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|_| mesh.add_default_vertex()).collect();
+        for face in [[0, 2, 1], [0, 1, 3], [1, 2, 3], [0, 3, 2]].iter() {
+            mesh.add_default_face(&[v[face[0]], v[face[1]], v[face[2]]]);
+        }
+        // the far corners of every tetrahedron edge are joined already.
+        assert!(mesh.edge_iter().all(|e| !mesh.is_flippable(e.index())));
+        mesh.remove_face(wedge::index::FaceIndex(3), false);
+        // boundary edges have one triangle.
+        let boundary = mesh.edge_iter().find(|e| e.is_boundary()).unwrap().index();
+        assert!(!mesh.is_flippable(boundary));
+    }
+
+    #[test]
+    #[should_panic(expected = "can be flipped")]
+    fn test_flip_edge_quad() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..5).map(|_| mesh.add_default_vertex()).collect();
+        mesh.add_default_face(&[v[0], v[1], v[2], v[3]]);
+        mesh.add_default_face(&[v[1], v[0], v[4]]);
+        let e = mesh.find_edge(v[0], v[1]).unwrap();
+        mesh.flip_edge(e);
+    }
+
+    #[test]
+    fn test_boundary_loop() {
+        // a ring of four quads between an outer and an inner square, both
+        // counterclockwise, with a wire edge poking into the hole.
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let outer: Vec<VertexIndex> = (0..4).map(|_| mesh.add_default_vertex()).collect();
+        let inner: Vec<VertexIndex> = (0..4).map(|_| mesh.add_default_vertex()).collect();
+        for k in 0..4 {
+            let l = (k + 1) % 4;
+            mesh.add_default_face(&[outer[k], outer[l], inner[l], inner[k]]);
+        }
+        let tip = mesh.add_default_vertex();
+        let wire = mesh.add_edge((), inner[0], tip);
+        assert_eq!(mesh.boundary_half_edge(wire), None);
+        assert_eq!(mesh.boundary_half_edge(mesh.find_edge(outer[0], inner[0]).unwrap()), None);
+
+        // the hole is walked in its own winding from any of its edges.
+        for k in 0..4 {
+            let e = mesh.find_edge(inner[k], inner[(k + 1) % 4]).unwrap();
+            let h = mesh.boundary_half_edge(e).unwrap();
+            assert_eq!((h.from, h.to), (inner[k], inner[(k + 1) % 4]));
+            let walk: Vec<VertexIndex> = mesh.boundary_loop(e).map(|h| h.from).collect();
+            let expect: Vec<VertexIndex> = (0..4).map(|i| inner[(k + i) % 4]).collect();
+            assert_eq!(walk, expect);
+        }
+        let e = mesh.find_edge(outer[1], outer[0]).unwrap();
+        let walk: Vec<VertexIndex> = mesh.boundary_loop(e).map(|h| h.from).collect();
+        assert!(rotation_of(&walk, &[outer[3], outer[2], outer[1], outer[0]]));
+        let loops = mesh.boundary_loops();
+        assert_eq!(loops.len(), 2);
+        assert!(loops.iter().all(|l| l.len() == 4));
+        for l in loops.iter() {
+            for (h, next) in l.iter().zip(l.iter().cycle().skip(1)) {
+                assert_eq!(h.to, next.from);
+                assert_eq!(mesh.next_boundary_half_edge(*h), *next);
+            }
+        }
+
+        // the walks fill both holes.
+        for l in loops.iter() {
+            let vertices: Vec<VertexIndex> = l.iter().map(|h| h.from).collect();
+            mesh.add_default_face(&vertices);
+        }
+        assert_consistent(&mesh);
+        assert!(mesh.boundary_loops().is_empty());
+        assert_eq!(mesh.summary().boundary_loops, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't have exactly one face")]
+    fn test_boundary_loop_interior_edge() {
+        let mut mesh = wedge::mesh::TopologyMesh::new();
+        let v: Vec<VertexIndex> = (0..4).map(|_| mesh.add_default_vertex()).collect();
+        mesh.add_default_face(&[v[0], v[1], v[2]]);
+        mesh.add_default_face(&[v[0], v[2], v[3]]);
+        let e = mesh.find_edge(v[0], v[2]).unwrap();
+        mesh.boundary_loop(e);
     }
 }